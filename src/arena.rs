@@ -0,0 +1,411 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use bevy_app::{App, Plugin};
+use bevy_ecs::{
+    system::{Command, NonSendMut, Resource, SystemParam},
+    world::{Mut, World},
+};
+
+use crate::main_thread;
+
+/// A generational key addressing a value in a [`NonSendArena<T>`].
+///
+/// Keys are [`Send`] and [`Sync`] even though `T` is not, so they can be stored in components and used from any thread. A key is invalidated when its value is removed, and is never reused for another value.
+pub struct ArenaKey<T> {
+    index: u32,
+    generation: u32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> ArenaKey<T> {
+    fn new(index: u32, generation: u32) -> Self {
+        ArenaKey {
+            index,
+            generation,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for ArenaKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArenaKey<T> {}
+
+impl<T> PartialEq for ArenaKey<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for ArenaKey<T> {}
+
+impl<T> Hash for ArenaKey<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for ArenaKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ArenaKey({}v{})", self.index, self.generation)
+    }
+}
+
+/// Hands out keys, shared between a [`NonSendArena`] and its [`NonSendArenaKeys`].
+#[derive(Default)]
+struct KeyAllocator {
+    generations: Vec<u32>,
+    free: Vec<u32>,
+}
+
+impl KeyAllocator {
+    fn reserve<T>(&mut self) -> ArenaKey<T> {
+        match self.free.pop() {
+            Some(index) => ArenaKey::new(index, self.generations[index as usize]),
+            None => {
+                let index = u32::try_from(self.generations.len())
+                    .expect("`NonSendArena` cannot hold more than `u32::MAX` values.");
+
+                self.generations.push(0);
+                ArenaKey::new(index, 0)
+            }
+        }
+    }
+
+    fn contains<T>(&self, key: ArenaKey<T>) -> bool {
+        self.generations.get(key.index as usize) == Some(&key.generation)
+    }
+
+    /// Invalidates `key`, returning false if it already was.
+    fn free<T>(&mut self, key: ArenaKey<T>) -> bool {
+        if !self.contains(key) {
+            return false;
+        }
+
+        let generation = &mut self.generations[key.index as usize];
+        *generation += 1;
+
+        // Retire the index instead of letting its generation wrap around and revive old keys.
+        if *generation != u32::MAX {
+            self.free.push(key.index);
+        }
+
+        true
+    }
+}
+
+type SharedAllocator = Arc<Mutex<KeyAllocator>>;
+
+fn lock(allocator: &SharedAllocator) -> MutexGuard<'_, KeyAllocator> {
+    allocator
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// A non-[`Send`] resource holding many values of the same non-[`Send`] type `T`, addressed by [`ArenaKey`]s.
+///
+/// This is meant for when there are too many native objects, such as textures, to store each in its own resource. The arena and its [`NonSendArenaKeys<T>`] are inserted by [`NonSendArenaPlugin<T>`].
+///
+/// Values can be inserted directly on the main thread with [`insert`](Self::insert), or from any system by reserving a key with [`NonSendArenaKeys::reserve`] and queueing [`insert_into_non_send_arena`]. They are accessed on the main thread with [`NonSendArenaMut`], or `NonSend<NonSendArena<T>>`.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{
+/// #     insert_into_non_send_arena, remove_from_non_send_arena, ArenaKey, NonSendArenaKeys,
+/// #     NonSendArenaMut, NonSendArenaPlugin,
+/// # };
+/// #
+/// struct Texture {
+///     width: u32,
+///     _handle: *const u8,
+/// }
+///
+/// #[derive(Component)]
+/// struct Sprite(ArenaKey<Texture>);
+///
+/// fn load_sprites(mut commands: Commands, keys: Res<NonSendArenaKeys<Texture>>) {
+///     for width in [16, 32, 64] {
+///         let key = keys.reserve();
+///
+///         commands.add(insert_into_non_send_arena(key, move || Texture {
+///             width,
+///             _handle: std::ptr::null(),
+///         }));
+///
+///         commands.spawn(Sprite(key));
+///     }
+/// }
+///
+/// fn draw_sprites(sprites: Query<&Sprite>, textures: NonSendArenaMut<Texture>) {
+///     for sprite in &sprites {
+///         let texture = textures.get(sprite.0).unwrap();
+///         println!("Drawing {} pixel wide texture.", texture.width);
+///     }
+/// }
+///
+/// fn unload_sprites(mut commands: Commands, sprites: Query<(Entity, &Sprite)>) {
+///     for (entity, sprite) in &sprites {
+///         commands.add(remove_from_non_send_arena(sprite.0));
+///         commands.entity(entity).despawn();
+///     }
+/// }
+/// #
+/// # App::new()
+/// #     .add_plugins(NonSendArenaPlugin::<Texture>::default())
+/// #     .add_systems(Startup, (load_sprites, draw_sprites, check_loaded, unload_sprites, check_unloaded).chain())
+/// #     .run();
+/// #
+/// # fn check_loaded(textures: NonSendArenaMut<Texture>) {
+/// #     assert_eq!(textures.len(), 3);
+/// # }
+/// #
+/// # fn check_unloaded(textures: NonSendArenaMut<Texture>) {
+/// #     assert!(textures.is_empty());
+/// # }
+/// ```
+pub struct NonSendArena<T> {
+    slots: Vec<Option<(u32, T)>>,
+    len: usize,
+    allocator: SharedAllocator,
+}
+
+impl<T> Default for NonSendArena<T> {
+    fn default() -> Self {
+        NonSendArena {
+            slots: Vec::new(),
+            len: 0,
+            allocator: SharedAllocator::default(),
+        }
+    }
+}
+
+impl<T> NonSendArena<T> {
+    /// Inserts `value`, returning its key.
+    pub fn insert(&mut self, value: T) -> ArenaKey<T> {
+        let key = lock(&self.allocator).reserve();
+        self.put(key, value);
+        key
+    }
+
+    /// Inserts `value` with a key reserved by [`NonSendArenaKeys::reserve`].
+    ///
+    /// Returns `value` back if the key was already removed.
+    pub fn insert_reserved(&mut self, key: ArenaKey<T>, value: T) -> Result<(), T> {
+        if !lock(&self.allocator).contains(key) {
+            return Err(value);
+        }
+
+        self.put(key, value);
+        Ok(())
+    }
+
+    fn put(&mut self, key: ArenaKey<T>, value: T) {
+        let index = key.index as usize;
+
+        if index >= self.slots.len() {
+            self.slots.resize_with(index + 1, || None);
+        }
+
+        if self.slots[index].replace((key.generation, value)).is_none() {
+            self.len += 1;
+        }
+    }
+
+    /// Removes the value with the given key, returning it.
+    ///
+    /// The key is invalidated even if its value was not inserted yet.
+    pub fn remove(&mut self, key: ArenaKey<T>) -> Option<T> {
+        if !lock(&self.allocator).free(key) {
+            return None;
+        }
+
+        let slot = self.slots.get_mut(key.index as usize)?;
+
+        match slot.take() {
+            Some((generation, value)) if generation == key.generation => {
+                self.len -= 1;
+                Some(value)
+            }
+            other => {
+                *slot = other;
+                None
+            }
+        }
+    }
+
+    /// Returns a reference to the value with the given key.
+    pub fn get(&self, key: ArenaKey<T>) -> Option<&T> {
+        match self.slots.get(key.index as usize)? {
+            Some((generation, value)) if *generation == key.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value with the given key.
+    pub fn get_mut(&mut self, key: ArenaKey<T>) -> Option<&mut T> {
+        match self.slots.get_mut(key.index as usize)? {
+            Some((generation, value)) if *generation == key.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns true if a value with the given key exists.
+    pub fn contains(&self, key: ArenaKey<T>) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the number of values in the arena.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the arena contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over all keys and values in the arena.
+    pub fn iter(&self) -> impl Iterator<Item = (ArenaKey<T>, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let (generation, value) = slot.as_ref()?;
+            Some((ArenaKey::new(index as u32, *generation), value))
+        })
+    }
+
+    /// Iterates mutably over all keys and values in the arena.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (ArenaKey<T>, &mut T)> {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                let (generation, value) = slot.as_mut()?;
+                Some((ArenaKey::new(index as u32, *generation), value))
+            })
+    }
+}
+
+/// A [`Resource`] used to reserve [`ArenaKey`]s for a [`NonSendArena<T>`] from any thread.
+///
+/// See [`insert_into_non_send_arena`].
+#[derive(Resource)]
+pub struct NonSendArenaKeys<T> {
+    allocator: SharedAllocator,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> NonSendArenaKeys<T> {
+    /// Reserves a new key, whose value can be inserted later with [`insert_into_non_send_arena`].
+    pub fn reserve(&self) -> ArenaKey<T> {
+        lock(&self.allocator).reserve()
+    }
+
+    /// Returns true if the key was reserved or inserted, and has not been removed.
+    pub fn is_valid(&self, key: ArenaKey<T>) -> bool {
+        lock(&self.allocator).contains(key)
+    }
+}
+
+/// A [`SystemParam`] for accessing a [`NonSendArena<T>`] on the main thread.
+///
+/// # Panics
+///
+/// Panics if [`NonSendArenaPlugin<T>`] was not added.
+#[derive(SystemParam)]
+pub struct NonSendArenaMut<'w, T: 'static> {
+    arena: NonSendMut<'w, NonSendArena<T>>,
+}
+
+impl<T: 'static> Deref for NonSendArenaMut<'_, T> {
+    type Target = NonSendArena<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.arena
+    }
+}
+
+impl<T: 'static> DerefMut for NonSendArenaMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.arena
+    }
+}
+
+/// A [`Plugin`] that inserts an empty [`NonSendArena<T>`] and its [`NonSendArenaKeys<T>`].
+pub struct NonSendArenaPlugin<T> {
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for NonSendArenaPlugin<T> {
+    fn default() -> Self {
+        NonSendArenaPlugin {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: 'static> Plugin for NonSendArenaPlugin<T> {
+    fn build(&self, app: &mut App) {
+        let arena = NonSendArena::<T>::default();
+
+        app.insert_resource(NonSendArenaKeys::<T> {
+            allocator: Arc::clone(&arena.allocator),
+            _marker: PhantomData,
+        })
+        .insert_non_send_resource(arena);
+    }
+}
+
+/// Creates a [`Command`] that inserts the value returned by `func` into the [`NonSendArena<T>`], using a key reserved with [`NonSendArenaKeys::reserve`].
+///
+/// `func` is executed on the main thread. It is not called if the key was removed before this command was applied.
+///
+/// # Panics
+///
+/// Panics if [`NonSendArenaPlugin<T>`] was not added.
+#[track_caller]
+pub fn insert_into_non_send_arena<F, T>(key: ArenaKey<T>, func: F) -> impl Command
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: 'static,
+{
+    main_thread::deferrable(move |world: &mut World| {
+        let mut arena = arena_mut::<T>(world);
+
+        if lock(&arena.allocator).contains(key) {
+            arena.put(key, func());
+        }
+    })
+}
+
+/// Creates a [`Command`] that removes and drops the value with the given key from the [`NonSendArena<T>`].
+///
+/// # Panics
+///
+/// Panics if [`NonSendArenaPlugin<T>`] was not added.
+#[track_caller]
+pub fn remove_from_non_send_arena<T: 'static>(key: ArenaKey<T>) -> impl Command {
+    main_thread::deferrable(move |world: &mut World| {
+        arena_mut::<T>(world).remove(key);
+    })
+}
+
+fn arena_mut<T: 'static>(world: &mut World) -> Mut<'_, NonSendArena<T>> {
+    world
+        .get_non_send_resource_mut::<NonSendArena<T>>()
+        .unwrap_or_else(|| {
+            panic!(
+                "`NonSendArena<{}>` does not exist. Did you add `NonSendArenaPlugin`?",
+                std::any::type_name::<T>(),
+            )
+        })
+}
@@ -0,0 +1,241 @@
+use std::{marker::PhantomData, panic::Location, sync::Arc};
+
+use bevy_app::{App, Plugin, PostUpdate};
+use bevy_asset::{Asset, AssetEvent, Assets, Handle};
+use bevy_ecs::{
+    event::EventReader,
+    system::{Command, Commands, ResMut, Resource},
+    world::World,
+};
+
+use crate::{main_thread, tracking, NonSendCommand, NonSendCommandKind};
+
+type BuildFn<A, R> = dyn Fn(&A) -> R + Send + Sync;
+
+/// A [`Plugin`] that rebuilds the non-[`Send`] resource `R` whenever its source asset `A` is loaded or modified.
+///
+/// The asset is bound to the resource with [`insert_non_send_from_asset`]. Rebuilds happen in [`PostUpdate`], replacing the previous value of `R`.
+pub struct NonSendFromAssetPlugin<A, R> {
+    _marker: PhantomData<fn(A) -> R>,
+}
+
+impl<A, R> Default for NonSendFromAssetPlugin<A, R> {
+    fn default() -> Self {
+        NonSendFromAssetPlugin {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A: Asset, R: 'static> Plugin for NonSendFromAssetPlugin<A, R> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, watch_asset::<A, R>);
+    }
+}
+
+/// The asset that the non-[`Send`] resource `R` is built from.
+#[derive(Resource)]
+pub struct NonSendAssetBinding<A: Asset, R> {
+    handle: Handle<A>,
+    build: Arc<BuildFn<A, R>>,
+    location: &'static Location<'static>,
+    // Whether `R` was built from the current asset, so its `Added` and `LoadedWithDependencies` events can be skipped.
+    built: bool,
+}
+
+impl<A: Asset, R> NonSendAssetBinding<A, R> {
+    /// Returns the handle of the source asset.
+    pub fn handle(&self) -> &Handle<A> {
+        &self.handle
+    }
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource built from an asset.
+///
+/// `build` is called on the main thread once the asset is available, and again every time it is modified, such as when it is hot-reloaded. If the asset is already available when the command is applied, the resource is built immediately, and is not built again when the asset's [`AssetEvent::Added`] event is read. [`NonSendFromAssetPlugin`] must be added for the resource to be rebuilt.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{insert_non_send_from_asset, NonSendFromAssetPlugin};
+/// #
+/// #[derive(Asset)]
+/// struct ToolkitConfig {
+///     optimize: bool,
+/// }
+/// #
+/// # // Deriving `TypePath` resolves to `bevy_reflect`, which is an optional dependency of this crate.
+/// # impl bevy::reflect::TypePath for ToolkitConfig {
+/// #     fn type_path() -> &'static str {
+/// #         "doctest::ToolkitConfig"
+/// #     }
+/// #
+/// #     fn short_type_path() -> &'static str {
+/// #         "ToolkitConfig"
+/// #     }
+/// # }
+///
+/// struct ShaderToolkit(*const u8, bool);
+///
+/// fn create_toolkit(mut commands: Commands, mut configs: ResMut<Assets<ToolkitConfig>>) {
+///     let handle = configs.add(ToolkitConfig { optimize: true });
+///
+///     commands.add(
+///         insert_non_send_from_asset(handle, |config: &ToolkitConfig| {
+///             ShaderToolkit(std::ptr::null(), config.optimize)
+///         })
+///     );
+/// }
+/// #
+/// # App::new()
+/// #     .add_plugins((MinimalPlugins, AssetPlugin::default()))
+/// #     .init_asset::<ToolkitConfig>()
+/// #     .add_plugins(NonSendFromAssetPlugin::<ToolkitConfig, ShaderToolkit>::default())
+/// #     .add_systems(Startup, create_toolkit)
+/// #     .add_systems(Update, check)
+/// #     .set_runner(|mut app| app.update())
+/// #     .run();
+/// #
+/// # fn check(toolkit: NonSend<ShaderToolkit>) {
+/// #     assert!(toolkit.1);
+/// # }
+/// ```
+#[track_caller]
+pub fn insert_non_send_from_asset<A, R, F>(handle: Handle<A>, build: F) -> impl Command
+where
+    A: Asset,
+    R: 'static,
+    F: Fn(&A) -> R + Send + Sync + 'static,
+{
+    let location = Location::caller();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| {
+            world.insert_resource(NonSendAssetBinding::<A, R> {
+                handle,
+                build: Arc::new(build),
+                location,
+                built: false,
+            });
+
+            rebuild::<A, R>(world);
+        },
+    )
+}
+
+/// Creates a [`Command`] for removing a non-[`Send`] resource built from an asset, along with its binding.
+#[track_caller]
+pub fn remove_non_send_from_asset<A: Asset, R: 'static>() -> impl Command {
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Remove,
+        Location::caller(),
+        |world: &mut World| {
+            world.remove_resource::<NonSendAssetBinding<A, R>>();
+            tracking::remove::<R>(world);
+        },
+    )
+}
+
+/// Rebuilds `R` from its bound asset, if the asset is available.
+fn rebuild<A: Asset, R: 'static>(world: &mut World) {
+    let Some(binding) = world.get_resource::<NonSendAssetBinding<A, R>>() else {
+        return;
+    };
+
+    let Some(asset) = world
+        .get_resource::<Assets<A>>()
+        .and_then(|assets| assets.get(&binding.handle))
+    else {
+        return;
+    };
+
+    let location = binding.location;
+    let resource = (binding.build)(asset);
+
+    world.resource_mut::<NonSendAssetBinding<A, R>>().built = true;
+    world.insert_non_send_resource(resource);
+    tracking::track::<R>(world, location, None);
+}
+
+fn watch_asset<A: Asset, R: 'static>(
+    mut events: EventReader<AssetEvent<A>>,
+    binding: Option<ResMut<NonSendAssetBinding<A, R>>>,
+    mut commands: Commands,
+) {
+    let Some(mut binding) = binding else {
+        events.clear();
+        return;
+    };
+
+    let id = binding.handle.id();
+
+    // Rebuild at most once per frame, even if the asset was both added and loaded.
+    let mut changed = false;
+
+    for event in events.read() {
+        if event.is_removed(id) {
+            binding.built = false;
+        } else if event.is_added(id) || event.is_loaded_with_dependencies(id) {
+            // The resource may already have been built from this asset when it was inserted.
+            changed |= !binding.built;
+        } else if event.is_modified(id) {
+            changed = true;
+        }
+    }
+
+    if changed {
+        commands.add(main_thread::deferrable(rebuild::<A, R>));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use bevy::{asset::AssetPlugin, prelude::*, reflect::TypePath};
+
+    use super::*;
+
+    #[derive(Asset)]
+    struct Config;
+
+    // Deriving `TypePath` resolves to `bevy_reflect`, which is an optional dependency of this crate.
+    impl TypePath for Config {
+        fn type_path() -> &'static str {
+            "bevy_command_non_send::asset::tests::Config"
+        }
+
+        fn short_type_path() -> &'static str {
+            "Config"
+        }
+    }
+
+    struct Toolkit;
+
+    #[test]
+    fn available_asset_is_built_once() {
+        static BUILDS: AtomicUsize = AtomicUsize::new(0);
+
+        let mut app = App::new();
+
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Config>()
+            .add_plugins(NonSendFromAssetPlugin::<Config, Toolkit>::default());
+
+        let handle = app.world.resource_mut::<Assets<Config>>().add(Config);
+
+        insert_non_send_from_asset(handle, |_: &Config| {
+            BUILDS.fetch_add(1, Ordering::Relaxed);
+            Toolkit
+        })
+        .apply(&mut app.world);
+
+        for _ in 0..3 {
+            app.update();
+        }
+
+        assert!(app.world.contains_non_send::<Toolkit>());
+        assert_eq!(BUILDS.load(Ordering::Relaxed), 1);
+    }
+}
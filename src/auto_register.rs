@@ -0,0 +1,147 @@
+use std::panic::Location;
+
+use bevy_app::{App, Plugin, PreStartup};
+use bevy_ecs::world::{FromWorld, World};
+
+use crate::tracking;
+
+/// A non-[`Send`] resource that was registered with [`auto_non_send!`](crate::auto_non_send).
+///
+/// You should not need to construct this type yourself.
+pub struct AutoNonSend {
+    priority: i32,
+    type_name: fn() -> &'static str,
+    location: &'static Location<'static>,
+    init: fn(&mut World, &'static Location<'static>),
+}
+
+impl AutoNonSend {
+    #[doc(hidden)]
+    #[track_caller]
+    // `Location::caller` is only const since Rust 1.79, which this feature requires.
+    #[clippy::msrv = "1.79"]
+    pub const fn new<R: FromWorld + 'static>(priority: i32) -> Self {
+        AutoNonSend {
+            priority,
+            type_name: std::any::type_name::<R>,
+            location: Location::caller(),
+            init: tracking::init::<R>,
+        }
+    }
+
+    /// Returns the priority this resource was registered with.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Returns the type name of the registered resource.
+    pub fn type_name(&self) -> &'static str {
+        (self.type_name)()
+    }
+
+    /// Returns the location of the [`auto_non_send!`](crate::auto_non_send) call that registered this resource.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+}
+
+inventory::collect!(AutoNonSend);
+
+/// Registers a non-[`Send`] resource to be initialized by [`NonSendAutoInitPlugin`].
+///
+/// The resource must implement [`FromWorld`], which is used unless a factory was registered in [`NonSendFactories`](crate::NonSendFactories). An optional priority may be specified, where resources with a higher priority are initialized first. The default priority is `0`.
+///
+/// Registration happens at link time, so this may be called from any crate in your dependency tree.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{auto_non_send, NonSendAutoInitPlugin};
+/// #
+/// #[derive(Default)]
+/// struct MyNonSend(*const u8);
+///
+/// auto_non_send!(MyNonSend);
+///
+/// #[derive(Default)]
+/// struct InitializedFirst(*const u8);
+///
+/// auto_non_send!(InitializedFirst, priority = 10);
+/// #
+/// # App::new()
+/// #     .add_plugins(NonSendAutoInitPlugin)
+/// #     .add_systems(Startup, check)
+/// #     .run();
+/// #
+/// # fn check(my_non_send: NonSend<MyNonSend>, first: NonSend<InitializedFirst>) {
+/// #     assert!(my_non_send.0.is_null());
+/// #     assert!(first.0.is_null());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! auto_non_send {
+    ($ty:ty) => {
+        $crate::auto_non_send!($ty, priority = 0);
+    };
+    ($ty:ty, priority = $priority:expr) => {
+        $crate::__private::inventory::submit! {
+            $crate::AutoNonSend::new::<$ty>($priority)
+        }
+    };
+}
+
+/// A [`Plugin`] that initializes all non-[`Send`] resources registered with [`auto_non_send!`](crate::auto_non_send).
+///
+/// Resources are initialized in [`PreStartup`], ordered by descending priority. Resources that already exist in the [`World`] are left untouched.
+pub struct NonSendAutoInitPlugin;
+
+impl Plugin for NonSendAutoInitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreStartup, auto_init_non_send_resources);
+    }
+}
+
+fn auto_init_non_send_resources(world: &mut World) {
+    let mut entries: Vec<&AutoNonSend> = inventory::iter::<AutoNonSend>().collect();
+
+    // Sort by descending priority, falling back to the type name so the order is deterministic.
+    entries.sort_by(|a, b| {
+        b.priority
+            .cmp(&a.priority)
+            .then_with(|| a.type_name().cmp(b.type_name()))
+    });
+
+    for entry in entries {
+        (entry.init)(world, entry.location);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+
+    use super::*;
+    use crate::NonSendTracker;
+
+    #[derive(Default)]
+    struct AutoRegistered(*const u8);
+
+    crate::auto_non_send!(AutoRegistered);
+
+    #[test]
+    fn auto_init_tracks_registration_location() {
+        let mut world = World::new();
+        world.init_resource::<NonSendTracker>();
+
+        auto_init_non_send_resources(&mut world);
+
+        assert!(world.non_send_resource::<AutoRegistered>().0.is_null());
+
+        let tracked = world
+            .resource::<NonSendTracker>()
+            .get(TypeId::of::<AutoRegistered>())
+            .unwrap();
+
+        assert_eq!(tracked.location().file(), file!());
+        assert!(tracked.can_reinit());
+    }
+}
@@ -0,0 +1,379 @@
+use std::{collections::HashMap, fmt};
+
+use bevy_app::{App, Plugin};
+use bevy_ecs::{
+    system::{In, Resource, SystemId},
+    world::World,
+};
+use serde_json::{json, Value};
+
+use crate::{
+    reinit_tracked_non_send, remove_tracked_non_send, NonSendTracker, NonSendTrackingPlugin,
+    TrackedNonSend,
+};
+
+/// The name of the BRP method handled by [`process_non_send_list_request`].
+pub const BRP_LIST_METHOD: &str = "bevy_command_non_send/list";
+
+/// The name of the BRP method handled by [`process_non_send_remove_request`].
+pub const BRP_REMOVE_METHOD: &str = "bevy_command_non_send/remove";
+
+/// The name of the BRP method handled by [`process_non_send_reinit_request`].
+pub const BRP_REINIT_METHOD: &str = "bevy_command_non_send/reinit";
+
+/// The result of a non-[`Send`] BRP method: the JSON value of the response, or an error.
+pub type NonSendBrpResult = Result<Value, NonSendBrpError>;
+
+/// The system that handles a non-[`Send`] BRP method, registered in [`NonSendBrpMethods`].
+pub type NonSendBrpSystemId = SystemId<Option<Value>, NonSendBrpResult>;
+
+/// An error returned by a non-[`Send`] BRP method, in the shape of a JSON-RPC error object.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NonSendBrpError {
+    /// The JSON-RPC error code. See the associated constants for the codes used by this crate.
+    pub code: i16,
+    /// A description of the error.
+    pub message: String,
+}
+
+impl NonSendBrpError {
+    /// The method does not exist.
+    pub const METHOD_NOT_FOUND: i16 = -32601;
+
+    /// The parameters of the request are invalid.
+    pub const INVALID_PARAMS: i16 = -32602;
+
+    /// The method could not be run, such as when tracking is not enabled.
+    pub const INTERNAL_ERROR: i16 = -32603;
+
+    /// The requested resource is not tracked by the [`NonSendTracker`].
+    pub const NOT_TRACKED: i16 = -32001;
+
+    /// The requested resource cannot be re-initialized, because it was not inserted with [`init_non_send_resource`](crate::init_non_send_resource).
+    pub const CANNOT_REINIT: i16 = -32002;
+
+    fn new(code: i16, message: impl Into<String>) -> Self {
+        NonSendBrpError {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the error as a JSON-RPC error object.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "code": self.code,
+            "message": self.message,
+        })
+    }
+}
+
+impl fmt::Display for NonSendBrpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (code {})", self.message, self.code)
+    }
+}
+
+impl std::error::Error for NonSendBrpError {}
+
+/// A [`Resource`] mapping the names of the non-[`Send`] BRP methods to the systems that handle them.
+///
+/// This is inserted by [`NonSendBrpPlugin`].
+#[derive(Resource, Default)]
+pub struct NonSendBrpMethods {
+    methods: HashMap<&'static str, NonSendBrpSystemId>,
+}
+
+impl NonSendBrpMethods {
+    /// Returns the system that handles the given method, if it is registered.
+    pub fn get(&self, method: &str) -> Option<NonSendBrpSystemId> {
+        self.methods.get(method).copied()
+    }
+
+    /// Iterates over the names of all registered methods, in arbitrary order.
+    pub fn methods(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.methods.keys().copied()
+    }
+}
+
+/// A [`Plugin`] that registers Bevy Remote Protocol (BRP) methods for inspecting and resetting tracked non-[`Send`] resources.
+///
+/// The stock `bevy/query` and `bevy/get` methods cannot see non-[`Send`] resources, so this registers the following methods instead:
+///
+/// - [`BRP_LIST_METHOD`]: See [`process_non_send_list_request`].
+/// - [`BRP_REMOVE_METHOD`]: See [`process_non_send_remove_request`].
+/// - [`BRP_REINIT_METHOD`]: See [`process_non_send_reinit_request`].
+///
+/// Bevy 0.13, which this crate targets, does not ship `bevy_remote`, so the methods are registered as systems in [`NonSendBrpMethods`] instead of a `RemotePlugin`. Forward the `method` and `params` of every JSON-RPC request received by your transport to [`run_non_send_brp_method`], and reply with its result. Only resources recorded by the [`NonSendTracker`] are known to these methods, so this adds [`NonSendTrackingPlugin`] if it was not added yet.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{run_non_send_brp_method, CommandsExt, NonSendBrpPlugin, BRP_LIST_METHOD};
+/// # use serde_json::json;
+/// #
+/// struct AudioDevice(*const u8);
+///
+/// let mut app = App::new();
+///
+/// app.add_plugins(NonSendBrpPlugin).add_systems(Startup, |mut commands: Commands| {
+///     commands.insert_non_send_resource(|| AudioDevice(std::ptr::null()));
+/// });
+///
+/// app.update();
+///
+/// // Usually received by the remote transport.
+/// let response = run_non_send_brp_method(&mut app.world, BRP_LIST_METHOD, None).unwrap();
+///
+/// assert_eq!(response[0]["type_name"], json!(std::any::type_name::<AudioDevice>()));
+/// ```
+pub struct NonSendBrpPlugin;
+
+impl Plugin for NonSendBrpPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<NonSendTrackingPlugin>() {
+            app.add_plugins(NonSendTrackingPlugin);
+        }
+
+        let methods = HashMap::from([
+            (
+                BRP_LIST_METHOD,
+                app.world.register_system(process_non_send_list_request),
+            ),
+            (
+                BRP_REMOVE_METHOD,
+                app.world.register_system(process_non_send_remove_request),
+            ),
+            (
+                BRP_REINIT_METHOD,
+                app.world.register_system(process_non_send_reinit_request),
+            ),
+        ]);
+
+        app.insert_resource(NonSendBrpMethods { methods });
+    }
+}
+
+/// Runs the non-[`Send`] BRP method with the given name and parameters, returning its response.
+///
+/// Returns a [`NonSendBrpError::METHOD_NOT_FOUND`] error if the method is not registered in [`NonSendBrpMethods`]. This must be called on the main thread, since the methods access non-[`Send`] resources.
+pub fn run_non_send_brp_method(
+    world: &mut World,
+    method: &str,
+    params: Option<Value>,
+) -> NonSendBrpResult {
+    let Some(system) = world
+        .get_resource::<NonSendBrpMethods>()
+        .and_then(|methods| methods.get(method))
+    else {
+        return Err(NonSendBrpError::new(
+            NonSendBrpError::METHOD_NOT_FOUND,
+            format!("Method `{method}` not found. Did you add `NonSendBrpPlugin`?"),
+        ));
+    };
+
+    world
+        .run_system_with_input(system, params)
+        .unwrap_or_else(|error| {
+            Err(NonSendBrpError::new(
+                NonSendBrpError::INTERNAL_ERROR,
+                error.to_string(),
+            ))
+        })
+}
+
+/// Handles [`BRP_LIST_METHOD`], listing the tracked non-[`Send`] resources.
+///
+/// Takes no parameters. Responds with an array of objects sorted by type name, each with the following fields:
+///
+/// - `type_name`: The type name of the resource. This identifies it in the other methods.
+/// - `location`: Where the command that inserted the resource was queued, as `file:line:column`.
+/// - `age_secs`: How many seconds ago the resource was inserted.
+/// - `can_reinit`: Whether the resource can be re-initialized with [`BRP_REINIT_METHOD`].
+pub fn process_non_send_list_request(
+    In(_params): In<Option<Value>>,
+    world: &mut World,
+) -> NonSendBrpResult {
+    let mut tracked: Vec<_> = tracker(world)?.iter().collect();
+    tracked.sort_by_key(|tracked| tracked.type_name());
+
+    let tracked = tracked
+        .into_iter()
+        .map(|tracked| {
+            json!({
+                "type_name": tracked.type_name(),
+                "location": tracked.location().to_string(),
+                "age_secs": tracked.age().as_secs_f64(),
+                "can_reinit": tracked.can_reinit(),
+            })
+        })
+        .collect();
+
+    Ok(Value::Array(tracked))
+}
+
+/// Handles [`BRP_REMOVE_METHOD`], removing a tracked non-[`Send`] resource.
+///
+/// Takes a `resource` parameter with the type name of the resource, as listed by [`BRP_LIST_METHOD`]. Responds with `null`. See [`remove_tracked_non_send`].
+pub fn process_non_send_remove_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> NonSendBrpResult {
+    let tracked = find(world, params)?;
+
+    remove_tracked_non_send(world, tracked.type_id());
+
+    Ok(Value::Null)
+}
+
+/// Handles [`BRP_REINIT_METHOD`], removing and re-initializing a tracked non-[`Send`] resource.
+///
+/// Takes a `resource` parameter with the type name of the resource, as listed by [`BRP_LIST_METHOD`]. Responds with `null`. See [`reinit_tracked_non_send`].
+pub fn process_non_send_reinit_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> NonSendBrpResult {
+    let tracked = find(world, params)?;
+
+    if !reinit_tracked_non_send(world, tracked.type_id()) {
+        return Err(NonSendBrpError::new(
+            NonSendBrpError::CANNOT_REINIT,
+            format!(
+                "`{}` cannot be re-initialized, because it was not inserted with `init_non_send_resource`.",
+                tracked.type_name(),
+            ),
+        ));
+    }
+
+    Ok(Value::Null)
+}
+
+fn tracker(world: &World) -> Result<&NonSendTracker, NonSendBrpError> {
+    world.get_resource::<NonSendTracker>().ok_or_else(|| {
+        NonSendBrpError::new(
+            NonSendBrpError::INTERNAL_ERROR,
+            "Tracking is not enabled. Did you add `NonSendTrackingPlugin`?",
+        )
+    })
+}
+
+/// Finds the tracked resource named by the `resource` parameter.
+fn find(world: &World, params: Option<Value>) -> Result<TrackedNonSend, NonSendBrpError> {
+    let Some(type_name) = params
+        .as_ref()
+        .and_then(|params| params.get("resource"))
+        .and_then(Value::as_str)
+    else {
+        return Err(NonSendBrpError::new(
+            NonSendBrpError::INVALID_PARAMS,
+            "Expected a `resource` parameter with the type name of the resource.",
+        ));
+    };
+
+    tracker(world)?
+        .get_by_name(type_name)
+        .cloned()
+        .ok_or_else(|| {
+            NonSendBrpError::new(
+                NonSendBrpError::NOT_TRACKED,
+                format!("`{type_name}` is not a tracked non-send resource."),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::system::Command;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct AudioDevice(u32);
+
+    struct Window;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(NonSendBrpPlugin);
+
+        crate::init_non_send_resource::<AudioDevice>().apply(&mut app.world);
+        crate::insert_non_send_resource(|| Window).apply(&mut app.world);
+
+        app
+    }
+
+    fn params<R>() -> Option<Value> {
+        Some(json!({ "resource": std::any::type_name::<R>() }))
+    }
+
+    #[test]
+    fn list_reports_tracked_resources() {
+        let mut app = app();
+
+        let response = run_non_send_brp_method(&mut app.world, BRP_LIST_METHOD, None).unwrap();
+        let Value::Array(tracked) = response else {
+            panic!("expected an array, got {response}");
+        };
+
+        assert_eq!(tracked.len(), 2);
+
+        let device = tracked
+            .iter()
+            .find(|tracked| tracked["type_name"] == std::any::type_name::<AudioDevice>())
+            .unwrap();
+
+        assert!(device["location"].as_str().unwrap().contains("brp.rs"));
+        assert!(device["age_secs"].as_f64().unwrap() >= 0.0);
+        assert_eq!(device["can_reinit"], true);
+    }
+
+    #[test]
+    fn remove_removes_resource() {
+        let mut app = app();
+
+        let response =
+            run_non_send_brp_method(&mut app.world, BRP_REMOVE_METHOD, params::<Window>());
+
+        assert_eq!(response, Ok(Value::Null));
+        assert!(!app.world.contains_non_send::<Window>());
+    }
+
+    #[test]
+    fn reinit_recreates_resource() {
+        let mut app = app();
+        app.world.non_send_resource_mut::<AudioDevice>().0 = 5;
+
+        let response =
+            run_non_send_brp_method(&mut app.world, BRP_REINIT_METHOD, params::<AudioDevice>());
+
+        assert_eq!(response, Ok(Value::Null));
+        assert_eq!(app.world.non_send_resource::<AudioDevice>().0, 0);
+    }
+
+    #[test]
+    fn reinit_without_init_fails() {
+        let mut app = app();
+
+        let error = run_non_send_brp_method(&mut app.world, BRP_REINIT_METHOD, params::<Window>())
+            .unwrap_err();
+
+        assert_eq!(error.code, NonSendBrpError::CANNOT_REINIT);
+        assert!(app.world.contains_non_send::<Window>());
+    }
+
+    #[test]
+    fn invalid_requests_fail() {
+        let mut app = app();
+
+        let missing = run_non_send_brp_method(&mut app.world, BRP_REMOVE_METHOD, None);
+        let unknown = run_non_send_brp_method(
+            &mut app.world,
+            BRP_REMOVE_METHOD,
+            Some(json!({ "resource": "Unknown" })),
+        );
+        let method = run_non_send_brp_method(&mut app.world, "bevy_command_non_send/get", None);
+
+        assert_eq!(missing.unwrap_err().code, NonSendBrpError::INVALID_PARAMS);
+        assert_eq!(unknown.unwrap_err().code, NonSendBrpError::NOT_TRACKED);
+        assert_eq!(method.unwrap_err().code, NonSendBrpError::METHOD_NOT_FOUND);
+    }
+}
@@ -0,0 +1,447 @@
+use std::{any::TypeId, collections::HashMap, marker::PhantomData, panic::Location};
+
+use bevy_ecs::{
+    schedule::{InternedScheduleLabel, ScheduleLabel},
+    system::Commands,
+    world::World,
+};
+
+use crate::{
+    deferred_drop, error, labeled, tracking, NonSendCommand, NonSendCommandError,
+    NonSendCommandKind, NonSendErrorContext, NonSendLabel,
+};
+
+type RemovalHook = Box<dyn FnOnce(&mut World) + Send>;
+type RemovalKey = (TypeId, Option<NonSendLabel>);
+pub(crate) type TakenRemovalOptions = (Vec<RemovalHook>, Option<InternedScheduleLabel>);
+
+/// The options registered with [`NonSendBuilder::on_removed`] and [`NonSendBuilder::drop_deferred`], stored as a non-[`Send`] resource since they only apply on the main thread.
+///
+/// Options are keyed by type and label, and are discarded once the resource is removed or replaced.
+#[derive(Default)]
+struct RemovalOptions {
+    hooks: HashMap<RemovalKey, Vec<RemovalHook>>,
+    drop_deferred: HashMap<RemovalKey, InternedScheduleLabel>,
+}
+
+/// The state of a [`NonSendBuilder`] before [`with_factory`](NonSendBuilder::with_factory) is called.
+///
+/// [`insert`](NonSendBuilder::insert) is only available once a factory was set.
+///
+/// ```compile_fail
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::CommandsExt;
+/// #
+/// struct Window(*const u8);
+///
+/// fn open_window(mut commands: Commands) {
+///     // Does not compile, because there is no factory to create the window with.
+///     commands.non_send::<Window>().if_absent().insert();
+/// }
+/// ```
+pub struct NoFactory;
+
+/// A builder for inserting a non-[`Send`] resource with a combination of options.
+///
+/// This is created with [`CommandsExt::non_send`](crate::CommandsExt::non_send), configured with chained methods, and finally queued with [`insert`](Self::insert). Nothing is queued until `insert` is called, which is only available once a factory was set with [`with_factory`](Self::with_factory).
+///
+/// ```
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::CommandsExt;
+/// #
+/// static CLOSED: AtomicBool = AtomicBool::new(false);
+///
+/// struct Window(*const u8);
+///
+/// fn open_window(mut commands: Commands) {
+///     commands
+///         .non_send::<Window>()
+///         .with_factory(|| Window(std::ptr::null()))
+///         .if_absent()
+///         .on_removed(|_world| CLOSED.store(true, Ordering::Relaxed))
+///         .insert();
+/// }
+///
+/// fn close_window(mut commands: Commands) {
+///     commands.remove_non_send_resource::<Window>();
+/// }
+/// #
+/// # App::new()
+/// #     .add_systems(Startup, (open_window, open_window, close_window).chain())
+/// #     .run();
+/// #
+/// # assert!(CLOSED.load(Ordering::Relaxed));
+/// ```
+#[must_use = "the resource is only inserted once `insert` is called"]
+pub struct NonSendBuilder<'a, R: 'static, F = NoFactory> {
+    commands: Commands<'a, 'a>,
+    factory: F,
+    if_absent: bool,
+    label: Option<NonSendLabel>,
+    drop_deferred: Option<InternedScheduleLabel>,
+    location: &'static Location<'static>,
+    on_removed: Vec<RemovalHook>,
+    _marker: PhantomData<fn() -> R>,
+}
+
+impl<'a, R: 'static> NonSendBuilder<'a, R> {
+    pub(crate) fn new(commands: Commands<'a, 'a>, location: &'static Location<'static>) -> Self {
+        NonSendBuilder {
+            commands,
+            factory: NoFactory,
+            if_absent: false,
+            label: None,
+            drop_deferred: None,
+            location,
+            on_removed: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, R: 'static, F> NonSendBuilder<'a, R, F> {
+    /// Sets the closure that creates the resource on the main thread.
+    ///
+    /// This is required before calling [`insert`](Self::insert).
+    pub fn with_factory<G>(self, func: G) -> NonSendBuilder<'a, R, G>
+    where
+        G: FnOnce() -> R + Send + 'static,
+    {
+        NonSendBuilder {
+            commands: self.commands,
+            factory: func,
+            if_absent: self.if_absent,
+            label: self.label,
+            drop_deferred: self.drop_deferred,
+            location: self.location,
+            on_removed: self.on_removed,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Only inserts the resource if it does not exist yet, instead of replacing it.
+    ///
+    /// When the resource already exists, the factory is not called and no options are registered.
+    pub fn if_absent(mut self) -> Self {
+        self.if_absent = true;
+        self
+    }
+
+    /// Inserts the resource as a labeled instance, alongside the other instances of the same type.
+    ///
+    /// The instance is accessed with [`NonSendKeyed`](crate::NonSendKeyed) and removed with [`remove_non_send_labeled`](crate::remove_non_send_labeled). See [`insert_non_send_labeled`](crate::insert_non_send_labeled).
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendKeyed};
+    /// #
+    /// struct GlContext(*const u8);
+    ///
+    /// fn create_context(mut commands: Commands) {
+    ///     commands
+    ///         .non_send::<GlContext>()
+    ///         .with_factory(|| GlContext(std::ptr::null()))
+    ///         .labeled("primary_monitor")
+    ///         .insert();
+    /// }
+    ///
+    /// fn draw(contexts: NonSendKeyed<GlContext>) {
+    ///     assert!(contexts.contains("primary_monitor"));
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, (create_context, draw).chain())
+    /// #     .run();
+    /// ```
+    pub fn labeled(mut self, label: impl Into<NonSendLabel>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Drops the resource when the schedule `label` next runs after it is removed, instead of immediately.
+    ///
+    /// This applies to removal through one of this crate's commands, such as [`remove_non_send_resource`](crate::remove_non_send_resource). As with [`remove_non_send_resource_deferred`](crate::remove_non_send_resource_deferred), the schedule may need to be registered with [`AppExt::add_non_send_drop_point`](crate::AppExt::add_non_send_drop_point) first.
+    ///
+    /// ```
+    /// # use std::sync::atomic::{AtomicBool, Ordering};
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{AppExt, CommandsExt};
+    /// #
+    /// static DROPPED: AtomicBool = AtomicBool::new(false);
+    ///
+    /// struct GpuBuffer(*const u8);
+    ///
+    /// impl Drop for GpuBuffer {
+    ///     fn drop(&mut self) {
+    ///         DROPPED.store(true, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// fn create_buffer(mut commands: Commands) {
+    ///     commands
+    ///         .non_send::<GpuBuffer>()
+    ///         .with_factory(|| GpuBuffer(std::ptr::null()))
+    ///         .drop_deferred(Last)
+    ///         .insert();
+    /// }
+    ///
+    /// fn release_buffer(mut commands: Commands) {
+    ///     commands.remove_non_send_resource::<GpuBuffer>();
+    /// }
+    ///
+    /// fn check_detached(buffer: Option<NonSend<GpuBuffer>>) {
+    ///     // The buffer was removed, but is not dropped until `Last`.
+    ///     assert!(buffer.is_none());
+    ///     assert!(!DROPPED.load(Ordering::Relaxed));
+    /// }
+    ///
+    /// let mut app = App::new();
+    ///
+    /// app.add_systems(Startup, create_buffer)
+    ///     // `Last` may not have any systems yet, so make sure it exists.
+    ///     .add_non_send_drop_point(Last)
+    ///     .add_systems(Update, release_buffer)
+    ///     .add_systems(PostUpdate, check_detached);
+    ///
+    /// app.update();
+    ///
+    /// assert!(DROPPED.load(Ordering::Relaxed));
+    /// ```
+    pub fn drop_deferred(mut self, label: impl ScheduleLabel) -> Self {
+        self.drop_deferred = Some(label.intern());
+        self
+    }
+
+    /// Records the location of the code calling this method in the [`NonSendTracker`](crate::NonSendTracker), instead of where [`CommandsExt::non_send`](crate::CommandsExt::non_send) was called.
+    ///
+    /// This is useful when the builder is created by a shared helper, but configured by its callers.
+    #[track_caller]
+    pub fn track_caller(mut self) -> Self {
+        self.location = Location::caller();
+        self
+    }
+
+    /// Registers a hook that runs on the main thread once the resource is removed.
+    ///
+    /// The hook runs after removal through one of this crate's commands, such as [`remove_non_send_resource`](crate::remove_non_send_resource), and only if the resource existed. It does not run if the resource is removed directly from the [`World`] or moved into a [`NonSendGuard`](crate::NonSendGuard), and it is discarded if the resource is replaced by another insertion. Several hooks may be registered, and they run in the order they were added.
+    ///
+    /// ```
+    /// # use std::sync::atomic::{AtomicU32, Ordering};
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::CommandsExt;
+    /// #
+    /// static CLOSED: AtomicU32 = AtomicU32::new(0);
+    ///
+    /// struct Window(*const u8);
+    ///
+    /// fn open_window(mut commands: Commands) {
+    ///     commands
+    ///         .non_send::<Window>()
+    ///         .with_factory(|| Window(std::ptr::null()))
+    ///         .on_removed(|_world| {
+    ///             CLOSED.fetch_add(1, Ordering::Relaxed);
+    ///         })
+    ///         .insert();
+    /// }
+    ///
+    /// fn reopen_window(mut commands: Commands) {
+    ///     // Replacing the window discards the hook of the previous one.
+    ///     commands.insert_non_send_resource(|| Window(std::ptr::null()));
+    /// }
+    ///
+    /// fn close_window(mut commands: Commands) {
+    ///     commands.remove_non_send_resource::<Window>();
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, (open_window, reopen_window, close_window, open_window, close_window, close_window).chain())
+    /// #     .run();
+    /// #
+    /// # // Only the second window ran its hook, once.
+    /// # assert_eq!(CLOSED.load(Ordering::Relaxed), 1);
+    /// ```
+    pub fn on_removed(mut self, hook: impl FnOnce(&mut World) + Send + 'static) -> Self {
+        self.on_removed.push(Box::new(hook));
+        self
+    }
+}
+
+impl<'a, R, F> NonSendBuilder<'a, R, F>
+where
+    R: 'static,
+    F: FnOnce() -> R + Send + 'static,
+{
+    /// Queues the insertion of the resource with the configured options.
+    pub fn insert(self) {
+        self.queue(false);
+    }
+
+    /// Queues the insertion of the resource with the configured options, passing a [`NonSendCommandError::ResourceExists`] or [`NonSendCommandError::LabelExists`] error to the [`NonSendErrorHandler`](crate::NonSendErrorHandler) if it already exists.
+    ///
+    /// The existing resource is kept, and the factory is not called. This takes precedence over [`if_absent`](Self::if_absent).
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendErrorHandler};
+    /// #
+    /// struct Window(u32);
+    ///
+    /// fn open_window(mut commands: Commands) {
+    ///     // The window is already open, so this will call the error handler.
+    ///     commands
+    ///         .non_send::<Window>()
+    ///         .with_factory(|| Window(2))
+    ///         .try_insert();
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_resource(NonSendErrorHandler::IGNORE)
+    /// #     .insert_non_send_resource(Window(1))
+    /// #     .add_systems(Startup, (open_window, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(window: NonSend<Window>) {
+    /// #     assert_eq!(window.0, 1);
+    /// # }
+    /// ```
+    pub fn try_insert(self) {
+        self.queue(true);
+    }
+
+    fn queue(mut self, fail_if_exists: bool) {
+        let factory = self.factory;
+        let if_absent = self.if_absent;
+        let label = self.label;
+        let drop_deferred = self.drop_deferred;
+        let location = self.location;
+        let on_removed = self.on_removed;
+
+        let command = move |world: &mut World| {
+            let exists = match label {
+                Some(label) => labeled::contains_labeled::<R>(world, label),
+                None => world.contains_non_send::<R>(),
+            };
+
+            if exists && fail_if_exists {
+                let error = match label {
+                    Some(label) => NonSendCommandError::LabelExists {
+                        type_name: std::any::type_name::<R>(),
+                        label,
+                    },
+                    None => NonSendCommandError::ResourceExists {
+                        type_name: std::any::type_name::<R>(),
+                    },
+                };
+
+                let context = NonSendErrorContext {
+                    command: "try_insert",
+                    location,
+                };
+
+                error::handle_error(world, Err(error), context);
+                return;
+            }
+
+            if exists && if_absent {
+                return;
+            }
+
+            match label {
+                Some(label) => labeled::insert_labeled(world, label, (factory)(), location),
+                None => {
+                    world.insert_non_send_resource((factory)());
+                    tracking::track::<R>(world, location, None);
+                }
+            }
+
+            if on_removed.is_empty() && drop_deferred.is_none() {
+                return;
+            }
+
+            world.init_non_send_resource::<RemovalOptions>();
+
+            let mut options = world.non_send_resource_mut::<RemovalOptions>();
+            let key = (TypeId::of::<R>(), label);
+
+            if let Some(drop_deferred) = drop_deferred {
+                options.drop_deferred.insert(key, drop_deferred);
+            }
+
+            options.hooks.entry(key).or_default().extend(on_removed);
+        };
+
+        self.commands.add(NonSendCommand::new::<R>(
+            NonSendCommandKind::Insert,
+            location,
+            command,
+        ));
+    }
+}
+
+/// Discards the removal options registered for `R` with the given label, such as when it is replaced.
+pub(crate) fn clear_removal_options<R: 'static>(world: &mut World, label: Option<NonSendLabel>) {
+    take_removal_options::<R>(world, label);
+}
+
+/// Drops the removed `resource`, or defers dropping it if requested, and then runs its removal hooks.
+pub(crate) fn removed<R: 'static>(world: &mut World, label: Option<NonSendLabel>, resource: R) {
+    let (hooks, drop_deferred) = take_removal_options::<R>(world, label);
+
+    match drop_deferred {
+        Some(schedule) => deferred_drop::defer_drop(world, schedule, resource),
+        None => drop(resource),
+    }
+
+    for hook in hooks {
+        hook(world);
+    }
+}
+
+/// Runs and discards the removal hooks registered for `R` with the given label, once it was removed and its drop was already handled.
+pub(crate) fn run_removal_hooks<R: 'static>(world: &mut World, label: Option<NonSendLabel>) {
+    let (hooks, _) = take_removal_options::<R>(world, label);
+
+    for hook in hooks {
+        hook(world);
+    }
+}
+
+/// Registers removal options again that were taken with [`take_removal_options`], such as when a resource is moved back into the [`World`].
+pub(crate) fn restore_removal_options<R: 'static>(
+    world: &mut World,
+    label: Option<NonSendLabel>,
+    (hooks, drop_deferred): TakenRemovalOptions,
+) {
+    if hooks.is_empty() && drop_deferred.is_none() {
+        return;
+    }
+
+    world.init_non_send_resource::<RemovalOptions>();
+
+    let mut options = world.non_send_resource_mut::<RemovalOptions>();
+    let key = (TypeId::of::<R>(), label);
+
+    if let Some(drop_deferred) = drop_deferred {
+        options.drop_deferred.insert(key, drop_deferred);
+    }
+
+    options.hooks.entry(key).or_default().extend(hooks);
+}
+
+/// Removes and returns the removal options registered for `R` with the given label.
+pub(crate) fn take_removal_options<R: 'static>(
+    world: &mut World,
+    label: Option<NonSendLabel>,
+) -> TakenRemovalOptions {
+    let Some(mut options) = world.get_non_send_resource_mut::<RemovalOptions>() else {
+        return (Vec::new(), None);
+    };
+
+    let key = (TypeId::of::<R>(), label);
+
+    (
+        options.hooks.remove(&key).unwrap_or_default(),
+        options.drop_deferred.remove(&key),
+    )
+}
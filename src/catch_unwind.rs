@@ -0,0 +1,157 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe, Location},
+};
+
+use bevy_ecs::{event::Event, system::Command, world::World};
+
+use crate::{
+    error, tracking, NonSendCommand, NonSendCommandError, NonSendCommandKind, NonSendErrorContext,
+};
+
+/// An [`Event`] sent when the factory passed to [`insert_non_send_resource_catch_unwind`] panics.
+///
+/// You must register this event with `App::add_event` to receive it. If it is not registered, an error is logged instead.
+#[derive(Event, Clone, Debug)]
+pub struct NonSendFactoryPanicked {
+    /// The type name of the resource that failed to be created.
+    pub type_name: &'static str,
+
+    /// The panic message, if it was a string.
+    pub message: Option<String>,
+
+    /// The location of the code that queued the command.
+    pub location: &'static Location<'static>,
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource in the [`World`], catching any panic raised by `func`.
+///
+/// This behaves like [`insert_non_send_resource`](crate::insert_non_send_resource), but if `func` panics the panic is caught with [`std::panic::catch_unwind`] and a [`NonSendFactoryPanicked`] event is sent instead of unwinding through command application. The panic hook still runs, so the panic message is printed as usual.
+///
+/// Panics can only be caught when the `panic` strategy is set to `unwind`, which is the default.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{insert_non_send_resource_catch_unwind, NonSendFactoryPanicked};
+/// #
+/// struct MyNonSend(*const u8);
+///
+/// fn create_my_non_send(mut commands: Commands) {
+///     commands.add(
+///         insert_non_send_resource_catch_unwind(|| -> MyNonSend {
+///             panic!("Unsupported hardware!");
+///         })
+///     );
+/// }
+///
+/// fn handle_panics(mut events: EventReader<NonSendFactoryPanicked>) {
+///     for event in events.read() {
+///         eprintln!("Failed to create {}: {:?}", event.type_name, event.message);
+///     }
+/// }
+/// #
+/// # App::new()
+/// #     .add_event::<NonSendFactoryPanicked>()
+/// #     .add_systems(Startup, (create_my_non_send, handle_panics, check).chain())
+/// #     .run();
+/// #
+/// # fn check(my_non_send: Option<NonSend<MyNonSend>>, events: Res<Events<NonSendFactoryPanicked>>) {
+/// #     assert!(my_non_send.is_none());
+/// #     assert_eq!(events.len(), 1);
+/// # }
+/// ```
+#[track_caller]
+pub fn insert_non_send_resource_catch_unwind<F, R>(func: F) -> impl Command
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: 'static,
+{
+    let location = Location::caller();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| match panic::catch_unwind(AssertUnwindSafe(func)) {
+            Ok(resource) => {
+                world.insert_non_send_resource(resource);
+                tracking::track::<R>(world, location, None);
+            }
+            Err(payload) => {
+                crate::ticket::report_failure();
+
+                world.send_event(NonSendFactoryPanicked {
+                    type_name: std::any::type_name::<R>(),
+                    message: panic_message(&*payload),
+                    location,
+                });
+            }
+        },
+    )
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource in the [`World`], passing a [`NonSendCommandError::FactoryPanicked`] error to the [`NonSendErrorHandler`](crate::NonSendErrorHandler) if `func` panics.
+///
+/// This behaves like [`insert_non_send_resource_catch_unwind`], but reports the panic through the error handler instead of sending a [`NonSendFactoryPanicked`] event.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{try_insert_non_send_resource_catch_unwind, NonSendErrorHandler};
+/// #
+/// struct MyNonSend(*const u8);
+///
+/// fn create_my_non_send(mut commands: Commands) {
+///     commands.add(
+///         try_insert_non_send_resource_catch_unwind(|| -> MyNonSend {
+///             panic!("Unsupported hardware!");
+///         })
+///     );
+/// }
+/// #
+/// # App::new()
+/// #     .insert_resource(NonSendErrorHandler::WARN)
+/// #     .add_systems(Startup, (create_my_non_send, check).chain())
+/// #     .run();
+/// #
+/// # fn check(my_non_send: Option<NonSend<MyNonSend>>) {
+/// #     assert!(my_non_send.is_none());
+/// # }
+/// ```
+#[track_caller]
+pub fn try_insert_non_send_resource_catch_unwind<F, R>(func: F) -> impl Command
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: 'static,
+{
+    let context = NonSendErrorContext {
+        command: "try_insert_non_send_resource_catch_unwind",
+        location: Location::caller(),
+    };
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        context.location,
+        move |world: &mut World| {
+            let result = match panic::catch_unwind(AssertUnwindSafe(func)) {
+                Ok(resource) => {
+                    world.insert_non_send_resource(resource);
+                    tracking::track::<R>(world, context.location, None);
+                    Ok(())
+                }
+                Err(payload) => Err(NonSendCommandError::FactoryPanicked {
+                    type_name: std::any::type_name::<R>(),
+                    message: panic_message(&*payload),
+                }),
+            };
+
+            error::handle_error(world, result, context);
+        },
+    )
+}
+
+/// Extracts the message from a panic payload, if it is a string.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> Option<String> {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+}
@@ -0,0 +1,137 @@
+use std::marker::PhantomData;
+
+use bevy_app::{App, Last, Plugin};
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{Local, NonSend},
+};
+
+/// How a non-[`Send`] resource changed. See [`NonSendResourceChanged`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NonSendChange {
+    /// The resource was inserted.
+    Added,
+
+    /// The resource was mutably accessed.
+    Changed,
+
+    /// The resource was removed.
+    Removed,
+}
+
+/// A [`Send`] [`Event`] sent when the non-[`Send`] resource `R` was inserted, changed, or removed.
+///
+/// This lets systems that do not have access to `R`, and so may run on any thread, react to it one frame later. See [`NonSendChangeEventsPlugin`].
+#[derive(Event)]
+pub struct NonSendResourceChanged<R> {
+    /// How the resource changed.
+    pub change: NonSendChange,
+    _marker: PhantomData<fn() -> R>,
+}
+
+impl<R> Clone for NonSendResourceChanged<R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R> Copy for NonSendResourceChanged<R> {}
+
+impl<R> std::fmt::Debug for NonSendResourceChanged<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NonSendResourceChanged")
+            .field("type_name", &std::any::type_name::<R>())
+            .field("change", &self.change)
+            .finish()
+    }
+}
+
+/// A [`Plugin`] that watches the non-[`Send`] resource `R` on the main thread, and sends a [`NonSendResourceChanged<R>`] event whenever it is inserted, changed, or removed.
+///
+/// Changes are detected with change ticks at the end of each frame, in [`Last`], so they are seen by other systems in the next frame. Add this plugin once for each type that should be watched.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{NonSendChange, NonSendChangeEventsPlugin, NonSendResourceChanged};
+/// #
+/// struct AudioDevice {
+///     volume: f32,
+///     _handle: *const u8,
+/// }
+///
+/// fn set_volume(mut device: NonSendMut<AudioDevice>) {
+///     device.volume = 0.5;
+/// }
+///
+/// // This system does not access `AudioDevice`, so it can run on any thread.
+/// fn on_device_changed(mut events: EventReader<NonSendResourceChanged<AudioDevice>>) {
+///     for event in events.read() {
+///         println!("Audio device was {:?}.", event.change);
+///     }
+/// }
+///
+/// let mut app = App::new();
+///
+/// app.add_plugins(NonSendChangeEventsPlugin::<AudioDevice>::default())
+///     .insert_non_send_resource(AudioDevice { volume: 1.0, _handle: std::ptr::null() })
+///     .add_systems(Update, on_device_changed);
+///
+/// app.update();
+/// # assert_eq!(changes(&mut app), [NonSendChange::Added]);
+///
+/// app.add_systems(Update, set_volume.run_if(run_once()));
+/// app.update();
+/// # assert_eq!(changes(&mut app), [NonSendChange::Changed]);
+///
+/// app.world.remove_non_send_resource::<AudioDevice>();
+/// app.update();
+/// # assert_eq!(changes(&mut app), [NonSendChange::Removed]);
+/// #
+/// # fn changes(app: &mut App) -> Vec<NonSendChange> {
+/// #     app.world
+/// #         .resource_mut::<Events<NonSendResourceChanged<AudioDevice>>>()
+/// #         .drain()
+/// #         .map(|event| event.change)
+/// #         .collect()
+/// # }
+/// ```
+pub struct NonSendChangeEventsPlugin<R> {
+    _marker: PhantomData<fn() -> R>,
+}
+
+impl<R> Default for NonSendChangeEventsPlugin<R> {
+    fn default() -> Self {
+        NonSendChangeEventsPlugin {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<R: 'static> Plugin for NonSendChangeEventsPlugin<R> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<NonSendResourceChanged<R>>()
+            .add_systems(Last, watch_non_send_changes::<R>);
+    }
+}
+
+fn watch_non_send_changes<R: 'static>(
+    resource: Option<NonSend<R>>,
+    mut existed: Local<bool>,
+    mut events: EventWriter<NonSendResourceChanged<R>>,
+) {
+    let change = match &resource {
+        Some(resource) if resource.is_added() || !*existed => Some(NonSendChange::Added),
+        Some(resource) if resource.is_changed() => Some(NonSendChange::Changed),
+        None if *existed => Some(NonSendChange::Removed),
+        _ => None,
+    };
+
+    *existed = resource.is_some();
+
+    if let Some(change) = change {
+        events.send(NonSendResourceChanged {
+            change,
+            _marker: PhantomData,
+        });
+    }
+}
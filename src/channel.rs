@@ -0,0 +1,105 @@
+use std::sync::{mpsc::Receiver, Mutex};
+
+use bevy_app::{App, Plugin, PreUpdate};
+use bevy_ecs::system::{NonSendMut, ResMut, Resource};
+use bevy_utils::synccell::SyncCell;
+
+type ApplyFn<R, T> = dyn Fn(&mut R, T) + Send + Sync;
+
+/// A [`Plugin`] that drains a [`Receiver<T>`] every frame, applying each item to the non-[`Send`] resource `R` on the main thread.
+///
+/// Items are applied in [`PreUpdate`], in the order they were sent. While `R` does not exist, items are left in the channel until it does. This is meant for background threads that produce updates, such as configuration deltas, for a non-[`Send`] object they cannot access.
+///
+/// ```
+/// # use std::sync::mpsc;
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::NonSendChannelPlugin;
+/// #
+/// struct AudioDevice {
+///     volume: f32,
+///     _handle: *const u8,
+/// }
+///
+/// enum AudioConfig {
+///     SetVolume(f32),
+/// }
+///
+/// let (sender, receiver) = mpsc::channel();
+///
+/// let mut app = App::new();
+///
+/// app.insert_non_send_resource(AudioDevice { volume: 1.0, _handle: std::ptr::null() })
+///     .add_plugins(NonSendChannelPlugin::new(receiver, |device: &mut AudioDevice, config| {
+///         match config {
+///             AudioConfig::SetVolume(volume) => device.volume = volume,
+///         }
+///     }));
+///
+/// // Usually sent from a background loader thread.
+/// std::thread::spawn(move || sender.send(AudioConfig::SetVolume(0.25)).unwrap())
+///     .join()
+///     .unwrap();
+///
+/// app.update();
+///
+/// assert_eq!(app.world.non_send_resource::<AudioDevice>().volume, 0.25);
+/// ```
+pub struct NonSendChannelPlugin<R: 'static, T: Send + 'static> {
+    receiver: Mutex<Option<Receiver<T>>>,
+    apply: Mutex<Option<Box<ApplyFn<R, T>>>>,
+}
+
+impl<R: 'static, T: Send + 'static> NonSendChannelPlugin<R, T> {
+    /// Creates a plugin that applies the items received from `receiver` to `R` with `apply`.
+    pub fn new(receiver: Receiver<T>, apply: impl Fn(&mut R, T) + Send + Sync + 'static) -> Self {
+        NonSendChannelPlugin {
+            receiver: Mutex::new(Some(receiver)),
+            apply: Mutex::new(Some(Box::new(apply))),
+        }
+    }
+}
+
+impl<R: 'static, T: Send + 'static> Plugin for NonSendChannelPlugin<R, T> {
+    fn build(&self, app: &mut App) {
+        let receiver = self
+            .receiver
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take()
+            .expect("`NonSendChannelPlugin` should only be built once.");
+
+        let apply = self
+            .apply
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take()
+            .expect("`NonSendChannelPlugin` should only be built once.");
+
+        app.insert_resource(ChannelReceiver::<R, T> {
+            receiver: SyncCell::new(receiver),
+            apply,
+        })
+        .add_systems(PreUpdate, apply_channel_items::<R, T>);
+    }
+}
+
+#[derive(Resource)]
+struct ChannelReceiver<R: 'static, T: Send + 'static> {
+    receiver: SyncCell<Receiver<T>>,
+    apply: Box<ApplyFn<R, T>>,
+}
+
+fn apply_channel_items<R: 'static, T: Send + 'static>(
+    mut channel: ResMut<ChannelReceiver<R, T>>,
+    resource: Option<NonSendMut<R>>,
+) {
+    let Some(mut resource) = resource else {
+        return;
+    };
+
+    let ChannelReceiver { receiver, apply } = &mut *channel;
+
+    for item in receiver.get().try_iter() {
+        apply(&mut *resource, item);
+    }
+}
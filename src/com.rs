@@ -0,0 +1,97 @@
+use std::{marker::PhantomData, panic::Location};
+
+use bevy_app::{App, Plugin};
+use bevy_ecs::{system::Command, world::World};
+use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED};
+
+use crate::{error, main_thread, tracking, NonSendCommandError, NonSendErrorContext};
+
+/// A non-[`Send`] resource that keeps a COM single-threaded apartment initialized on the main thread.
+///
+/// When this resource is dropped, `CoUninitialize` is called. Because it is non-[`Send`], it is always dropped on the thread that initialized the apartment. Declare the COM objects that must be released first with [`AppExt::drop_non_send_before`](crate::AppExt::drop_non_send_before), so that they are dropped before the apartment when the app exits.
+pub struct ComApartment {
+    // `CoUninitialize` must be called on the thread that called `CoInitializeEx`.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ComApartment {
+    fn initialize() -> windows::core::Result<Self> {
+        // SAFETY: The reserved parameter must be null. A successful call is balanced by the
+        // `CoUninitialize` in `Drop`, on the same thread.
+        unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) }.ok()?;
+
+        Ok(ComApartment {
+            _not_send: PhantomData,
+        })
+    }
+}
+
+impl Drop for ComApartment {
+    fn drop(&mut self) {
+        // SAFETY: `CoInitializeEx` succeeded on this thread when this value was created.
+        unsafe { CoUninitialize() };
+    }
+}
+
+/// A [`Plugin`] that initializes a COM single-threaded apartment on the main thread and stores it in a [`ComApartment`] resource.
+///
+/// The apartment is initialized at most once per [`World`], and is uninitialized when the [`ComApartment`] is dropped. Use [`AppExt::drop_non_send_before`](crate::AppExt::drop_non_send_before) to drop COM objects before it.
+///
+/// # Panics
+///
+/// Panics if COM was already initialized on the main thread with an incompatible concurrency model.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{AppExt, ComApartment, ComApartmentPlugin};
+/// #
+/// struct SpeechSynthesizer(*const u8);
+///
+/// App::new()
+///     .add_plugins(ComApartmentPlugin)
+///     // Release the COM object before `CoUninitialize` is called.
+///     .drop_non_send_before::<SpeechSynthesizer, ComApartment>()
+///     .run();
+/// ```
+pub struct ComApartmentPlugin;
+
+impl Plugin for ComApartmentPlugin {
+    fn build(&self, app: &mut App) {
+        if app.world.contains_non_send::<ComApartment>() {
+            return;
+        }
+
+        let apartment = ComApartment::initialize()
+            .unwrap_or_else(|error| panic!("Failed to initialize COM apartment: {error}"));
+
+        app.insert_non_send_resource(apartment);
+    }
+}
+
+/// Creates a [`Command`] that initializes a COM single-threaded apartment on the main thread, if the [`ComApartment`] resource does not exist yet.
+///
+/// If initialization fails, a [`NonSendCommandError::Com`] error is passed to the [`NonSendErrorHandler`](crate::NonSendErrorHandler). See [`ComApartmentPlugin`].
+#[track_caller]
+pub fn init_com_apartment() -> impl Command {
+    let location = Location::caller();
+    let context = NonSendErrorContext {
+        command: "init_com_apartment",
+        location,
+    };
+
+    main_thread::deferrable(move |world: &mut World| {
+        if world.contains_non_send::<ComApartment>() {
+            return;
+        }
+
+        match ComApartment::initialize() {
+            Ok(apartment) => {
+                world.insert_non_send_resource(apartment);
+                tracking::track::<ComApartment>(world, location, None);
+            }
+            Err(error) => {
+                error::handle_error(world, Err(NonSendCommandError::Com(error)), context);
+            }
+        }
+    })
+}
@@ -0,0 +1,361 @@
+use std::{any::TypeId, fmt, panic::Location, sync::Arc};
+
+use bevy_ecs::{
+    system::{Command, Resource},
+    world::{FromWorld, Mut, World},
+};
+
+#[cfg(feature = "profiler")]
+use crate::profiler;
+use crate::{main_thread, tracking, NonSendRecorder};
+
+pub(crate) type ReplayFn = Arc<dyn Fn(&mut World) + Send + Sync>;
+
+/// A type-erased operation on the [`World`], stored in a [`NonSendCommand`].
+pub struct NonSendApply {
+    apply: Box<dyn FnOnce(&mut World) + Send>,
+    // A copy of the operation that can be applied again, used by `NonSendRecorder`.
+    pub(crate) replay: Option<ReplayFn>,
+}
+
+impl NonSendApply {
+    fn new(command: impl Command) -> Self {
+        NonSendApply {
+            apply: Box::new(move |world| command.apply(world)),
+            replay: None,
+        }
+    }
+
+    fn replayable(replay: impl Fn(&mut World) + Send + Sync + 'static) -> Self {
+        let replay: ReplayFn = Arc::new(replay);
+        let apply = Arc::clone(&replay);
+
+        NonSendApply {
+            apply: Box::new(move |world| apply(world)),
+            replay: Some(replay),
+        }
+    }
+
+    /// Returns true if this operation can be replayed by a [`NonSendRecording`](crate::NonSendRecording).
+    pub fn is_replayable(&self) -> bool {
+        self.replay.is_some()
+    }
+}
+
+impl fmt::Debug for NonSendApply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NonSendApply").finish_non_exhaustive()
+    }
+}
+
+/// A queued operation on a non-[`Send`] resource that can be inspected before it is applied.
+///
+/// Unlike the closures returned by [`init_non_send_resource`](crate::init_non_send_resource) and friends, this describes which operation will be performed and on which type. The [`CommandsExt`](crate::CommandsExt) methods that initialize, insert, or remove a single resource type queue a [`NonSendCommand`], including the labeled, double-buffered, fallback, and builder variants.
+///
+/// The following commands do not, because they do not target a single resource type or do not create nor destroy one:
+///
+/// - [`save_non_send_resources`](crate::save_non_send_resources) and [`load_non_send_resources`](crate::load_non_send_resources), which operate on every registered type.
+/// - `insert_non_send_reflect`, which only knows the [`TypeId`] of the resource.
+/// - [`wrap_non_send_resource`](crate::wrap_non_send_resource), [`unwrap_non_send_resource`](crate::unwrap_non_send_resource), and [`swap_non_send_buffers`](crate::swap_non_send_buffers), which move an existing resource.
+/// - [`ffi_init_once`](crate::ffi_init_once), [`ffi_shutdown`](crate::ffi_shutdown), and `init_com_apartment`, which do not insert a resource.
+/// - `restore_non_send`, which is called directly on the [`World`] instead of being queued.
+///
+/// Before a [`NonSendCommand`] is applied, it is passed to the [`NonSendCommandInspector`], if one exists.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::NonSendCommand;
+/// #
+/// struct MyNonSend(*const u8);
+///
+/// fn create_my_non_send(mut commands: Commands) {
+///     let command = NonSendCommand::insert(|| MyNonSend(std::ptr::null()));
+///
+///     if let NonSendCommand::Insert { type_name, .. } = &command {
+///         println!("Inserting {type_name}.");
+///     }
+///
+///     commands.add(command);
+/// }
+/// #
+/// # App::new()
+/// #     .add_systems(Startup, (create_my_non_send, check).chain())
+/// #     .run();
+/// #
+/// # fn check(my_non_send: NonSend<MyNonSend>) {
+/// #     assert!(my_non_send.0.is_null());
+/// # }
+/// ```
+#[derive(Debug)]
+pub enum NonSendCommand {
+    /// Initializes a resource using [`FromWorld`]. See [`init_non_send_resource`](crate::init_non_send_resource).
+    Init {
+        /// The type name of the resource.
+        type_name: &'static str,
+        /// The [`TypeId`] of the resource.
+        type_id: TypeId,
+        /// The location of the code that created the command.
+        location: &'static Location<'static>,
+        /// The operation that will be applied.
+        apply: NonSendApply,
+    },
+
+    /// Inserts a resource created by a closure. See [`insert_non_send_resource`](crate::insert_non_send_resource).
+    Insert {
+        /// The type name of the resource.
+        type_name: &'static str,
+        /// The [`TypeId`] of the resource.
+        type_id: TypeId,
+        /// The location of the code that created the command.
+        location: &'static Location<'static>,
+        /// The operation that will be applied.
+        apply: NonSendApply,
+    },
+
+    /// Removes a resource. See [`remove_non_send_resource`](crate::remove_non_send_resource).
+    Remove {
+        /// The type name of the resource.
+        type_name: &'static str,
+        /// The [`TypeId`] of the resource.
+        type_id: TypeId,
+        /// The location of the code that created the command.
+        location: &'static Location<'static>,
+        /// The operation that will be applied.
+        apply: NonSendApply,
+    },
+}
+
+impl NonSendCommand {
+    /// Wraps one of this crate's commands operating on `R`, so that it can be inspected.
+    pub(crate) fn new<R: 'static>(
+        kind: NonSendCommandKind,
+        location: &'static Location<'static>,
+        command: impl Command,
+    ) -> Self {
+        let type_name = std::any::type_name::<R>();
+        let type_id = TypeId::of::<R>();
+        let apply = NonSendApply::new(command);
+
+        match kind {
+            NonSendCommandKind::Init => NonSendCommand::Init {
+                type_name,
+                type_id,
+                location,
+                apply,
+            },
+            NonSendCommandKind::Insert => NonSendCommand::Insert {
+                type_name,
+                type_id,
+                location,
+                apply,
+            },
+            NonSendCommandKind::Remove => NonSendCommand::Remove {
+                type_name,
+                type_id,
+                location,
+                apply,
+            },
+        }
+    }
+
+    /// Creates a [`NonSendCommand::Init`] for `R`.
+    #[track_caller]
+    pub fn init<R: FromWorld + 'static>() -> Self {
+        let location = Location::caller();
+
+        NonSendCommand::Init {
+            type_name: std::any::type_name::<R>(),
+            type_id: TypeId::of::<R>(),
+            location,
+            apply: NonSendApply::replayable(move |world| tracking::init::<R>(world, location)),
+        }
+    }
+
+    /// Creates a [`NonSendCommand::Insert`] for the resource returned by `func`.
+    #[track_caller]
+    pub fn insert<F, R>(func: F) -> Self
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static,
+    {
+        let location = Location::caller();
+
+        NonSendCommand::new::<R>(
+            NonSendCommandKind::Insert,
+            location,
+            move |world: &mut World| {
+                world.insert_non_send_resource((func)());
+                tracking::track::<R>(world, location, None);
+            },
+        )
+    }
+
+    /// Creates a [`NonSendCommand::Insert`] for the resource returned by `func`, which can be replayed by a [`NonSendRecording`](crate::NonSendRecording).
+    ///
+    /// Unlike [`NonSendCommand::insert`], `func` may be called more than once.
+    #[track_caller]
+    pub fn insert_replayable<F, R>(func: F) -> Self
+    where
+        F: Fn() -> R + Send + Sync + 'static,
+        R: 'static,
+    {
+        let location = Location::caller();
+
+        NonSendCommand::Insert {
+            type_name: std::any::type_name::<R>(),
+            type_id: TypeId::of::<R>(),
+            location,
+            apply: NonSendApply::replayable(move |world| {
+                world.insert_non_send_resource(func());
+                tracking::track::<R>(world, location, None);
+            }),
+        }
+    }
+
+    /// Creates a [`NonSendCommand::Remove`] for `R`.
+    #[track_caller]
+    pub fn remove<R: 'static>() -> Self {
+        NonSendCommand::Remove {
+            type_name: std::any::type_name::<R>(),
+            type_id: TypeId::of::<R>(),
+            location: Location::caller(),
+            apply: NonSendApply::replayable(|world| {
+                tracking::remove::<R>(world);
+            }),
+        }
+    }
+
+    /// Returns which kind of operation this command performs.
+    pub fn kind(&self) -> NonSendCommandKind {
+        match self {
+            NonSendCommand::Init { .. } => NonSendCommandKind::Init,
+            NonSendCommand::Insert { .. } => NonSendCommandKind::Insert,
+            NonSendCommand::Remove { .. } => NonSendCommandKind::Remove,
+        }
+    }
+
+    /// Returns the type name of the resource this command operates on.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            NonSendCommand::Init { type_name, .. }
+            | NonSendCommand::Insert { type_name, .. }
+            | NonSendCommand::Remove { type_name, .. } => type_name,
+        }
+    }
+
+    /// Returns the [`TypeId`] of the resource this command operates on.
+    pub fn type_id(&self) -> TypeId {
+        match self {
+            NonSendCommand::Init { type_id, .. }
+            | NonSendCommand::Insert { type_id, .. }
+            | NonSendCommand::Remove { type_id, .. } => *type_id,
+        }
+    }
+
+    /// Returns the location of the code that created this command.
+    pub fn location(&self) -> &'static Location<'static> {
+        match self {
+            NonSendCommand::Init { location, .. }
+            | NonSendCommand::Insert { location, .. }
+            | NonSendCommand::Remove { location, .. } => location,
+        }
+    }
+
+    /// Returns the operation that will be applied.
+    pub fn apply_fn(&self) -> &NonSendApply {
+        match self {
+            NonSendCommand::Init { apply, .. }
+            | NonSendCommand::Insert { apply, .. }
+            | NonSendCommand::Remove { apply, .. } => apply,
+        }
+    }
+
+    /// Applies this command to the [`World`] without passing it to the [`NonSendCommandInspector`] or [`NonSendRecorder`].
+    pub fn apply_uninspected(self, world: &mut World) {
+        let (NonSendCommand::Init { apply, .. }
+        | NonSendCommand::Insert { apply, .. }
+        | NonSendCommand::Remove { apply, .. }) = self;
+
+        (apply.apply)(world);
+    }
+}
+
+impl Command for NonSendCommand {
+    fn apply(self, world: &mut World) {
+        let location = self.location();
+
+        // Applied on the wrong thread with deferral enabled, so this will run again on the main thread.
+        let Some(command) = main_thread::defer_off_main_thread(world, self, location) else {
+            return;
+        };
+
+        let allowed = if world.contains_resource::<NonSendCommandInspector>() {
+            world.resource_scope(|_, mut inspector: Mut<NonSendCommandInspector>| {
+                (inspector.0)(&command)
+            })
+        } else {
+            true
+        };
+
+        if allowed {
+            if let Some(mut recorder) = world.get_resource_mut::<NonSendRecorder>() {
+                recorder.record(&command);
+            }
+
+            #[cfg(feature = "profiler")]
+            profiler::apply_profiled(command, world);
+
+            #[cfg(not(feature = "profiler"))]
+            command.apply_uninspected(world);
+        }
+    }
+}
+
+/// The kind of operation performed by a [`NonSendCommand`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum NonSendCommandKind {
+    /// See [`NonSendCommand::Init`].
+    Init,
+    /// See [`NonSendCommand::Insert`].
+    Insert,
+    /// See [`NonSendCommand::Remove`].
+    Remove,
+}
+
+/// A [`Resource`] that is called with every [`NonSendCommand`] before it is applied.
+///
+/// If the inspector returns false, the command is skipped. This can be used to log, count, or filter non-[`Send`] operations.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{CommandsExt, NonSendCommand, NonSendCommandInspector};
+/// #
+/// struct MyNonSend(*const u8);
+///
+/// fn create_my_non_send(mut commands: Commands) {
+///     commands.insert_non_send_resource(|| MyNonSend(std::ptr::null()));
+/// }
+///
+/// App::new()
+///     .insert_resource(NonSendCommandInspector::new(|command| {
+///         println!("Applying {} from {}.", command.type_name(), command.location());
+///
+///         // Skip all insertions.
+///         !matches!(command, NonSendCommand::Insert { .. })
+///     }))
+///     .add_systems(Startup, (create_my_non_send, check).chain())
+///     .run();
+/// #
+/// # fn check(my_non_send: Option<NonSend<MyNonSend>>) {
+/// #     assert!(my_non_send.is_none());
+/// # }
+/// ```
+#[derive(Resource)]
+pub struct NonSendCommandInspector(Box<dyn FnMut(&NonSendCommand) -> bool + Send + Sync>);
+
+impl NonSendCommandInspector {
+    /// Creates a new inspector from a closure.
+    pub fn new(inspector: impl FnMut(&NonSendCommand) -> bool + Send + Sync + 'static) -> Self {
+        NonSendCommandInspector(Box::new(inspector))
+    }
+}
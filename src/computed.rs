@@ -0,0 +1,65 @@
+use std::panic::Location;
+
+use bevy_app::{App, StateTransition};
+use bevy_ecs::{
+    schedule::{apply_state_transition, IntoSystemConfigs, State, States},
+    system::Resource,
+    world::{Mut, World},
+};
+
+use crate::tracking;
+
+type ComputeFn<S, R> = dyn Fn(&S) -> Option<R> + Send + Sync;
+
+/// The factory of a non-[`Send`] resource computed from the state `S`, and the state it was last computed for.
+#[derive(Resource)]
+struct ComputedNonSend<S: States, R: 'static> {
+    compute: Box<ComputeFn<S, R>>,
+    location: &'static Location<'static>,
+    computed_for: Option<S>,
+}
+
+/// Registers `R` to be recomputed from `S` whenever the state changes. See `AppExt::add_computed_non_send`.
+pub(crate) fn add_computed_non_send<S, R>(
+    app: &mut App,
+    compute: impl Fn(&S) -> Option<R> + Send + Sync + 'static,
+    location: &'static Location<'static>,
+) where
+    S: States,
+    R: 'static,
+{
+    app.insert_resource(ComputedNonSend::<S, R> {
+        compute: Box::new(compute),
+        location,
+        computed_for: None,
+    })
+    .add_systems(
+        StateTransition,
+        update_computed_non_send::<S, R>.after(apply_state_transition::<S>),
+    );
+}
+
+fn update_computed_non_send<S: States, R: 'static>(world: &mut World) {
+    let Some(state) = world
+        .get_resource::<State<S>>()
+        .map(|state| state.get().clone())
+    else {
+        return;
+    };
+
+    world.resource_scope(|world, mut computed: Mut<ComputedNonSend<S, R>>| {
+        if computed.computed_for.as_ref() == Some(&state) {
+            return;
+        }
+
+        // Tear down the resource of the previous state before constructing the next one.
+        tracking::remove::<R>(world);
+
+        if let Some(resource) = (computed.compute)(&state) {
+            world.insert_non_send_resource(resource);
+            tracking::track::<R>(world, computed.location, None);
+        }
+
+        computed.computed_for = Some(state);
+    });
+}
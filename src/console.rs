@@ -0,0 +1,180 @@
+use std::fmt::Write;
+
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::{
+    event::{Event, ManualEventReader},
+    reflect::AppTypeRegistry,
+    system::Local,
+    world::World,
+};
+
+use crate::{
+    reinit_tracked_non_send, remove_tracked_non_send, NonSendTracker, NonSendTrackingPlugin,
+    TrackedNonSend,
+};
+
+/// An [`Event`] carrying a line typed into a developer console, to be handled by [`NonSendConsolePlugin`].
+///
+/// Lines that do not start with `nonsend` are ignored, so every line of a console may be forwarded.
+#[derive(Event, Clone, Debug)]
+pub struct NonSendConsoleCommand(pub String);
+
+/// An [`Event`] carrying the output of a [`NonSendConsoleCommand`], to be printed by the developer console.
+#[derive(Event, Clone, Debug)]
+pub struct NonSendConsoleOutput(pub String);
+
+/// A [`Plugin`] that handles developer console commands for inspecting and resetting non-[`Send`] resources in a running app.
+///
+/// Commands are sent as [`NonSendConsoleCommand`] events and answered with [`NonSendConsoleOutput`] events in [`Update`], so any console can be wired to them. This crate does not register a command with `bevy_console` itself, so its input must be forwarded as [`NonSendConsoleCommand`] events. See [`run_non_send_console_command`] for the supported commands. This adds [`NonSendTrackingPlugin`] if it was not added yet.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{CommandsExt, NonSendConsoleCommand, NonSendConsoleOutput, NonSendConsolePlugin};
+/// #
+/// #[derive(Reflect, Default)]
+/// struct AudioDevice(u32);
+///
+/// let mut app = App::new();
+///
+/// app.add_plugins(NonSendConsolePlugin)
+///     .register_type::<AudioDevice>()
+///     .add_systems(Startup, |mut commands: Commands| commands.init_non_send_resource::<AudioDevice>());
+///
+/// app.update();
+///
+/// // Usually forwarded from the developer console.
+/// app.world.send_event(NonSendConsoleCommand("nonsend reinit AudioDevice".to_string()));
+/// app.update();
+///
+/// let output = app.world.resource_mut::<Events<NonSendConsoleOutput>>().drain().next().unwrap();
+/// assert!(output.0.starts_with("Re-initialized"));
+/// ```
+pub struct NonSendConsolePlugin;
+
+impl Plugin for NonSendConsolePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<NonSendTrackingPlugin>() {
+            app.add_plugins(NonSendTrackingPlugin);
+        }
+
+        app.add_event::<NonSendConsoleCommand>()
+            .add_event::<NonSendConsoleOutput>()
+            .add_systems(Update, handle_console_commands);
+    }
+}
+
+/// Runs a single developer console command, returning its output.
+///
+/// Returns [`None`] if the line does not start with `nonsend`. The supported commands are:
+///
+/// - `nonsend list`: Lists the tracked non-[`Send`] resources and where they were inserted.
+/// - `nonsend remove <type>`: Removes a tracked resource. See [`remove_tracked_non_send`].
+/// - `nonsend reinit <type>`: Removes and re-initializes a tracked resource. See [`reinit_tracked_non_send`].
+///
+/// Types are given by their [`TypePath`](bevy_reflect::TypePath), such as `my_game::audio::AudioDevice`, or by their short type path, such as `AudioDevice`, if it is not ambiguous. Types are resolved through the [`AppTypeRegistry`], so they must be registered with `App::register_type`. Only resources recorded by the [`NonSendTracker`] are known to these commands.
+pub fn run_non_send_console_command(world: &mut World, line: &str) -> Option<String> {
+    let mut args = line.split_whitespace();
+
+    if args.next() != Some("nonsend") {
+        return None;
+    }
+
+    let output = match (args.next(), args.next(), args.next()) {
+        (Some("list"), None, _) => list(world),
+        (Some("remove"), Some(type_path), None) => match find(world, type_path) {
+            Ok(tracked) if remove_tracked_non_send(world, tracked.type_id()) => {
+                format!("Removed `{}`.", tracked.type_name())
+            }
+            Ok(tracked) => format!("`{}` is no longer tracked.", tracked.type_name()),
+            Err(error) => error,
+        },
+        (Some("reinit"), Some(type_path), None) => match find(world, type_path) {
+            Ok(tracked) if reinit_tracked_non_send(world, tracked.type_id()) => {
+                format!("Re-initialized `{}`.", tracked.type_name())
+            }
+            Ok(tracked) => format!(
+                "`{}` cannot be re-initialized, because it was not inserted with `init_non_send_resource`.",
+                tracked.type_name(),
+            ),
+            Err(error) => error,
+        },
+        _ => "Usage: nonsend list | nonsend remove <type> | nonsend reinit <type>".to_string(),
+    };
+
+    Some(output)
+}
+
+fn list(world: &World) -> String {
+    let Some(tracker) = world.get_resource::<NonSendTracker>() else {
+        return "Tracking is not enabled. Did you add `NonSendTrackingPlugin`?".to_string();
+    };
+
+    let mut tracked: Vec<_> = tracker.iter().collect();
+    tracked.sort_by_key(|tracked| tracked.type_name());
+
+    let mut output = format!("{} non-send resources:", tracked.len());
+
+    for tracked in tracked {
+        let _ = write!(
+            output,
+            "\n  {} (inserted at {}, {:.1?} ago)",
+            tracked.type_name(),
+            tracked.location(),
+            tracked.age(),
+        );
+    }
+
+    output
+}
+
+/// Finds the tracked resource with the given [`TypePath`](bevy_reflect::TypePath), or with the given short type path if only one matches.
+///
+/// Types are resolved through the [`AppTypeRegistry`]. Tracked types that are not registered can only be found by their full type name.
+fn find(world: &World, type_path: &str) -> Result<TrackedNonSend, String> {
+    let Some(tracker) = world.get_resource::<NonSendTracker>() else {
+        return Err("Tracking is not enabled. Did you add `NonSendTrackingPlugin`?".to_string());
+    };
+
+    let registry = world
+        .get_resource::<AppTypeRegistry>()
+        .map(|registry| registry.read());
+
+    let mut matches = tracker.iter().filter(|tracked| {
+        match registry
+            .as_ref()
+            .and_then(|registry| registry.get(tracked.type_id()))
+        {
+            Some(registration) => {
+                let table = registration.type_info().type_path_table();
+                table.path() == type_path || table.short_path() == type_path
+            }
+            None => tracked.type_name() == type_path,
+        }
+    });
+
+    match (matches.next(), matches.next()) {
+        (Some(tracked), None) => Ok(tracked.clone()),
+        (Some(_), Some(_)) => Err(format!(
+            "`{type_path}` is ambiguous, use the full type path instead."
+        )),
+        (None, _) => Err(format!(
+            "`{type_path}` is not a tracked non-send resource. Did you register it with `App::register_type`?"
+        )),
+    }
+}
+
+fn handle_console_commands(
+    world: &mut World,
+    mut reader: Local<ManualEventReader<NonSendConsoleCommand>>,
+) {
+    let lines: Vec<String> = reader
+        .read(world.resource())
+        .map(|command| command.0.clone())
+        .collect();
+
+    for line in lines {
+        if let Some(output) = run_non_send_console_command(world, &line) {
+            world.send_event(NonSendConsoleOutput(output));
+        }
+    }
+}
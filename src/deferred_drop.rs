@@ -0,0 +1,155 @@
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    panic::Location,
+};
+
+use bevy_ecs::{
+    schedule::{InternedScheduleLabel, ScheduleLabel, Schedules},
+    system::Command,
+    world::World,
+};
+use bevy_utils::tracing::warn;
+
+use crate::{tracking, NonSendCommand, NonSendCommandKind};
+
+/// Non-[`Send`] resources that have been removed, but not yet dropped.
+#[derive(Default)]
+pub(crate) struct DeferredDrops {
+    pending: HashMap<InternedScheduleLabel, Vec<Box<dyn Any>>>,
+    // Schedules that contain a `drop_deferred` system.
+    registered: HashSet<InternedScheduleLabel>,
+}
+
+/// Creates a [`Command`] for removing a non-[`Send`] resource from the [`World`] immediately, but dropping it later when the given schedule runs.
+///
+/// The resource can no longer be accessed once this command is applied, but its [`Drop`] implementation is only run on the main thread during the next run of `label`. This lets work that still references the resource, such as GPU fences or native callbacks, complete first.
+///
+/// If `label` does not exist yet, or is currently running, it must be registered with [`AppExt::add_non_send_drop_point`](crate::AppExt::add_non_send_drop_point). Otherwise the resource is kept alive until the [`World`] is dropped.
+///
+/// ```
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{remove_non_send_resource_deferred, AppExt};
+/// #
+/// static DROPPED: AtomicBool = AtomicBool::new(false);
+///
+/// struct GpuBuffer(*const u8);
+///
+/// impl Drop for GpuBuffer {
+///     fn drop(&mut self) {
+///         DROPPED.store(true, Ordering::Relaxed);
+///     }
+/// }
+///
+/// fn release_buffer(mut commands: Commands) {
+///     commands.add(remove_non_send_resource_deferred::<GpuBuffer>(Last));
+/// }
+///
+/// fn check_detached(buffer: Option<NonSend<GpuBuffer>>) {
+///     // The buffer can no longer be accessed, but it has not been dropped yet.
+///     assert!(buffer.is_none());
+///     assert!(!DROPPED.load(Ordering::Relaxed));
+/// }
+///
+/// let mut app = App::new();
+///
+/// app.insert_non_send_resource(GpuBuffer(std::ptr::null()))
+///     // `Last` may not have any systems yet, so make sure it exists.
+///     .add_non_send_drop_point(Last)
+///     .add_systems(Update, release_buffer)
+///     .add_systems(PostUpdate, check_detached);
+///
+/// app.update();
+///
+/// assert!(DROPPED.load(Ordering::Relaxed));
+/// ```
+#[track_caller]
+pub fn remove_non_send_resource_deferred<R: 'static>(label: impl ScheduleLabel) -> impl Command {
+    let label = label.intern();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Remove,
+        Location::caller(),
+        move |world: &mut World| {
+            let Some(resource) = world.remove_non_send_resource::<R>() else {
+                return;
+            };
+
+            tracking::untrack::<R>(world);
+            defer_drop(world, label, resource);
+            crate::builder::run_removal_hooks::<R>(world, None);
+        },
+    )
+}
+
+/// Keeps `resource` alive until `label` runs, and then drops it.
+pub(crate) fn defer_drop<R: 'static>(world: &mut World, label: InternedScheduleLabel, resource: R) {
+    if !register(world, label) {
+        warn!(
+            "Schedule {label:?} does not exist or is currently running, so `{}` will not be dropped until it is added with `AppExt::add_non_send_drop_point`.",
+            std::any::type_name::<R>()
+        );
+    }
+
+    world
+        .non_send_resource_mut::<DeferredDrops>()
+        .pending
+        .entry(label)
+        .or_default()
+        .push(Box::new(resource));
+}
+
+/// Adds a system that drops deferred resources to `label`, if it is not already registered.
+///
+/// Returns false if the schedule could not be found.
+fn register(world: &mut World, label: InternedScheduleLabel) -> bool {
+    if !world.contains_non_send::<DeferredDrops>() {
+        world.insert_non_send_resource(DeferredDrops::default());
+    }
+
+    if world
+        .non_send_resource::<DeferredDrops>()
+        .registered
+        .contains(&label)
+    {
+        return true;
+    }
+
+    let Some(schedule) = world
+        .get_resource_mut::<Schedules>()
+        .and_then(|schedules| schedules.into_inner().get_mut(label))
+    else {
+        return false;
+    };
+
+    schedule.add_systems(drop_deferred(label));
+    mark_registered(world, label);
+
+    true
+}
+
+/// Records that `label` contains a `drop_deferred` system.
+///
+/// Returns false if it was already registered.
+pub(crate) fn mark_registered(world: &mut World, label: InternedScheduleLabel) -> bool {
+    if !world.contains_non_send::<DeferredDrops>() {
+        world.insert_non_send_resource(DeferredDrops::default());
+    }
+
+    world
+        .non_send_resource_mut::<DeferredDrops>()
+        .registered
+        .insert(label)
+}
+
+/// Creates an exclusive system that drops all resources deferred to `label`.
+pub(crate) fn drop_deferred(label: InternedScheduleLabel) -> impl FnMut(&mut World) {
+    move |world: &mut World| {
+        let pending = world
+            .get_non_send_resource_mut::<DeferredDrops>()
+            .and_then(|mut drops| drops.pending.remove(&label));
+
+        drop(pending);
+    }
+}
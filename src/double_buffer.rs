@@ -0,0 +1,147 @@
+use std::panic::Location;
+
+use bevy_ecs::{system::Command, world::World};
+
+use crate::{main_thread, tracking, NonSendCommand, NonSendCommandKind};
+
+/// A double-buffered non-[`Send`] resource.
+///
+/// A producer system fills the back buffer with [`back_mut`](Self::back_mut) while consumers read the stable front buffer with [`front`](Self::front). Once the back buffer is complete, [`swap_non_send_buffers`] makes it the new front buffer. Swapping does not move either buffer, so it is cheap even for large values.
+///
+/// Insert it with [`insert_non_send_double_buffer`], then access it with [`NonSend<NonSendDoubleBuffer<R>>`](bevy_ecs::system::NonSend) and [`NonSendMut<NonSendDoubleBuffer<R>>`](bevy_ecs::system::NonSendMut).
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{insert_non_send_double_buffer, swap_non_send_buffers, NonSendDoubleBuffer};
+/// #
+/// #[derive(Default)]
+/// struct NativeMesh(*const u8, Vec<u32>);
+///
+/// fn create_mesh(mut commands: Commands) {
+///     commands.add(insert_non_send_double_buffer(NativeMesh::default));
+/// }
+///
+/// fn fill_mesh(mut mesh: NonSendMut<NonSendDoubleBuffer<NativeMesh>>, mut commands: Commands) {
+///     mesh.back_mut().1.push(1);
+///     commands.add(swap_non_send_buffers::<NativeMesh>());
+/// }
+///
+/// fn read_mesh(mesh: NonSend<NonSendDoubleBuffer<NativeMesh>>) {
+///     assert_eq!(mesh.front().1, [1]);
+/// }
+/// #
+/// # App::new()
+/// #     .add_systems(Startup, (create_mesh, fill_mesh, read_mesh).chain())
+/// #     .run();
+/// ```
+pub struct NonSendDoubleBuffer<R> {
+    buffers: [R; 2],
+    front: usize,
+}
+
+impl<R> NonSendDoubleBuffer<R> {
+    /// Creates a double buffer from its initial front and back buffers.
+    ///
+    /// This is useful when the buffers cannot be created by the same function, otherwise see [`insert_non_send_double_buffer`].
+    ///
+    /// ```
+    /// # use bevy_command_non_send::NonSendDoubleBuffer;
+    /// #
+    /// let buffer = NonSendDoubleBuffer::new(vec![1, 2], Vec::new());
+    ///
+    /// assert_eq!(buffer.front(), &[1, 2]);
+    /// assert!(buffer.back().is_empty());
+    /// ```
+    pub fn new(front: R, back: R) -> Self {
+        NonSendDoubleBuffer {
+            buffers: [front, back],
+            front: 0,
+        }
+    }
+
+    /// Returns the front buffer, which is read by consumers.
+    pub fn front(&self) -> &R {
+        &self.buffers[self.front]
+    }
+
+    /// Returns the back buffer, which is written by the producer.
+    pub fn back(&self) -> &R {
+        &self.buffers[1 - self.front]
+    }
+
+    /// Returns the back buffer mutably.
+    pub fn back_mut(&mut self) -> &mut R {
+        &mut self.buffers[1 - self.front]
+    }
+
+    /// Swaps the front and back buffers.
+    ///
+    /// Within systems, prefer [`swap_non_send_buffers`], which can be queued together with the commands that finish the back buffer.
+    ///
+    /// ```
+    /// # use bevy_command_non_send::NonSendDoubleBuffer;
+    /// #
+    /// let mut buffer = NonSendDoubleBuffer::new("front", "back");
+    ///
+    /// buffer.swap();
+    ///
+    /// assert_eq!(*buffer.front(), "back");
+    /// assert_eq!(*buffer.back(), "front");
+    /// ```
+    pub fn swap(&mut self) {
+        self.front = 1 - self.front;
+    }
+
+    /// Returns the front and back buffers, in that order.
+    ///
+    /// ```
+    /// # use bevy_command_non_send::NonSendDoubleBuffer;
+    /// #
+    /// let mut buffer = NonSendDoubleBuffer::new(1, 2);
+    /// buffer.swap();
+    ///
+    /// assert_eq!(buffer.into_inner(), (2, 1));
+    /// ```
+    pub fn into_inner(self) -> (R, R) {
+        let [first, second] = self.buffers;
+
+        if self.front == 0 {
+            (first, second)
+        } else {
+            (second, first)
+        }
+    }
+}
+
+/// Creates a [`Command`] for inserting a [`NonSendDoubleBuffer<R>`] in the [`World`].
+///
+/// `func` is called twice on the main thread, once for each buffer. See [`NonSendDoubleBuffer`] for an example.
+#[track_caller]
+pub fn insert_non_send_double_buffer<F, R>(func: F) -> impl Command
+where
+    F: Fn() -> R + Send + 'static,
+    R: 'static,
+{
+    let location = Location::caller();
+
+    NonSendCommand::new::<NonSendDoubleBuffer<R>>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| {
+            world.insert_non_send_resource(NonSendDoubleBuffer::new(func(), func()));
+            tracking::track::<NonSendDoubleBuffer<R>>(world, location, None);
+        },
+    )
+}
+
+/// Creates a [`Command`] that swaps the front and back buffers of a [`NonSendDoubleBuffer<R>`].
+///
+/// Does nothing if the double buffer does not exist. See [`NonSendDoubleBuffer`] for an example.
+#[track_caller]
+pub fn swap_non_send_buffers<R: 'static>() -> impl Command {
+    main_thread::deferrable(|world: &mut World| {
+        if let Some(mut buffer) = world.get_non_send_resource_mut::<NonSendDoubleBuffer<R>>() {
+            buffer.swap();
+        }
+    })
+}
@@ -0,0 +1,218 @@
+use std::{any::TypeId, collections::HashMap};
+
+use bevy_app::AppExit;
+use bevy_ecs::{event::Events, system::Resource, world::World};
+
+use crate::shutdown_ffi;
+
+/// A [`Resource`] describing the order in which non-[`Send`] resources must be dropped when the app exits.
+///
+/// Constraints are declared with [`AppExt::drop_non_send_before`](crate::AppExt::drop_non_send_before). When an [`AppExit`] event is sent, all resources with constraints are removed in an order that respects them, before the [`World`] itself is dropped.
+///
+/// You can also tear resources down manually with [`teardown_non_send_resources`].
+#[derive(Resource, Default)]
+pub struct NonSendDropOrder {
+    nodes: Vec<DropNode>,
+    indices: HashMap<TypeId, usize>,
+    // `(a, b)` means `a` must be dropped before `b`.
+    edges: Vec<(usize, usize)>,
+}
+
+struct DropNode {
+    type_name: &'static str,
+    remove: fn(&mut World),
+}
+
+impl NonSendDropOrder {
+    /// Declares that `A` must be dropped before `B`.
+    pub fn drop_before<A: 'static, B: 'static>(&mut self) {
+        let a = self.node::<A>();
+        let b = self.node::<B>();
+
+        if !self.edges.contains(&(a, b)) {
+            self.edges.push((a, b));
+        }
+    }
+
+    fn node<R: 'static>(&mut self) -> usize {
+        *self.indices.entry(TypeId::of::<R>()).or_insert_with(|| {
+            self.nodes.push(DropNode {
+                type_name: std::any::type_name::<R>(),
+                remove: |world| {
+                    crate::tracking::remove::<R>(world);
+                },
+            });
+
+            self.nodes.len() - 1
+        })
+    }
+
+    /// Returns the type names of all constrained resources in the order they will be dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the constraints contain a cycle.
+    pub fn order(&self) -> Vec<&'static str> {
+        self.sorted()
+            .into_iter()
+            .map(|i| self.nodes[i].type_name)
+            .collect()
+    }
+
+    /// Topologically sorts the nodes, preferring registration order when there is a choice.
+    fn sorted(&self) -> Vec<usize> {
+        let mut in_degree = vec![0; self.nodes.len()];
+
+        for &(_, b) in &self.edges {
+            in_degree[b] += 1;
+        }
+
+        let mut sorted = Vec::with_capacity(self.nodes.len());
+        let mut visited = vec![false; self.nodes.len()];
+
+        while sorted.len() < self.nodes.len() {
+            let Some(next) = (0..self.nodes.len()).find(|&i| !visited[i] && in_degree[i] == 0)
+            else {
+                let cycle: Vec<_> = (0..self.nodes.len())
+                    .filter(|&i| !visited[i])
+                    .map(|i| self.nodes[i].type_name)
+                    .collect();
+
+                panic!("Non-send drop order contains a cycle between: {cycle:?}");
+            };
+
+            visited[next] = true;
+            sorted.push(next);
+
+            for &(a, b) in &self.edges {
+                if a == next {
+                    in_degree[b] -= 1;
+                }
+            }
+        }
+
+        sorted
+    }
+}
+
+/// Removes all non-[`Send`] resources with drop order constraints from the [`World`], respecting [`NonSendDropOrder`].
+///
+/// Afterwards, libraries initialized with [`ffi_init_once`](crate::ffi_init_once) are shut down with [`shutdown_ffi`].
+///
+/// # Panics
+///
+/// Panics if the constraints contain a cycle.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{teardown_non_send_resources, AppExt, NonSendDropOrder};
+/// #
+/// struct AudioDevice(*const u8);
+///
+/// struct AudioStream(*const u8);
+///
+/// let mut app = App::new();
+///
+/// app.drop_non_send_before::<AudioStream, AudioDevice>()
+///     .insert_non_send_resource(AudioDevice(std::ptr::null()))
+///     .insert_non_send_resource(AudioStream(std::ptr::null()));
+///
+/// // `AudioStream` is dropped first, then `AudioDevice`.
+/// # assert_eq!(
+/// #     app.world.resource::<NonSendDropOrder>().order(),
+/// #     [std::any::type_name::<AudioStream>(), std::any::type_name::<AudioDevice>()],
+/// # );
+/// teardown_non_send_resources(&mut app.world);
+///
+/// assert!(!app.world.contains_non_send::<AudioDevice>());
+/// assert!(!app.world.contains_non_send::<AudioStream>());
+/// ```
+pub fn teardown_non_send_resources(world: &mut World) {
+    if let Some(drop_order) = world.get_resource::<NonSendDropOrder>() {
+        let removals: Vec<_> = drop_order
+            .sorted()
+            .into_iter()
+            .map(|i| drop_order.nodes[i].remove)
+            .collect();
+
+        for remove in removals {
+            remove(world);
+        }
+    }
+
+    shutdown_ffi(world);
+}
+
+/// Tears down non-[`Send`] resources once an [`AppExit`] event is sent.
+pub(crate) fn teardown_on_exit(world: &mut World) {
+    let exiting = world
+        .get_resource::<Events<AppExit>>()
+        .is_some_and(|events| !events.is_empty());
+
+    if exiting {
+        teardown_non_send_resources(world);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use super::*;
+
+    struct Device;
+
+    struct Stream;
+
+    struct Buffer;
+
+    #[test]
+    fn order_respects_constraints() {
+        let mut drop_order = NonSendDropOrder::default();
+
+        drop_order.drop_before::<Buffer, Device>();
+        drop_order.drop_before::<Stream, Buffer>();
+
+        assert_eq!(
+            drop_order.order(),
+            [
+                std::any::type_name::<Stream>(),
+                std::any::type_name::<Buffer>(),
+                std::any::type_name::<Device>(),
+            ],
+        );
+    }
+
+    #[test]
+    #[should_panic = "contains a cycle"]
+    fn cycle_panics() {
+        let mut drop_order = NonSendDropOrder::default();
+
+        drop_order.drop_before::<Device, Stream>();
+        drop_order.drop_before::<Stream, Buffer>();
+        drop_order.drop_before::<Buffer, Device>();
+
+        drop_order.order();
+    }
+
+    #[test]
+    fn cycle_panics_before_removing_anything() {
+        let mut world = World::new();
+        let mut drop_order = NonSendDropOrder::default();
+
+        drop_order.drop_before::<Device, Stream>();
+        drop_order.drop_before::<Stream, Device>();
+
+        world.insert_resource(drop_order);
+        world.insert_non_send_resource(Device);
+        world.insert_non_send_resource(Stream);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            teardown_non_send_resources(&mut world);
+        }));
+
+        assert!(result.is_err());
+        assert!(world.contains_non_send::<Device>());
+        assert!(world.contains_non_send::<Stream>());
+    }
+}
@@ -0,0 +1,347 @@
+use std::{any::TypeId, borrow::Cow};
+
+use bevy_ecs::{
+    archetype::ArchetypeComponentId,
+    change_detection::{MutUntyped, MAX_CHANGE_AGE},
+    component::{ComponentId, Tick},
+    ptr::Ptr,
+    query::Access,
+    system::System,
+    world::{unsafe_world_cell::UnsafeWorldCell, World, WorldId},
+};
+
+/// A builder for a system that accesses non-[`Send`] resources whose types are only known at runtime.
+///
+/// This is meant for scripting and modding layers that cannot name the types of the non-[`Send`] resources they access. The resulting [`DynamicNonSendSystem`] is always run on the main thread.
+///
+/// Bevy 0.13 does not expose the archetype component ids of non-[`Send`] resources, so the built system conservatively conflicts with every system that writes to the [`World`] (or every system at all, if it requests write access).
+///
+/// ```
+/// # use std::any::TypeId;
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::DynamicNonSendBuilder;
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// #
+/// # static RAN: AtomicBool = AtomicBool::new(false);
+/// #
+/// struct ScriptState(std::rc::Rc<u8>);
+///
+/// let mut app = App::new();
+///
+/// app.insert_non_send_resource(ScriptState(5.into()));
+///
+/// let system = DynamicNonSendBuilder::new("read_script_state")
+///     .read(TypeId::of::<ScriptState>())
+///     .build(|access| {
+///         let ptr = access.get(TypeId::of::<ScriptState>()).unwrap();
+///
+///         // SAFETY: The type registered under this `TypeId` is `ScriptState`.
+///         let state = unsafe { ptr.deref::<ScriptState>() };
+///
+///         assert_eq!(*state.0, 5);
+/// #       RAN.store(true, Ordering::Relaxed);
+///     });
+///
+/// app.add_systems(Update, system);
+/// app.update();
+/// # assert!(RAN.load(Ordering::Relaxed));
+/// ```
+pub struct DynamicNonSendBuilder {
+    name: Cow<'static, str>,
+    reads: Vec<TypeId>,
+    writes: Vec<TypeId>,
+}
+
+impl DynamicNonSendBuilder {
+    /// Creates a new builder for a system with the given name.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        DynamicNonSendBuilder {
+            name: name.into(),
+            reads: Vec::new(),
+            writes: Vec::new(),
+        }
+    }
+
+    /// Requests read-only access to the non-[`Send`] resource with the given [`TypeId`].
+    pub fn read(mut self, type_id: TypeId) -> Self {
+        self.reads.push(type_id);
+        self
+    }
+
+    /// Requests mutable access to the non-[`Send`] resource with the given [`TypeId`].
+    pub fn write(mut self, type_id: TypeId) -> Self {
+        self.writes.push(type_id);
+        self
+    }
+
+    /// Builds the system, which calls `func` with access to the requested resources every time it runs.
+    pub fn build<F>(self, func: F) -> DynamicNonSendSystem<F>
+    where
+        F: FnMut(DynamicNonSend) + Send + Sync + 'static,
+    {
+        DynamicNonSendSystem {
+            func,
+            name: self.name,
+            reads: self.reads,
+            writes: self.writes,
+            resources: Vec::new(),
+            component_access: Access::default(),
+            archetype_component_access: Access::default(),
+            last_run: Tick::new(0),
+            world_id: None,
+        }
+    }
+}
+
+/// A system built by [`DynamicNonSendBuilder`].
+pub struct DynamicNonSendSystem<F> {
+    func: F,
+    name: Cow<'static, str>,
+    reads: Vec<TypeId>,
+    writes: Vec<TypeId>,
+    resources: Vec<DynamicResource>,
+    component_access: Access<ComponentId>,
+    archetype_component_access: Access<ArchetypeComponentId>,
+    last_run: Tick,
+    world_id: Option<WorldId>,
+}
+
+impl<F> DynamicNonSendSystem<F> {
+    /// Asserts that the system is run on the [`World`] it was initialized with, like Bevy's own systems do.
+    ///
+    /// The [`ComponentId`]s of the requested resources are only valid for that [`World`].
+    fn validate_world(&self, world_id: WorldId) {
+        assert_eq!(
+            self.world_id,
+            Some(world_id),
+            "Encountered a mismatched World. System {} cannot be used with Worlds other than the one it was initialized with.",
+            self.name
+        );
+    }
+}
+
+#[derive(Clone, Copy)]
+struct DynamicResource {
+    type_id: TypeId,
+    component_id: ComponentId,
+    mutable: bool,
+}
+
+impl<F> System for DynamicNonSendSystem<F>
+where
+    F: FnMut(DynamicNonSend) + Send + Sync + 'static,
+{
+    type In = ();
+    type Out = ();
+
+    fn name(&self) -> Cow<'static, str> {
+        self.name.clone()
+    }
+
+    fn component_access(&self) -> &Access<ComponentId> {
+        &self.component_access
+    }
+
+    fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        &self.archetype_component_access
+    }
+
+    fn is_send(&self) -> bool {
+        false
+    }
+
+    fn is_exclusive(&self) -> bool {
+        false
+    }
+
+    fn has_deferred(&self) -> bool {
+        false
+    }
+
+    unsafe fn run_unsafe(&mut self, _input: (), world: UnsafeWorldCell) {
+        self.validate_world(world.id());
+
+        let this_run = world.increment_change_tick();
+
+        (self.func)(DynamicNonSend {
+            world,
+            resources: &self.resources,
+        });
+
+        self.last_run = this_run;
+    }
+
+    fn apply_deferred(&mut self, _world: &mut World) {}
+
+    fn initialize(&mut self, world: &mut World) {
+        if let Some(world_id) = self.world_id {
+            assert_eq!(
+                world_id,
+                world.id(),
+                "System {} was initialized with a different World than the one it was added to.",
+                self.name
+            );
+            return;
+        }
+
+        self.world_id = Some(world.id());
+
+        let requests = self
+            .reads
+            .iter()
+            .map(|&type_id| (type_id, false))
+            .chain(self.writes.iter().map(|&type_id| (type_id, true)));
+
+        for (type_id, mutable) in requests {
+            let component_id = world
+                .components()
+                .get_resource_id(type_id)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Non-send resource with {type_id:?} requested by {} must be registered before the system is initialized.",
+                        self.name
+                    )
+                });
+
+            assert!(
+                !self.component_access.has_write(component_id),
+                "Non-send resource with {type_id:?} in system {} conflicts with a previous mutable access.",
+                self.name
+            );
+
+            if mutable {
+                assert!(
+                    !self.component_access.has_read(component_id),
+                    "Non-send resource with {type_id:?} in system {} conflicts with a previous access.",
+                    self.name
+                );
+
+                self.component_access.add_write(component_id);
+            } else {
+                self.component_access.add_read(component_id);
+            }
+
+            self.resources.push(DynamicResource {
+                type_id,
+                component_id,
+                mutable,
+            });
+        }
+
+        if self.writes.is_empty() {
+            self.archetype_component_access.read_all();
+        } else {
+            self.archetype_component_access.write_all();
+        }
+    }
+
+    fn update_archetype_component_access(&mut self, world: UnsafeWorldCell) {
+        self.validate_world(world.id());
+    }
+
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        // Mirrors `Tick::check_tick`, which is private to `bevy_ecs`.
+        let age = change_tick.get().wrapping_sub(self.last_run.get());
+
+        if age > MAX_CHANGE_AGE {
+            self.last_run = Tick::new(change_tick.get().wrapping_sub(MAX_CHANGE_AGE));
+        }
+    }
+
+    fn get_last_run(&self) -> Tick {
+        self.last_run
+    }
+
+    fn set_last_run(&mut self, last_run: Tick) {
+        self.last_run = last_run;
+    }
+}
+
+/// Access to the non-[`Send`] resources requested by a [`DynamicNonSendSystem`].
+pub struct DynamicNonSend<'w> {
+    world: UnsafeWorldCell<'w>,
+    resources: &'w [DynamicResource],
+}
+
+impl DynamicNonSend<'_> {
+    fn find(&self, type_id: TypeId) -> Option<DynamicResource> {
+        self.resources
+            .iter()
+            .find(|resource| resource.type_id == type_id)
+            .copied()
+    }
+
+    /// Returns a pointer to the non-[`Send`] resource with the given [`TypeId`].
+    ///
+    /// Returns [`None`] if access to the resource was not requested or it does not exist.
+    pub fn get(&self, type_id: TypeId) -> Option<Ptr<'_>> {
+        let resource = self.find(type_id)?;
+
+        // SAFETY: Access to this resource was registered in `initialize`, and `&self` prevents
+        // `get_mut` from aliasing it.
+        unsafe {
+            self.world
+                .get_non_send_resource_by_id(resource.component_id)
+        }
+    }
+
+    /// Returns a mutable pointer to the non-[`Send`] resource with the given [`TypeId`].
+    ///
+    /// Returns [`None`] if mutable access to the resource was not requested or it does not exist.
+    pub fn get_mut(&mut self, type_id: TypeId) -> Option<MutUntyped<'_>> {
+        let resource = self.find(type_id).filter(|resource| resource.mutable)?;
+
+        // SAFETY: Mutable access to this resource was registered in `initialize`, and `&mut self`
+        // prevents any other reference from aliasing it.
+        unsafe {
+            self.world
+                .get_non_send_resource_mut_by_id(resource.component_id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ScriptState;
+
+    fn system() -> DynamicNonSendSystem<impl FnMut(DynamicNonSend) + Send + Sync> {
+        DynamicNonSendBuilder::new("read_script_state")
+            .read(TypeId::of::<ScriptState>())
+            .build(|_| {})
+    }
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.insert_non_send_resource(ScriptState);
+        world
+    }
+
+    #[test]
+    fn runs_on_initialized_world() {
+        let mut world = world();
+        let mut system = system();
+
+        system.initialize(&mut world);
+        system.run((), &mut world);
+    }
+
+    #[test]
+    #[should_panic = "mismatched World"]
+    fn run_on_other_world_panics() {
+        let mut system = system();
+
+        system.initialize(&mut world());
+        system.run((), &mut world());
+    }
+
+    #[test]
+    #[should_panic = "different World"]
+    fn initialize_on_other_world_panics() {
+        let mut system = system();
+
+        system.initialize(&mut world());
+        system.initialize(&mut world());
+    }
+}
@@ -0,0 +1,268 @@
+use std::{fmt, io, panic::Location};
+
+use bevy_ecs::{system::Resource, world::World};
+use bevy_utils::tracing::{error, warn};
+
+use crate::NonSendLabel;
+
+/// An error returned by one of this crate's fallible commands.
+///
+/// Errors are routed through the [`NonSendErrorHandler`] resource. If it does not exist, [`NonSendErrorHandler::PANIC`] is used.
+///
+/// The fallible commands are the `try_` variants of the core, catch-unwind, fallback, and labeled commands, such as [`try_insert_non_send_resource`](crate::try_insert_non_send_resource) and [`try_remove_non_send_labeled`](crate::try_remove_non_send_labeled), as well as [`NonSendBuilder::try_insert`](crate::NonSendBuilder::try_insert), the persistence and reflection commands, and the commands of optional integrations such as `libloading`. Their infallible counterparts replace existing resources, ignore missing ones, or report failures through events instead.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum NonSendCommandError {
+    /// The non-[`Send`] resource did not exist in the [`World`].
+    ResourceMissing {
+        /// The type name of the missing resource.
+        type_name: &'static str,
+    },
+
+    /// The non-[`Send`] resource already existed in the [`World`], and would have been replaced.
+    ResourceExists {
+        /// The type name of the existing resource.
+        type_name: &'static str,
+    },
+
+    /// No labeled instance of the non-[`Send`] type had the given label. See [`insert_non_send_labeled`](crate::insert_non_send_labeled).
+    LabelMissing {
+        /// The type name of the instance.
+        type_name: &'static str,
+
+        /// The label of the missing instance.
+        label: NonSendLabel,
+    },
+
+    /// A labeled instance of the non-[`Send`] type already had the given label, and would have been replaced.
+    LabelExists {
+        /// The type name of the instance.
+        type_name: &'static str,
+
+        /// The label of the existing instance.
+        label: NonSendLabel,
+    },
+
+    /// The factory creating the non-[`Send`] resource panicked.
+    FactoryPanicked {
+        /// The type name of the resource that was not created.
+        type_name: &'static str,
+
+        /// The panic message, if it was a string.
+        message: Option<String>,
+    },
+
+    /// Every factory creating the non-[`Send`] resource returned an error.
+    FactoryFailed {
+        /// The type name of the resource that was not created.
+        type_name: &'static str,
+
+        /// The error returned by the last factory.
+        message: String,
+    },
+
+    /// Saving or loading non-[`Send`] resources failed.
+    Io(io::Error),
+
+    /// The type was not registered in the `AppTypeRegistry`, or was missing `ReflectNonSend` type data.
+    NotRegistered {
+        /// The type path of the unregistered type.
+        type_path: String,
+    },
+
+    /// A reflected value could not be converted into its concrete type.
+    FromReflectFailed {
+        /// The type path of the reflected value.
+        type_path: String,
+    },
+
+    /// Commands were not applied on the operating system's main thread, which is required by platform UI frameworks. See [`is_os_main_thread`](crate::is_os_main_thread).
+    NotOsMainThread {
+        /// The type name of the resource that was not created.
+        type_name: &'static str,
+
+        /// The name of the thread that applied the command, if it has one.
+        thread_name: Option<String>,
+    },
+
+    /// Loading or unloading a dynamic library failed.
+    #[cfg(feature = "libloading")]
+    Library(libloading::Error),
+
+    /// Running Lua code failed.
+    #[cfg(feature = "mlua")]
+    Lua(mlua::Error),
+
+    /// Initializing COM failed.
+    #[cfg(all(windows, feature = "windows"))]
+    Com(windows::core::Error),
+}
+
+impl fmt::Display for NonSendCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NonSendCommandError::ResourceMissing { type_name } => {
+                write!(f, "non-send resource `{type_name}` does not exist")
+            }
+            NonSendCommandError::ResourceExists { type_name } => {
+                write!(f, "non-send resource `{type_name}` already exists")
+            }
+            NonSendCommandError::LabelMissing { type_name, label } => {
+                write!(
+                    f,
+                    "non-send resource `{type_name}` has no instance labeled {label:?}"
+                )
+            }
+            NonSendCommandError::LabelExists { type_name, label } => {
+                write!(
+                    f,
+                    "non-send resource `{type_name}` already has an instance labeled {label:?}"
+                )
+            }
+            NonSendCommandError::FactoryPanicked { type_name, message } => match message {
+                Some(message) => write!(f, "factory of `{type_name}` panicked: {message}"),
+                None => write!(f, "factory of `{type_name}` panicked"),
+            },
+            NonSendCommandError::FactoryFailed { type_name, message } => {
+                write!(f, "factory of `{type_name}` failed: {message}")
+            }
+            NonSendCommandError::Io(error) => {
+                write!(f, "failed to save or load non-send resources: {error}")
+            }
+            NonSendCommandError::NotRegistered { type_path } => {
+                write!(
+                    f,
+                    "`{type_path}` is not registered with `ReflectNonSend` type data, did you use `#[reflect(NonSend)]` and call `App::register_type()`?"
+                )
+            }
+            NonSendCommandError::FromReflectFailed { type_path } => {
+                write!(f, "could not convert reflected value into `{type_path}`")
+            }
+            NonSendCommandError::NotOsMainThread {
+                type_name,
+                thread_name,
+            } => {
+                write!(
+                    f,
+                    "cannot create `{type_name}` on thread {:?}, because it is not the OS main thread; platform UI frameworks such as AppKit and UIKit must be used from the thread the process started on, so run the `App` from `fn main` instead of a spawned thread, and use `harness = false` for tests",
+                    thread_name.as_deref().unwrap_or("<unnamed>"),
+                )
+            }
+            #[cfg(feature = "libloading")]
+            NonSendCommandError::Library(error) => write!(f, "dynamic library error: {error}"),
+            #[cfg(feature = "mlua")]
+            NonSendCommandError::Lua(error) => write!(f, "Lua error: {error}"),
+            #[cfg(all(windows, feature = "windows"))]
+            NonSendCommandError::Com(error) => write!(f, "COM error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for NonSendCommandError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NonSendCommandError::Io(error) => Some(error),
+            #[cfg(feature = "libloading")]
+            NonSendCommandError::Library(error) => Some(error),
+            #[cfg(feature = "mlua")]
+            NonSendCommandError::Lua(error) => Some(error),
+            #[cfg(all(windows, feature = "windows"))]
+            NonSendCommandError::Com(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for NonSendCommandError {
+    fn from(error: io::Error) -> Self {
+        NonSendCommandError::Io(error)
+    }
+}
+
+/// Information about the command that failed, passed to the [`NonSendErrorHandler`].
+#[derive(Clone, Copy, Debug)]
+pub struct NonSendErrorContext {
+    /// The name of the command that failed.
+    pub command: &'static str,
+
+    /// The location of the code that queued the command.
+    pub location: &'static Location<'static>,
+}
+
+/// A [`Resource`] that decides what happens when one of this crate's fallible commands fails.
+///
+/// The default handler is [`NonSendErrorHandler::PANIC`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{try_remove_non_send_resource, NonSendErrorHandler};
+/// #
+/// struct MyNonSend(*const u8);
+///
+/// fn remove_my_non_send(mut commands: Commands) {
+///     // `MyNonSend` does not exist, so this will call the error handler.
+///     commands.add(
+///         try_remove_non_send_resource::<MyNonSend>()
+///     );
+/// }
+/// #
+/// # App::new()
+/// #     .insert_resource(NonSendErrorHandler::WARN)
+/// #     .add_systems(Startup, remove_my_non_send)
+/// #     .run();
+/// ```
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct NonSendErrorHandler(pub fn(NonSendCommandError, NonSendErrorContext));
+
+impl NonSendErrorHandler {
+    /// Panics with the error.
+    pub const PANIC: Self = NonSendErrorHandler(|error, context| {
+        panic!(
+            "Command `{}` queued at {} failed: {error}",
+            context.command, context.location
+        )
+    });
+
+    /// Logs the error at the error level.
+    pub const ERROR: Self = NonSendErrorHandler(|error, context| {
+        error!(
+            "Command `{}` queued at {} failed: {error}",
+            context.command, context.location
+        )
+    });
+
+    /// Logs the error at the warning level.
+    pub const WARN: Self = NonSendErrorHandler(|error, context| {
+        warn!(
+            "Command `{}` queued at {} failed: {error}",
+            context.command, context.location
+        )
+    });
+
+    /// Ignores the error.
+    pub const IGNORE: Self = NonSendErrorHandler(|_, _| {});
+}
+
+impl Default for NonSendErrorHandler {
+    fn default() -> Self {
+        NonSendErrorHandler::PANIC
+    }
+}
+
+/// Passes `result`'s error, if any, to the [`World`]'s [`NonSendErrorHandler`].
+pub(crate) fn handle_error(
+    world: &World,
+    result: Result<(), NonSendCommandError>,
+    context: NonSendErrorContext,
+) {
+    if let Err(error) = result {
+        crate::ticket::report_failure();
+
+        let handler = world
+            .get_resource::<NonSendErrorHandler>()
+            .copied()
+            .unwrap_or_default();
+
+        (handler.0)(error, context);
+    }
+}
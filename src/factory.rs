@@ -0,0 +1,138 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+use bevy_ecs::{system::Resource, world::World};
+
+type FactoryFn = dyn Fn() -> Box<dyn Any> + Send + Sync;
+
+/// A [`Resource`] containing the factories used to create non-[`Send`] resources when they are initialized.
+///
+/// Libraries register a default factory for their types with [`AppExt::register_non_send_factory`](crate::AppExt::register_non_send_factory), and applications replace it with [`AppExt::override_non_send_factory`](crate::AppExt::override_non_send_factory). An override always takes precedence over the default factory, regardless of which was registered first.
+///
+/// [`init_non_send_resource`](crate::init_non_send_resource), [`CommandsExt::init_non_send_resource`](crate::CommandsExt::init_non_send_resource), [`NonSendLocal`](crate::NonSendLocal), and resources registered with `auto_non_send!` consult this registry first, and only fall back to [`FromWorld`](bevy_ecs::world::FromWorld) if no factory was registered.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{AppExt, CommandsExt};
+/// #
+/// struct Renderer {
+///     is_mock: bool,
+/// }
+///
+/// impl FromWorld for Renderer {
+///     fn from_world(_world: &mut World) -> Self {
+///         Renderer { is_mock: false }
+///     }
+/// }
+///
+/// fn init_renderer(mut commands: Commands) {
+///     commands.init_non_send_resource::<Renderer>();
+/// }
+///
+/// // In a test, replace the real renderer with a mock.
+/// App::new()
+///     .override_non_send_factory(|| Renderer { is_mock: true })
+///     .add_systems(Startup, (init_renderer, check).chain())
+///     .run();
+///
+/// fn check(renderer: NonSend<Renderer>) {
+///     assert!(renderer.is_mock);
+/// }
+/// ```
+#[derive(Resource, Default)]
+pub struct NonSendFactories {
+    entries: HashMap<TypeId, FactoryEntry>,
+}
+
+struct FactoryEntry {
+    type_name: &'static str,
+    create: Box<FactoryFn>,
+    overridden: bool,
+}
+
+impl NonSendFactories {
+    /// Registers the default factory for `R`, unless it has been overridden.
+    ///
+    /// Registering another default factory replaces the previous one.
+    pub fn register<R, F>(&mut self, factory: F)
+    where
+        R: 'static,
+        F: Fn() -> R + Send + Sync + 'static,
+    {
+        if self.is_overridden::<R>() {
+            return;
+        }
+
+        self.insert(factory, false);
+    }
+
+    /// Overrides the factory for `R`, taking precedence over any default factory.
+    ///
+    /// Overriding it again replaces the previous override.
+    pub fn override_factory<R, F>(&mut self, factory: F)
+    where
+        R: 'static,
+        F: Fn() -> R + Send + Sync + 'static,
+    {
+        self.insert(factory, true);
+    }
+
+    /// Returns true if a factory is registered for `R`.
+    pub fn contains<R: 'static>(&self) -> bool {
+        self.entries.contains_key(&TypeId::of::<R>())
+    }
+
+    /// Returns true if the factory for `R` was overridden.
+    pub fn is_overridden<R: 'static>(&self) -> bool {
+        self.entries
+            .get(&TypeId::of::<R>())
+            .is_some_and(|entry| entry.overridden)
+    }
+
+    /// Returns the type names of all types with a registered factory.
+    pub fn type_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.entries.values().map(|entry| entry.type_name)
+    }
+
+    fn insert<R, F>(&mut self, factory: F, overridden: bool)
+    where
+        R: 'static,
+        F: Fn() -> R + Send + Sync + 'static,
+    {
+        self.entries.insert(
+            TypeId::of::<R>(),
+            FactoryEntry {
+                type_name: std::any::type_name::<R>(),
+                create: Box::new(move || Box::new(factory())),
+                overridden,
+            },
+        );
+    }
+}
+
+/// Creates `R` using its registered factory, returning [`None`] if there is none.
+pub(crate) fn build<R: 'static>(world: &World) -> Option<R> {
+    let entry = world
+        .get_resource::<NonSendFactories>()?
+        .entries
+        .get(&TypeId::of::<R>())?;
+
+    let resource = (entry.create)()
+        .downcast::<R>()
+        .expect("factories are stored under the `TypeId` of the type they create");
+
+    Some(*resource)
+}
+
+/// Inserts `R` using its registered factory, returning false if there is none.
+pub(crate) fn create<R: 'static>(world: &mut World) -> bool {
+    let Some(resource) = build::<R>(world) else {
+        return false;
+    };
+
+    world.insert_non_send_resource(resource);
+
+    true
+}
@@ -0,0 +1,173 @@
+use std::{fmt, panic::Location};
+
+use bevy_ecs::{event::Event, system::Command, world::World};
+
+use crate::{
+    error, tracking, NonSendCommand, NonSendCommandError, NonSendCommandKind, NonSendErrorContext,
+};
+
+/// An [`Event`] sent when the primary factory passed to [`insert_non_send_resource_or_else`] fails and the fallback is used instead.
+///
+/// You must register this event with `App::add_event::<NonSendFallbackUsed<E>>` to receive it. If it is not registered, an error is logged instead.
+#[derive(Event, Clone, Debug)]
+pub struct NonSendFallbackUsed<E: Send + Sync + 'static> {
+    /// The type name of the resource that was created by the fallback.
+    pub type_name: &'static str,
+
+    /// The error returned by the primary factory.
+    pub error: E,
+
+    /// The location of the code that queued the command.
+    pub location: &'static Location<'static>,
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource in the [`World`], falling back to a second factory if the first one fails.
+///
+/// Both closures are executed on the main thread. If `primary` returns an error, the resource returned by `fallback` is inserted instead and a [`NonSendFallbackUsed`] event carrying the error is sent.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{insert_non_send_resource_or_else, NonSendFallbackUsed};
+/// #
+/// enum Renderer {
+///     Hardware(*const u8),
+///     Software,
+/// }
+///
+/// fn create_renderer(mut commands: Commands) {
+///     commands.add(
+///         insert_non_send_resource_or_else(
+///             || Err("Unsupported driver!"),
+///             || Renderer::Software,
+///         )
+///     );
+/// }
+///
+/// fn log_fallbacks(mut events: EventReader<NonSendFallbackUsed<&'static str>>) {
+///     for event in events.read() {
+///         eprintln!("Falling back for {}: {}", event.type_name, event.error);
+///     }
+/// }
+/// #
+/// # App::new()
+/// #     .add_event::<NonSendFallbackUsed<&'static str>>()
+/// #     .add_systems(Startup, (create_renderer, log_fallbacks, check).chain())
+/// #     .run();
+/// #
+/// # fn check(renderer: NonSend<Renderer>, events: Res<Events<NonSendFallbackUsed<&'static str>>>) {
+/// #     assert!(matches!(*renderer, Renderer::Software));
+/// #     assert_eq!(events.len(), 1);
+/// # }
+/// ```
+#[track_caller]
+pub fn insert_non_send_resource_or_else<P, F, R, E>(primary: P, fallback: F) -> impl Command
+where
+    P: FnOnce() -> Result<R, E> + Send + 'static,
+    F: FnOnce() -> R + Send + 'static,
+    R: 'static,
+    E: Send + Sync + 'static,
+{
+    let location = Location::caller();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| {
+            let resource = match primary() {
+                Ok(resource) => resource,
+                Err(error) => {
+                    let resource = fallback();
+
+                    world.send_event(NonSendFallbackUsed::<E> {
+                        type_name: std::any::type_name::<R>(),
+                        error,
+                        location,
+                    });
+
+                    resource
+                }
+            };
+
+            world.insert_non_send_resource(resource);
+            tracking::track::<R>(world, location, None);
+        },
+    )
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource in the [`World`], falling back to a second factory if the first one fails, and passing a [`NonSendCommandError::FactoryFailed`] error to the [`NonSendErrorHandler`](crate::NonSendErrorHandler) if both fail.
+///
+/// This behaves like [`insert_non_send_resource_or_else`], except that `fallback` may fail as well. The error of `primary` is still sent as a [`NonSendFallbackUsed`] event, and the error of `fallback` is formatted into the [`NonSendCommandError`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{try_insert_non_send_resource_or_else, NonSendErrorHandler};
+/// #
+/// struct Renderer(*const u8);
+///
+/// fn create_renderer(mut commands: Commands) {
+///     commands.add(
+///         try_insert_non_send_resource_or_else(
+///             || Err::<Renderer, _>("Unsupported driver!"),
+///             || Err("No software renderer!"),
+///         )
+///     );
+/// }
+/// #
+/// # App::new()
+/// #     .insert_resource(NonSendErrorHandler::WARN)
+/// #     .add_event::<bevy_command_non_send::NonSendFallbackUsed<&'static str>>()
+/// #     .add_systems(Startup, (create_renderer, check).chain())
+/// #     .run();
+/// #
+/// # fn check(renderer: Option<NonSend<Renderer>>) {
+/// #     assert!(renderer.is_none());
+/// # }
+/// ```
+#[track_caller]
+pub fn try_insert_non_send_resource_or_else<P, F, R, E>(primary: P, fallback: F) -> impl Command
+where
+    P: FnOnce() -> Result<R, E> + Send + 'static,
+    F: FnOnce() -> Result<R, E> + Send + 'static,
+    R: 'static,
+    E: fmt::Display + Send + Sync + 'static,
+{
+    let context = NonSendErrorContext {
+        command: "try_insert_non_send_resource_or_else",
+        location: Location::caller(),
+    };
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        context.location,
+        move |world: &mut World| {
+            let resource = match primary() {
+                Ok(resource) => Ok(resource),
+                Err(error) => {
+                    let resource = fallback();
+
+                    world.send_event(NonSendFallbackUsed::<E> {
+                        type_name: std::any::type_name::<R>(),
+                        error,
+                        location: context.location,
+                    });
+
+                    resource
+                }
+            };
+
+            let result = match resource {
+                Ok(resource) => {
+                    world.insert_non_send_resource(resource);
+                    tracking::track::<R>(world, context.location, None);
+                    Ok(())
+                }
+                Err(error) => Err(NonSendCommandError::FactoryFailed {
+                    type_name: std::any::type_name::<R>(),
+                    message: error.to_string(),
+                }),
+            };
+
+            error::handle_error(world, result, context);
+        },
+    )
+}
@@ -0,0 +1,150 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Mutex, OnceLock},
+};
+
+use bevy_ecs::{
+    system::Command,
+    world::{Mut, World},
+};
+use bevy_utils::tracing::info_span;
+
+use crate::main_thread;
+
+type ShutdownFn = Box<dyn FnOnce()>;
+
+/// Keys that have been initialized by `ffi_init_once`, for the whole process.
+static INITIALIZED: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+/// Marks `key` as initialized, returning false if it already was.
+fn mark(key: &'static str) -> bool {
+    INITIALIZED
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(key)
+}
+
+/// A non-[`Send`] resource recording which FFI libraries were initialized in this [`World`] with [`ffi_init_once`], and how to shut them down.
+///
+/// Shutdown hooks registered with [`ffi_shutdown`] run in the reverse order that their libraries were initialized. They run when [`shutdown_ffi`] or [`teardown_non_send_resources`](crate::teardown_non_send_resources) is called, or otherwise when this resource is dropped.
+#[derive(Default)]
+pub struct NonSendFfiRegistry {
+    initialized: Vec<&'static str>,
+    shutdowns: HashMap<&'static str, ShutdownFn>,
+}
+
+impl NonSendFfiRegistry {
+    /// Returns the keys of the libraries initialized in this [`World`] that have not been shut down, in the order they were initialized.
+    pub fn initialized(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.initialized.iter().copied()
+    }
+
+    /// Returns true if the library with the given key was initialized in this [`World`] and has not been shut down.
+    pub fn is_initialized(&self, key: &str) -> bool {
+        self.initialized.contains(&key)
+    }
+
+    fn shutdown(&mut self) {
+        while let Some(key) = self.initialized.pop() {
+            if let Some(shutdown) = self.shutdowns.remove(key) {
+                let _span = info_span!("ffi_shutdown", key).entered();
+
+                shutdown();
+            }
+        }
+    }
+}
+
+impl Drop for NonSendFfiRegistry {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn registry(world: &mut World) -> Mut<'_, NonSendFfiRegistry> {
+    if !world.contains_non_send::<NonSendFfiRegistry>() {
+        world.insert_non_send_resource(NonSendFfiRegistry::default());
+    }
+
+    world.non_send_resource_mut::<NonSendFfiRegistry>()
+}
+
+/// Creates a [`Command`] that runs `init` on the main thread, at most once per process for the given key.
+///
+/// This is meant for initializing native libraries that must only be initialized once, from the main thread. Later applications of a command with the same key do nothing, even in a different [`World`]. The key is recorded in the [`NonSendFfiRegistry`] of the [`World`] that ran `init`.
+///
+/// ```
+/// # use std::sync::atomic::{AtomicU32, Ordering};
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{ffi_init_once, ffi_shutdown, shutdown_ffi, NonSendFfiRegistry};
+/// #
+/// static INITS: AtomicU32 = AtomicU32::new(0);
+/// static SHUTDOWNS: AtomicU32 = AtomicU32::new(0);
+///
+/// fn init_audio(mut commands: Commands) {
+///     commands.add(ffi_init_once("openal", || {
+///         // Call into the native library here.
+///         INITS.fetch_add(1, Ordering::Relaxed);
+///     }));
+///
+///     commands.add(ffi_shutdown("openal", || {
+///         SHUTDOWNS.fetch_add(1, Ordering::Relaxed);
+///     }));
+/// }
+///
+/// let mut app = App::new();
+///
+/// app.add_systems(Update, init_audio);
+///
+/// app.update();
+/// app.update();
+///
+/// assert_eq!(INITS.load(Ordering::Relaxed), 1);
+/// assert!(app.world.non_send_resource::<NonSendFfiRegistry>().is_initialized("openal"));
+///
+/// shutdown_ffi(&mut app.world);
+///
+/// assert_eq!(SHUTDOWNS.load(Ordering::Relaxed), 1);
+/// ```
+#[track_caller]
+pub fn ffi_init_once<F>(key: &'static str, init: F) -> impl Command
+where
+    F: FnOnce() + Send + 'static,
+{
+    main_thread::deferrable(move |world: &mut World| {
+        if !mark(key) {
+            return;
+        }
+
+        {
+            let _span = info_span!("ffi_init_once", key).entered();
+
+            init();
+        }
+
+        registry(world).initialized.push(key);
+    })
+}
+
+/// Creates a [`Command`] that registers a hook to shut down the library initialized by [`ffi_init_once`] with the given key.
+///
+/// Only one hook is kept per key, so registering another one replaces it. The hook only runs if the library was initialized in the same [`World`]. See [`NonSendFfiRegistry`] for when hooks run.
+#[track_caller]
+pub fn ffi_shutdown<F>(key: &'static str, shutdown: F) -> impl Command
+where
+    F: FnOnce() + Send + 'static,
+{
+    main_thread::deferrable(move |world: &mut World| {
+        registry(world).shutdowns.insert(key, Box::new(shutdown));
+    })
+}
+
+/// Runs the shutdown hooks of all libraries initialized in the [`World`], in the reverse order they were initialized.
+///
+/// Libraries are shut down at most once. See [`ffi_shutdown`].
+pub fn shutdown_ffi(world: &mut World) {
+    if let Some(mut registry) = world.get_non_send_resource_mut::<NonSendFfiRegistry>() {
+        registry.shutdown();
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    panic::Location,
+    thread::{self, ThreadId},
+};
+
+use bevy_ecs::{
+    system::{Command, Resource},
+    world::World,
+};
+
+use crate::{main_thread, tracking};
+
+/// A wrapper that makes a non-[`Send`] value nominally [`Send`] and [`Sync`], so that it can be stored as a normal [`Resource`].
+///
+/// The value may only be accessed, moved out of, or dropped on the thread that created the guard. Doing so from any other thread panics. For resources, this means only exclusive systems and systems with a [`NonSend`](bevy_ecs::system::NonSend) parameter may access it.
+///
+/// Use [`wrap_non_send_resource`] and [`unwrap_non_send_resource`] to convert between a non-[`Send`] resource and a `NonSendGuard` resource. This is useful for third-party APIs that only accept [`Resource`]s.
+pub struct NonSendGuard<T> {
+    value: ManuallyDrop<T>,
+    thread: ThreadId,
+}
+
+// SAFETY: The value can only be accessed or dropped on the thread that created the guard, which
+// is checked at runtime.
+unsafe impl<T> Send for NonSendGuard<T> {}
+
+// SAFETY: Shared access to the value is also checked at runtime, see above.
+unsafe impl<T> Sync for NonSendGuard<T> {}
+
+impl<T: 'static> Resource for NonSendGuard<T> {}
+
+impl<T> NonSendGuard<T> {
+    /// Wraps `value`, binding it to the current thread.
+    pub fn new(value: T) -> Self {
+        NonSendGuard {
+            value: ManuallyDrop::new(value),
+            thread: thread::current().id(),
+        }
+    }
+
+    /// Returns true if the current thread is the one that created this guard.
+    pub fn is_valid(&self) -> bool {
+        thread::current().id() == self.thread
+    }
+
+    /// Returns a reference to the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a thread other than the one that created this guard.
+    #[track_caller]
+    pub fn get(&self) -> &T {
+        self.assert_valid();
+        &self.value
+    }
+
+    /// Returns a mutable reference to the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a thread other than the one that created this guard.
+    #[track_caller]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.assert_valid();
+        &mut self.value
+    }
+
+    /// Returns a reference to the value, or [`None`] if called from a thread other than the one that created this guard.
+    pub fn try_get(&self) -> Option<&T> {
+        self.is_valid().then(|| &*self.value)
+    }
+
+    /// Unwraps the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a thread other than the one that created this guard.
+    #[track_caller]
+    pub fn into_inner(mut self) -> T {
+        self.assert_valid();
+
+        // SAFETY: `self` is forgotten immediately afterwards, so the value is not dropped twice.
+        let value = unsafe { ManuallyDrop::take(&mut self.value) };
+        std::mem::forget(self);
+
+        value
+    }
+
+    #[track_caller]
+    fn assert_valid(&self) {
+        assert!(
+            self.is_valid(),
+            "`NonSendGuard<{}>` was accessed from a thread other than the one that created it.",
+            std::any::type_name::<T>()
+        );
+    }
+}
+
+impl<T> Deref for NonSendGuard<T> {
+    type Target = T;
+
+    #[track_caller]
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+impl<T> DerefMut for NonSendGuard<T> {
+    #[track_caller]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.get_mut()
+    }
+}
+
+impl<T> Drop for NonSendGuard<T> {
+    fn drop(&mut self) {
+        if self.is_valid() {
+            // SAFETY: The value is never used again.
+            unsafe { ManuallyDrop::drop(&mut self.value) };
+        } else if !thread::panicking() {
+            panic!(
+                "`NonSendGuard<{}>` was dropped on a thread other than the one that created it.",
+                std::any::type_name::<T>()
+            );
+        }
+
+        // If the thread is already panicking, the value is leaked instead of aborting.
+    }
+}
+
+/// Creates a [`Command`] that moves the non-[`Send`] resource `R` into a [`NonSendGuard<R>`] resource.
+///
+/// Does nothing if `R` does not exist.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{unwrap_non_send_resource, wrap_non_send_resource, NonSendGuard};
+/// #
+/// struct MyNonSend(*const u8);
+///
+/// fn wrap_my_non_send(mut commands: Commands) {
+///     commands.add(wrap_non_send_resource::<MyNonSend>());
+/// }
+///
+/// // Exclusive systems always run on the main thread, where the guard was created.
+/// fn use_guard(world: &mut World) {
+///     let guard = world.resource::<NonSendGuard<MyNonSend>>();
+///
+///     assert!(guard.0.is_null());
+/// }
+///
+/// fn unwrap_my_non_send(mut commands: Commands) {
+///     commands.add(unwrap_non_send_resource::<MyNonSend>());
+/// }
+/// #
+/// # App::new()
+/// #     .insert_non_send_resource(MyNonSend(std::ptr::null()))
+/// #     .add_systems(Startup, (wrap_my_non_send, use_guard, unwrap_my_non_send, check).chain())
+/// #     .run();
+/// #
+/// # fn check(my_non_send: NonSend<MyNonSend>, guard: Option<Res<NonSendGuard<MyNonSend>>>) {
+/// #     assert!(my_non_send.0.is_null());
+/// #     assert!(guard.is_none());
+/// # }
+/// ```
+#[track_caller]
+pub fn wrap_non_send_resource<R: 'static>() -> impl Command {
+    main_thread::deferrable(|world: &mut World| {
+        if let Some(resource) = world.remove_non_send_resource::<R>() {
+            tracking::untrack::<R>(world);
+            world.insert_resource(NonSendGuard::new(resource));
+        }
+    })
+}
+
+/// Creates a [`Command`] that moves the value of a [`NonSendGuard<R>`] resource back into the non-[`Send`] resource `R`.
+///
+/// Does nothing if the guard does not exist. See [`wrap_non_send_resource`].
+#[track_caller]
+pub fn unwrap_non_send_resource<R: 'static>() -> impl Command {
+    let location = Location::caller();
+
+    main_thread::deferrable(move |world: &mut World| {
+        if let Some(guard) = world.remove_resource::<NonSendGuard<R>>() {
+            world.insert_non_send_resource(guard.into_inner());
+            tracking::track_moved::<R>(world, location, None);
+        }
+    })
+}
@@ -0,0 +1,353 @@
+use std::{collections::HashMap, fmt, panic::Location};
+
+use bevy_ecs::{
+    system::{Command, NonSendMut, SystemParam},
+    world::World,
+};
+use bevy_utils::intern::{Interned, Interner};
+
+use crate::{
+    error, tracking, NonSendCommand, NonSendCommandError, NonSendCommandKind, NonSendErrorContext,
+};
+
+static LABEL_INTERNER: Interner<str> = Interner::new();
+
+/// An interned label that identifies one of several instances of the same non-[`Send`] type.
+///
+/// Labels are cheap to copy and compare. They can be created from any string with [`NonSendLabel::new`] or [`From<&str>`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NonSendLabel(Interned<str>);
+
+impl NonSendLabel {
+    /// Interns `label`, returning the same [`NonSendLabel`] for equal strings.
+    pub fn new(label: &str) -> Self {
+        NonSendLabel(LABEL_INTERNER.intern(label))
+    }
+
+    /// Returns the string this label was created from.
+    pub fn as_str(&self) -> &'static str {
+        self.0 .0
+    }
+}
+
+impl From<&str> for NonSendLabel {
+    fn from(label: &str) -> Self {
+        NonSendLabel::new(label)
+    }
+}
+
+impl fmt::Debug for NonSendLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for NonSendLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The non-[`Send`] resource storing every labeled instance of `R`.
+///
+/// It is inserted with the first instance, and removed with the last one.
+struct LabeledNonSend<R: 'static> {
+    instances: HashMap<NonSendLabel, R>,
+}
+
+/// A [`SystemParam`] for accessing the labeled instances of the non-[`Send`] type `R` on the main thread.
+///
+/// See [`insert_non_send_labeled`].
+#[derive(SystemParam)]
+pub struct NonSendKeyed<'w, R: 'static> {
+    storage: Option<NonSendMut<'w, LabeledNonSend<R>>>,
+}
+
+impl<'w, R: 'static> NonSendKeyed<'w, R> {
+    /// Returns the instance with the given label, if it exists.
+    pub fn get(&self, label: impl Into<NonSendLabel>) -> Option<&R> {
+        let label = label.into();
+
+        self.storage.as_ref()?.instances.get(&label)
+    }
+
+    /// Returns the instance with the given label mutably, if it exists.
+    pub fn get_mut(&mut self, label: impl Into<NonSendLabel>) -> Option<&mut R> {
+        let label = label.into();
+
+        self.storage.as_mut()?.instances.get_mut(&label)
+    }
+
+    /// Returns true if an instance with the given label exists.
+    pub fn contains(&self, label: impl Into<NonSendLabel>) -> bool {
+        self.get(label).is_some()
+    }
+
+    /// Returns the number of instances.
+    pub fn len(&self) -> usize {
+        self.storage
+            .as_ref()
+            .map_or(0, |storage| storage.instances.len())
+    }
+
+    /// Returns true if there are no instances.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over all instances and their labels, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (NonSendLabel, &R)> {
+        self.storage
+            .as_deref()
+            .into_iter()
+            .flat_map(|storage| storage.instances.iter())
+            .map(|(label, instance)| (*label, instance))
+    }
+
+    /// Iterates mutably over all instances and their labels, in arbitrary order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (NonSendLabel, &mut R)> {
+        self.storage
+            .as_deref_mut()
+            .into_iter()
+            .flat_map(|storage| storage.instances.iter_mut())
+            .map(|(label, instance)| (*label, instance))
+    }
+}
+
+/// Creates a [`Command`] for inserting an instance of a non-[`Send`] type under a label, alongside the other instances of the same type.
+///
+/// This allows several values of the same type to exist at once, such as one graphics context per window. If an instance with the same label already exists, it is replaced. Labeled instances are accessed with [`NonSendKeyed`] instead of [`NonSend`](bevy_ecs::system::NonSend).
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{insert_non_send_labeled, NonSendKeyed};
+/// #
+/// struct GlContext {
+///     width: u32,
+///     _handle: *const u8,
+/// }
+///
+/// fn create_contexts(mut commands: Commands) {
+///     for (monitor, width) in [("primary_monitor", 1920), ("secondary_monitor", 1280)] {
+///         commands.add(insert_non_send_labeled(monitor, move || GlContext {
+///             width,
+///             _handle: std::ptr::null(),
+///         }));
+///     }
+/// }
+///
+/// fn draw(contexts: NonSendKeyed<GlContext>) {
+///     assert_eq!(contexts.len(), 2);
+///     assert_eq!(contexts.get("secondary_monitor").unwrap().width, 1280);
+/// }
+/// #
+/// # App::new()
+/// #     .add_systems(Startup, (create_contexts, draw).chain())
+/// #     .run();
+/// ```
+#[track_caller]
+pub fn insert_non_send_labeled<F, R>(label: impl Into<NonSendLabel>, func: F) -> impl Command
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: 'static,
+{
+    let label = label.into();
+    let location = Location::caller();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| insert_labeled(world, label, (func)(), location),
+    )
+}
+
+/// Creates a [`Command`] for inserting a labeled instance of a non-[`Send`] type, passing a [`NonSendCommandError::LabelExists`] error to the [`NonSendErrorHandler`](crate::NonSendErrorHandler) instead of replacing it if an instance with the same label already exists.
+///
+/// `func` is not called if the label is taken. See [`insert_non_send_labeled`] for more details.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{try_insert_non_send_labeled, NonSendErrorHandler, NonSendKeyed};
+/// #
+/// struct GlContext(u32);
+///
+/// fn create_contexts(mut commands: Commands) {
+///     commands.add(try_insert_non_send_labeled("primary_monitor", || GlContext(1)));
+///
+///     // The label is already taken, so this will call the error handler.
+///     commands.add(try_insert_non_send_labeled("primary_monitor", || GlContext(2)));
+/// }
+/// #
+/// # App::new()
+/// #     .insert_resource(NonSendErrorHandler::IGNORE)
+/// #     .add_systems(Startup, (create_contexts, check).chain())
+/// #     .run();
+/// #
+/// # fn check(contexts: NonSendKeyed<GlContext>) {
+/// #     assert_eq!(contexts.get("primary_monitor").unwrap().0, 1);
+/// # }
+/// ```
+#[track_caller]
+pub fn try_insert_non_send_labeled<F, R>(label: impl Into<NonSendLabel>, func: F) -> impl Command
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: 'static,
+{
+    let label = label.into();
+    let context = NonSendErrorContext {
+        command: "try_insert_non_send_labeled",
+        location: Location::caller(),
+    };
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        context.location,
+        move |world: &mut World| {
+            let result = match contains_labeled::<R>(world, label) {
+                true => Err(NonSendCommandError::LabelExists {
+                    type_name: std::any::type_name::<R>(),
+                    label,
+                }),
+                false => {
+                    insert_labeled(world, label, (func)(), context.location);
+                    Ok(())
+                }
+            };
+
+            error::handle_error(world, result, context);
+        },
+    )
+}
+
+/// Returns true if an instance of `R` with the given label exists.
+pub(crate) fn contains_labeled<R: 'static>(world: &World, label: NonSendLabel) -> bool {
+    world
+        .get_non_send_resource::<LabeledNonSend<R>>()
+        .is_some_and(|storage| storage.instances.contains_key(&label))
+}
+
+/// Inserts `instance` under `label`, replacing any previous instance with the same label.
+pub(crate) fn insert_labeled<R: 'static>(
+    world: &mut World,
+    label: NonSendLabel,
+    instance: R,
+    location: &'static Location<'static>,
+) {
+    // The removal options of a replaced instance do not carry over to the new one.
+    crate::builder::clear_removal_options::<R>(world, Some(label));
+
+    if let Some(mut storage) = world.get_non_send_resource_mut::<LabeledNonSend<R>>() {
+        storage.instances.insert(label, instance);
+    } else {
+        world.insert_non_send_resource(LabeledNonSend {
+            instances: HashMap::from([(label, instance)]),
+        });
+        tracking::track::<LabeledNonSend<R>>(world, location, None);
+    }
+}
+
+/// Creates a [`Command`] for removing the instance of a non-[`Send`] type with the given label.
+///
+/// Does nothing if no instance has this label.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{remove_non_send_labeled, CommandsExt, NonSendKeyed};
+/// #
+/// struct GlContext(*const u8);
+///
+/// fn close_secondary_monitor(mut commands: Commands) {
+///     commands.add(remove_non_send_labeled::<GlContext>("secondary_monitor"));
+/// }
+/// #
+/// # fn create_contexts(mut commands: Commands) {
+/// #     commands.insert_non_send_labeled("primary_monitor", || GlContext(std::ptr::null()));
+/// #     commands.insert_non_send_labeled("secondary_monitor", || GlContext(std::ptr::null()));
+/// # }
+/// #
+/// # App::new()
+/// #     .add_systems(Startup, (create_contexts, close_secondary_monitor, check).chain())
+/// #     .run();
+/// #
+/// # fn check(contexts: NonSendKeyed<GlContext>) {
+/// #     assert!(contexts.contains("primary_monitor"));
+/// #     assert!(!contexts.contains("secondary_monitor"));
+/// # }
+/// ```
+#[track_caller]
+pub fn remove_non_send_labeled<R: 'static>(label: impl Into<NonSendLabel>) -> impl Command {
+    let label = label.into();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Remove,
+        Location::caller(),
+        move |world: &mut World| {
+            remove_labeled::<R>(world, label);
+        },
+    )
+}
+
+/// Creates a [`Command`] for removing the instance of a non-[`Send`] type with the given label, passing a [`NonSendCommandError::LabelMissing`] error to the [`NonSendErrorHandler`](crate::NonSendErrorHandler) if no instance has this label.
+///
+/// See [`remove_non_send_labeled`] for more details.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{try_remove_non_send_labeled, NonSendErrorHandler};
+/// #
+/// struct GlContext(*const u8);
+///
+/// fn close_secondary_monitor(mut commands: Commands) {
+///     // There is no secondary monitor, so this will call the error handler.
+///     commands.add(try_remove_non_send_labeled::<GlContext>("secondary_monitor"));
+/// }
+/// #
+/// # App::new()
+/// #     .insert_resource(NonSendErrorHandler::WARN)
+/// #     .add_systems(Startup, close_secondary_monitor)
+/// #     .run();
+/// ```
+#[track_caller]
+pub fn try_remove_non_send_labeled<R: 'static>(label: impl Into<NonSendLabel>) -> impl Command {
+    let label = label.into();
+    let context = NonSendErrorContext {
+        command: "try_remove_non_send_labeled",
+        location: Location::caller(),
+    };
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Remove,
+        context.location,
+        move |world: &mut World| {
+            let result = match remove_labeled::<R>(world, label) {
+                true => Ok(()),
+                false => Err(NonSendCommandError::LabelMissing {
+                    type_name: std::any::type_name::<R>(),
+                    label,
+                }),
+            };
+
+            error::handle_error(world, result, context);
+        },
+    )
+}
+
+/// Removes the instance of `R` with the given label, dropping it and running its removal hooks.
+///
+/// Returns false if no instance had this label.
+fn remove_labeled<R: 'static>(world: &mut World, label: NonSendLabel) -> bool {
+    let Some(mut storage) = world.get_non_send_resource_mut::<LabeledNonSend<R>>() else {
+        return false;
+    };
+
+    let Some(instance) = storage.instances.remove(&label) else {
+        return false;
+    };
+
+    if storage.instances.is_empty() {
+        tracking::remove::<LabeledNonSend<R>>(world);
+    }
+
+    crate::builder::removed(world, Some(label), instance);
+    true
+}
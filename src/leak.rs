@@ -0,0 +1,160 @@
+use std::{panic::Location, time::Duration};
+
+use bevy_app::{App, AppExit, Last, Plugin};
+use bevy_ecs::{
+    component::{ComponentId, Tick},
+    event::EventReader,
+    schedule::{IntoSystemConfigs, Schedules},
+    world::World,
+};
+use bevy_utils::tracing::warn;
+
+use crate::{NonSendTracker, NonSendTrackingPlugin};
+
+/// A non-[`Send`] resource that was inserted through this crate's commands, and was neither removed nor accessed since.
+///
+/// See [`find_non_send_leaks`].
+#[derive(Clone, Debug)]
+pub struct NonSendLeak {
+    type_name: &'static str,
+    location: &'static Location<'static>,
+    age: Duration,
+}
+
+impl NonSendLeak {
+    /// Returns the type name of the resource.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the location of the code that queued the command which inserted the resource.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// Returns how long ago the resource was inserted.
+    pub fn age(&self) -> Duration {
+        self.age
+    }
+}
+
+/// Returns every tracked non-[`Send`] resource that still exists and was never accessed since it was inserted.
+///
+/// A resource counts as accessed if it was changed, or if a system that reads it through [`NonSend`](bevy_ecs::system::NonSend) or [`NonSendMut`](bevy_ecs::system::NonSendMut) ran since it was inserted. Systems are found through the [`Schedules`] resource, so systems of the schedule that is currently running, and systems run outside of a schedule, are not taken into account. Only resources recorded by the [`NonSendTracker`] are checked, so this returns nothing if tracking is not enabled.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{find_non_send_leaks, CommandsExt, NonSendLeakDetectionPlugin};
+/// #
+/// struct UsedHandle(*const u8);
+/// struct ReadHandle(*const u8);
+/// struct ForgottenHandle(*const u8);
+///
+/// fn create_handles(mut commands: Commands) {
+///     commands.insert_non_send_resource(|| UsedHandle(std::ptr::null()));
+///     commands.insert_non_send_resource(|| ReadHandle(std::ptr::null()));
+///     commands.insert_non_send_resource(|| ForgottenHandle(std::ptr::null()));
+/// }
+///
+/// fn use_handles(mut used: NonSendMut<UsedHandle>, read: NonSend<ReadHandle>) {
+///     used.0 = read.0;
+/// }
+///
+/// let mut app = App::new();
+///
+/// app.add_plugins(NonSendLeakDetectionPlugin)
+///     .add_systems(Startup, create_handles)
+///     .add_systems(Update, use_handles);
+///
+/// app.update();
+///
+/// let leaks = find_non_send_leaks(&app.world);
+///
+/// assert_eq!(leaks.len(), 1);
+/// assert!(leaks[0].type_name().ends_with("ForgottenHandle"));
+/// ```
+pub fn find_non_send_leaks(world: &World) -> Vec<NonSendLeak> {
+    let Some(tracker) = world.get_resource::<NonSendTracker>() else {
+        return Vec::new();
+    };
+
+    let this_run = world.read_change_tick();
+
+    tracker
+        .iter()
+        .filter(|tracked| {
+            let Some(id) = world.components().get_resource_id(tracked.type_id()) else {
+                return false;
+            };
+
+            let Some(ticks) = world
+                .storages()
+                .non_send_resources
+                .get(id)
+                .and_then(|data| data.get_ticks())
+            else {
+                // The resource was removed without going through this crate.
+                return false;
+            };
+
+            !ticks.is_changed(tracked.inserted_tick(), this_run)
+                && !read_since(world, id, tracked.inserted_tick(), this_run)
+        })
+        .map(|tracked| NonSendLeak {
+            type_name: tracked.type_name(),
+            location: tracked.location(),
+            age: tracked.age(),
+        })
+        .collect()
+}
+
+/// Returns true if a system that reads the resource `id` has run since `since`.
+fn read_since(world: &World, id: ComponentId, since: Tick, this_run: Tick) -> bool {
+    let Some(schedules) = world.get_resource::<Schedules>() else {
+        return false;
+    };
+
+    schedules
+        .iter()
+        .filter_map(|(_, schedule)| schedule.systems().ok())
+        .flatten()
+        .any(|(_, system)| {
+            // Only count explicit access, as systems taking `&World`, such as `report_non_send_leaks`, read every resource.
+            system
+                .component_access()
+                .reads_and_writes()
+                .any(|read| read == id)
+                && system.get_last_run().is_newer_than(since, this_run)
+        })
+}
+
+/// Logs a warning for each leak returned by [`find_non_send_leaks`].
+///
+/// This runs automatically on [`AppExit`] when [`NonSendLeakDetectionPlugin`] is added, but may also be called on demand.
+pub fn report_non_send_leaks(world: &World) {
+    for leak in find_non_send_leaks(world) {
+        warn!(
+            "Non-send resource {} inserted at {} was never removed nor accessed ({:.1?} old).",
+            leak.type_name, leak.location, leak.age,
+        );
+    }
+}
+
+/// A [`Plugin`] that reports the non-[`Send`] resources that were never removed nor accessed when the app exits.
+///
+/// Resources that outlive their use are a common source of crashes at shutdown, such as native handles dropped after the library that created them. This adds [`NonSendTrackingPlugin`] if it was not added yet, and calls [`report_non_send_leaks`] in [`Last`] when an [`AppExit`] event is sent.
+pub struct NonSendLeakDetectionPlugin;
+
+impl Plugin for NonSendLeakDetectionPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<NonSendTrackingPlugin>() {
+            app.add_plugins(NonSendTrackingPlugin);
+        }
+
+        app.add_systems(Last, report_non_send_leaks.run_if(on_app_exit));
+    }
+}
+
+fn on_app_exit(mut exit: EventReader<AppExit>) -> bool {
+    exit.read().count() > 0
+}
@@ -1,13 +1,218 @@
 //! This is a small utility library that enables manipulating non-[`Send`] resources using [`Commands`] in [Bevy].
 //!
 //! You can use this library by importing [`CommandsExt`] and calling all of its methods on [`Commands`], or you can manually call [`Commands::add`].
-//! 
+//!
+//! # Features
+//!
+//! - `auto_register`: Enables `auto_non_send!` and `NonSendAutoInitPlugin`, which initialize non-[`Send`] resources registered across crates at link time. Requires Rust 1.79 or later.
+//! - `bevy_asset`: Enables `insert_non_send_from_asset` and `NonSendFromAssetPlugin`, which build non-[`Send`] resources from assets and rebuild them when the assets change.
+//! - `bevy_diagnostic`: Reports the measurements of `NonSendProfiler` to the `DiagnosticsStore`, under the path `non_send/<type name>`. This also enables `profiler`.
+//! - `bevy_reflect`: Enables `insert_non_send_reflect` and `ReflectNonSend`, which insert non-[`Send`] resources from reflected values.
+//! - `brp`: Enables `NonSendBrpPlugin` and `run_non_send_brp_method`, which handle the `bevy_command_non_send/list`, `bevy_command_non_send/remove`, and `bevy_command_non_send/reinit` Bevy Remote Protocol methods for tracked non-[`Send`] resources. Bevy 0.13 does not ship `bevy_remote`, so requests must be forwarded from your own transport.
+//! - `console`: Enables `NonSendConsolePlugin` and `run_non_send_console_command`, which handle the `nonsend list`, `nonsend remove <type>`, and `nonsend reinit <type>` developer console commands in a running app. Types are resolved by their `TypePath` through the type registry, so this also enables `bevy_reflect`.
+//! - `hot_rebuild`: Enables `NonSendRebuildPlugin` and `insert_non_send_rebuildable`, which drop and rebuild non-[`Send`] resources while the app is running, for iterating on them during development.
+//! - `leak_detection`: Enables `NonSendLeakDetectionPlugin` and `find_non_send_leaks`, which report the non-[`Send`] resources that were never removed nor accessed, along with where they were inserted, when the app exits.
+//! - `libloading`: Enables `load_non_send_from_library` and `unload_non_send_library`, which load non-[`Send`] resources from dynamic libraries and unload them only after the resources are dropped.
+//! - `mlua`: Enables `LuaNonSendPlugin`, `run_lua_chunk`, and `with_lua`, which manage a vendored Lua 5.4 state as a non-[`Send`] resource.
+//! - `profiler`: Enables `NonSendProfilerPlugin` and `NonSendProfiler`, which measure how long the non-[`Send`] commands of each resource type take to apply.
+//! - `pyo3`: Enables `insert_non_send_with_gil` and `with_gil_command`, which acquire the Python GIL on the main thread to create and use non-[`Send`] resources holding interpreter state.
+//! - `raw-window-handle`: Enables `insert_non_send_with_window_handle` and `insert_non_send_with_primary_window_handle`, whose factories receive raw window and display handles.
+//! - `snapshot`: Enables `snapshot_non_send`, `restore_non_send`, and `AppExt::register_non_send_snapshot`, which capture non-[`Send`] resources and roll them back, such as for undo or rollback networking.
+//! - `windows`: Enables `ComApartmentPlugin` and `init_com_apartment` on Windows, which initialize a COM single-threaded apartment on the main thread and uninitialize it after the COM objects that depend on it.
+//! - `winit`: Enables `insert_non_send_with_winit_windows` and `insert_non_send_with_window`, whose factories receive `winit` windows. This enables `winit`'s X11 backend on Linux and BSD. Enable the `wayland` feature of `bevy_winit` for Wayland support.
+//!
 //! [Bevy]: https://bevyengine.org
 
+mod arena;
+#[cfg(feature = "bevy_asset")]
+mod asset;
+#[cfg(feature = "auto_register")]
+mod auto_register;
+#[cfg(feature = "brp")]
+mod brp;
+mod builder;
+mod catch_unwind;
+mod change_events;
+mod channel;
+#[cfg(all(windows, feature = "windows"))]
+mod com;
+mod command;
+mod computed;
+#[cfg(feature = "console")]
+mod console;
+mod deferred_drop;
+mod double_buffer;
+mod drop_order;
+mod dynamic;
+mod error;
+mod factory;
+mod fallback;
+mod ffi;
+mod guard;
+mod labeled;
+#[cfg(feature = "leak_detection")]
+mod leak;
+#[cfg(feature = "libloading")]
+mod library;
+mod local;
+#[cfg(feature = "mlua")]
+mod lua;
+mod main_thread;
+mod mirror;
+mod once;
+mod persistence;
+mod pipe;
+mod priority;
+#[cfg(feature = "profiler")]
+mod profiler;
+mod projection;
+#[cfg(feature = "pyo3")]
+mod python;
+mod read_service;
+#[cfg(feature = "hot_rebuild")]
+mod rebuild;
+mod record;
+#[cfg(feature = "bevy_reflect")]
+mod reflect;
+mod scoped;
+#[cfg(feature = "snapshot")]
+mod snapshot;
+mod ticket;
+mod tracking;
+mod watchdog;
+#[cfg(feature = "raw-window-handle")]
+mod window_handle;
+#[cfg(feature = "winit")]
+mod winit;
+
+pub use self::arena::{
+    insert_into_non_send_arena, remove_from_non_send_arena, ArenaKey, NonSendArena,
+    NonSendArenaKeys, NonSendArenaMut, NonSendArenaPlugin,
+};
+#[cfg(feature = "bevy_asset")]
+pub use self::asset::{
+    insert_non_send_from_asset, remove_non_send_from_asset, NonSendAssetBinding,
+    NonSendFromAssetPlugin,
+};
+#[cfg(feature = "auto_register")]
+pub use self::auto_register::{AutoNonSend, NonSendAutoInitPlugin};
+#[cfg(feature = "brp")]
+pub use self::brp::{
+    process_non_send_list_request, process_non_send_reinit_request,
+    process_non_send_remove_request, run_non_send_brp_method, NonSendBrpError, NonSendBrpMethods,
+    NonSendBrpPlugin, NonSendBrpResult, NonSendBrpSystemId, BRP_LIST_METHOD, BRP_REINIT_METHOD,
+    BRP_REMOVE_METHOD,
+};
+pub use self::builder::{NoFactory, NonSendBuilder};
+pub use self::catch_unwind::{
+    insert_non_send_resource_catch_unwind, try_insert_non_send_resource_catch_unwind,
+    NonSendFactoryPanicked,
+};
+pub use self::change_events::{NonSendChange, NonSendChangeEventsPlugin, NonSendResourceChanged};
+pub use self::channel::NonSendChannelPlugin;
+#[cfg(all(windows, feature = "windows"))]
+pub use self::com::{init_com_apartment, ComApartment, ComApartmentPlugin};
+pub use self::command::{
+    NonSendApply, NonSendCommand, NonSendCommandInspector, NonSendCommandKind,
+};
+#[cfg(feature = "console")]
+pub use self::console::{
+    run_non_send_console_command, NonSendConsoleCommand, NonSendConsoleOutput, NonSendConsolePlugin,
+};
+pub use self::deferred_drop::remove_non_send_resource_deferred;
+pub use self::double_buffer::{
+    insert_non_send_double_buffer, swap_non_send_buffers, NonSendDoubleBuffer,
+};
+pub use self::drop_order::{teardown_non_send_resources, NonSendDropOrder};
+pub use self::dynamic::{DynamicNonSend, DynamicNonSendBuilder, DynamicNonSendSystem};
+pub use self::error::{NonSendCommandError, NonSendErrorContext, NonSendErrorHandler};
+pub use self::factory::NonSendFactories;
+pub use self::fallback::{
+    insert_non_send_resource_or_else, try_insert_non_send_resource_or_else, NonSendFallbackUsed,
+};
+pub use self::ffi::{ffi_init_once, ffi_shutdown, shutdown_ffi, NonSendFfiRegistry};
+pub use self::guard::{unwrap_non_send_resource, wrap_non_send_resource, NonSendGuard};
+pub use self::labeled::{
+    insert_non_send_labeled, remove_non_send_labeled, try_insert_non_send_labeled,
+    try_remove_non_send_labeled, NonSendKeyed, NonSendLabel,
+};
+#[cfg(feature = "leak_detection")]
+pub use self::leak::{
+    find_non_send_leaks, report_non_send_leaks, NonSendLeak, NonSendLeakDetectionPlugin,
+};
+#[cfg(feature = "libloading")]
+pub use self::library::{load_non_send_from_library, unload_non_send_library, NonSendLibrary};
+pub use self::local::NonSendLocal;
+#[cfg(feature = "mlua")]
+pub use self::lua::{run_lua_chunk, with_lua, LuaNonSendPlugin};
+pub use self::main_thread::{
+    insert_non_send_resource_on_os_main_thread, is_main_thread, is_os_main_thread, on_main_thread,
+    run_main_thread_queue, MainThreadDeferralPlugin, MainThreadMarker, MainThreadPlugin,
+    MainThreadQueue,
+};
+pub use self::mirror::{
+    insert_non_send_mirrored, insert_non_send_projected, remove_non_send_mirrored,
+    remove_non_send_projected, NonSendMirrorPlugin,
+};
+pub use self::once::{init_non_send_resource_once, is_non_send_initialized_once};
+pub use self::persistence::{
+    load_non_send_resources, load_non_send_resources_from, save_non_send_resources,
+    save_non_send_resources_to, try_load_non_send_resources, try_save_non_send_resources,
+    NonSendPersistence,
+};
+pub use self::pipe::PipeIntoNonSend;
+pub use self::priority::{PriorityCommands, PriorityQueue};
+#[cfg(feature = "profiler")]
+pub use self::profiler::{NonSendProfiler, NonSendProfilerPlugin, NonSendTiming, TIMING_BUCKETS};
+#[cfg(feature = "pyo3")]
+pub use self::python::{insert_non_send_with_gil, with_gil_command};
+pub use self::read_service::{
+    NonSendReadService, NonSendReadServicePlugin, ReadReply, ReadRequest,
+};
+#[cfg(feature = "hot_rebuild")]
+pub use self::rebuild::{
+    insert_non_send_rebuildable, rebuild_all_non_send_resources, rebuild_non_send_resource,
+    NonSendRebuildPlugin, NonSendRebuilders, NonSendRebuilt,
+};
+pub use self::record::{
+    NonSendRecordPlugin, NonSendRecorder, NonSendRecording, NonSendReplayPlugin, NonSendReplayer,
+    RecordedNonSendCommand,
+};
+#[cfg(feature = "bevy_reflect")]
+pub use self::reflect::{insert_non_send_reflect, try_insert_non_send_reflect, ReflectNonSend};
+pub use self::scoped::{
+    insert_non_send_resource_for_frame, with_temporary_non_send_resource,
+    with_temporary_non_send_resource_in, NonSendFramePlugin,
+};
+#[cfg(feature = "snapshot")]
+pub use self::snapshot::{restore_non_send, snapshot_non_send, NonSendSnapshot, NonSendSnapshots};
+pub use self::ticket::{with_ticket, CommandTicket, TicketStatus, TicketedCommand};
+pub use self::tracking::{
+    reinit_tracked_non_send, remove_tracked_non_send, NonSendTracker, NonSendTrackingPlugin,
+    TrackedNonSend,
+};
+pub use self::watchdog::{NonSendWatchdog, NonSendWatchdogPlugin};
+#[cfg(feature = "raw-window-handle")]
+pub use self::window_handle::{
+    insert_non_send_with_primary_window_handle, insert_non_send_with_window_handle,
+};
+#[cfg(feature = "winit")]
+pub use self::winit::{insert_non_send_with_window, insert_non_send_with_winit_windows};
+
+use std::{
+    borrow::Cow,
+    fmt,
+    io::{self, Read, Write},
+    panic::Location,
+};
+
+use bevy_app::{App, Last};
 use bevy_ecs::{
-    system::{Command, Commands},
+    schedule::{ScheduleLabel, States},
+    system::{Command, Commands, Resource},
     world::{FromWorld, World},
 };
+use bevy_utils::tracing::info_span;
 
 /// Creates a [`Command`] for inserting a non-[`Send`] resource in the [`World`] with an inferred value.
 ///
@@ -39,10 +244,62 @@ use bevy_ecs::{
 /// #     assert!(my_non_send.0.is_null());
 /// # }
 /// ```
+#[track_caller]
 pub fn init_non_send_resource<R: FromWorld + 'static>() -> impl Command {
-    |world: &mut World| {
-        world.init_non_send_resource::<R>();
-    }
+    NonSendCommand::init::<R>()
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource in the [`World`] using a closure with read-only access to the [`World`], if it does not exist yet.
+///
+/// This is similar to [`init_non_send_resource`], but the value is created by `func` instead of [`FromWorld`]. `func` is executed on the main thread and is only called if the resource does not already exist.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::init_non_send_resource_from;
+/// #
+/// #[derive(Resource)]
+/// struct Config {
+///     scale: u8,
+/// }
+///
+/// struct MyNonSend(*const u8, u8);
+///
+/// fn create_my_non_send(mut commands: Commands) {
+///     commands.add(
+///         init_non_send_resource_from(|world: &World| {
+///             MyNonSend(std::ptr::null(), world.resource::<Config>().scale)
+///         })
+///     );
+/// }
+/// #
+/// # App::new()
+/// #     .insert_resource(Config { scale: 2 })
+/// #     .add_systems(Startup, (create_my_non_send, check).chain())
+/// #     .run();
+/// #
+/// # fn check(my_non_send: NonSend<MyNonSend>) {
+/// #     assert_eq!(my_non_send.1, 2);
+/// # }
+/// ```
+#[track_caller]
+pub fn init_non_send_resource_from<F, R>(func: F) -> impl Command
+where
+    F: FnOnce(&World) -> R + Send + 'static,
+    R: 'static,
+{
+    let location = Location::caller();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Init,
+        location,
+        move |world: &mut World| {
+            if !world.contains_non_send::<R>() {
+                let resource = (func)(world);
+                world.insert_non_send_resource(resource);
+                tracking::track::<R>(world, location, None);
+            }
+        },
+    )
 }
 
 /// Creates a [`Command`] for inserting a non-[`Send`] resource in the [`World`] with an specific value.
@@ -73,14 +330,48 @@ pub fn init_non_send_resource<R: FromWorld + 'static>() -> impl Command {
 /// #     assert!(my_non_send.0.is_null());
 /// # }
 /// ```
+#[track_caller]
 pub fn insert_non_send_resource<F, R>(func: F) -> impl Command
 where
     F: FnOnce() -> R + Send + 'static,
     R: 'static,
 {
-    move |world: &mut World| {
-        world.insert_non_send_resource((func)());
-    }
+    NonSendCommand::insert(func)
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource in the [`World`] with a closure that may be called more than once.
+///
+/// This behaves like [`insert_non_send_resource`], except that `func` is [`Fn`] instead of [`FnOnce`]. This allows a [`NonSendRecorder`] to replay the command later with a [`NonSendReplayPlugin`], which is not possible for commands that consume their closure.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::insert_non_send_resource_replayable;
+/// #
+/// struct MyNonSend(*const u8);
+///
+/// fn create_my_non_send(mut commands: Commands) {
+///     commands.add(
+///         insert_non_send_resource_replayable(|| {
+///             MyNonSend(std::ptr::null())
+///         })
+///     );
+/// }
+/// #
+/// # App::new()
+/// #     .add_systems(Startup, (create_my_non_send, check).chain())
+/// #     .run();
+/// #
+/// # fn check(my_non_send: NonSend<MyNonSend>) {
+/// #     assert!(my_non_send.0.is_null());
+/// # }
+/// ```
+#[track_caller]
+pub fn insert_non_send_resource_replayable<F, R>(func: F) -> impl Command
+where
+    F: Fn() -> R + Send + Sync + 'static,
+    R: 'static,
+{
+    NonSendCommand::insert_replayable(func)
 }
 
 /// Creates a [`Command`] for removing a non-[`Send`] resource from the [`World`].
@@ -108,10 +399,210 @@ where
 /// #     assert!(my_non_send.is_none());
 /// # }
 /// ```
+#[track_caller]
 pub fn remove_non_send_resource<R: 'static>() -> impl Command {
-    |world: &mut World| {
-        world.remove_non_send_resource::<R>();
-    }
+    NonSendCommand::remove::<R>()
+}
+
+/// Creates a [`Command`] for removing a non-[`Send`] resource from the [`World`], passing a [`NonSendCommandError::ResourceMissing`] error to the [`NonSendErrorHandler`] if it does not exist.
+///
+/// See [`remove_non_send_resource`] for more details.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{try_remove_non_send_resource, NonSendErrorHandler};
+/// #
+/// struct MyNonSend(*const u8);
+///
+/// fn remove_my_non_send(mut commands: Commands) {
+///     commands.add(
+///         try_remove_non_send_resource::<MyNonSend>()
+///     );
+/// }
+/// #
+/// # App::new()
+/// #     .insert_resource(NonSendErrorHandler(|_, _| panic!("`MyNonSend` should exist.")))
+/// #     .insert_non_send_resource(MyNonSend(std::ptr::null()))
+/// #     .add_systems(Startup, (remove_my_non_send, check).chain())
+/// #     .run();
+/// #
+/// # fn check(my_non_send: Option<NonSend<MyNonSend>>) {
+/// #     assert!(my_non_send.is_none());
+/// # }
+/// ```
+#[track_caller]
+pub fn try_remove_non_send_resource<R: 'static>() -> impl Command {
+    let context = NonSendErrorContext {
+        command: "try_remove_non_send_resource",
+        location: Location::caller(),
+    };
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Remove,
+        context.location,
+        move |world: &mut World| {
+            let result = match tracking::remove::<R>(world) {
+                true => Ok(()),
+                false => Err(NonSendCommandError::ResourceMissing {
+                    type_name: std::any::type_name::<R>(),
+                }),
+            };
+
+            error::handle_error(world, result, context);
+        },
+    )
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource in the [`World`] with an inferred value, passing a [`NonSendCommandError::ResourceExists`] error to the [`NonSendErrorHandler`] if it already exists.
+///
+/// See [`init_non_send_resource`] for more details.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{try_init_non_send_resource, NonSendErrorHandler};
+/// #
+/// #[derive(Default)]
+/// struct MyNonSend(u32);
+///
+/// fn create_my_non_send(mut commands: Commands) {
+///     commands.add(
+///         try_init_non_send_resource::<MyNonSend>()
+///     );
+/// }
+/// #
+/// # App::new()
+/// #     .insert_resource(NonSendErrorHandler(|_, _| panic!("`MyNonSend` should not exist.")))
+/// #     .add_systems(Startup, (create_my_non_send, check).chain())
+/// #     .run();
+/// #
+/// # fn check(my_non_send: NonSend<MyNonSend>) {
+/// #     assert_eq!(my_non_send.0, 0);
+/// # }
+/// ```
+#[track_caller]
+pub fn try_init_non_send_resource<R: FromWorld + 'static>() -> impl Command {
+    let context = NonSendErrorContext {
+        command: "try_init_non_send_resource",
+        location: Location::caller(),
+    };
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Init,
+        context.location,
+        move |world: &mut World| {
+            let result = match world.contains_non_send::<R>() {
+                true => Err(NonSendCommandError::ResourceExists {
+                    type_name: std::any::type_name::<R>(),
+                }),
+                false => {
+                    tracking::init::<R>(world, context.location);
+                    Ok(())
+                }
+            };
+
+            error::handle_error(world, result, context);
+        },
+    )
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource in the [`World`] with an specific value, passing a [`NonSendCommandError::ResourceExists`] error to the [`NonSendErrorHandler`] instead of replacing it if it already exists.
+///
+/// `func` is not called if the resource already exists. See [`insert_non_send_resource`] for more details.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{try_insert_non_send_resource, NonSendErrorHandler};
+/// #
+/// struct MyNonSend(u32);
+///
+/// fn create_my_non_send(mut commands: Commands) {
+///     // `MyNonSend` already exists, so this will call the error handler.
+///     commands.add(
+///         try_insert_non_send_resource(|| MyNonSend(2))
+///     );
+/// }
+/// #
+/// # App::new()
+/// #     .insert_resource(NonSendErrorHandler::IGNORE)
+/// #     .insert_non_send_resource(MyNonSend(1))
+/// #     .add_systems(Startup, (create_my_non_send, check).chain())
+/// #     .run();
+/// #
+/// # fn check(my_non_send: NonSend<MyNonSend>) {
+/// #     assert_eq!(my_non_send.0, 1);
+/// # }
+/// ```
+#[track_caller]
+pub fn try_insert_non_send_resource<F, R>(func: F) -> impl Command
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: 'static,
+{
+    let context = NonSendErrorContext {
+        command: "try_insert_non_send_resource",
+        location: Location::caller(),
+    };
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        context.location,
+        move |world: &mut World| {
+            let result = match world.contains_non_send::<R>() {
+                true => Err(NonSendCommandError::ResourceExists {
+                    type_name: std::any::type_name::<R>(),
+                }),
+                false => {
+                    world.insert_non_send_resource((func)());
+                    tracking::track::<R>(world, context.location, None);
+                    Ok(())
+                }
+            };
+
+            error::handle_error(world, result, context);
+        },
+    )
+}
+
+/// Creates a [`Command`] that runs a labeled closure on the main thread with exclusive access to the [`World`].
+///
+/// This is meant for one-off main-thread work that does not map to inserting or removing a resource, such as rebuilding a tray icon. The closure runs inside an `info` tracing span named `queue_non_send`, which records `label` and the location of the code that queued the command.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::queue_non_send;
+/// #
+/// struct TrayIcon(*const u8, u32);
+///
+/// fn rebuild_tray_icon(mut commands: Commands) {
+///     commands.add(
+///         queue_non_send("rebuild_tray_icon", |world: &mut World| {
+///             world.non_send_resource_mut::<TrayIcon>().1 += 1;
+///         })
+///     );
+/// }
+/// #
+/// # App::new()
+/// #     .insert_non_send_resource(TrayIcon(std::ptr::null(), 0))
+/// #     .add_systems(Startup, (rebuild_tray_icon, check).chain())
+/// #     .run();
+/// #
+/// # fn check(tray_icon: NonSend<TrayIcon>) {
+/// #     assert_eq!(tray_icon.1, 1);
+/// # }
+/// ```
+#[track_caller]
+pub fn queue_non_send<F>(label: impl Into<Cow<'static, str>>, func: F) -> impl Command
+where
+    F: FnOnce(&mut World) + Send + 'static,
+{
+    let label = label.into();
+    let location = Location::caller();
+
+    main_thread::deferrable(move |world: &mut World| {
+        let _span = info_span!("queue_non_send", label = &*label, %location).entered();
+
+        func(world);
+    })
 }
 
 /// Extensions to [`Commands`] that allow you to call [`init_non_send_resource`], [`insert_non_send_resource`], and [`remove_non_send_resource`].
@@ -171,6 +662,37 @@ pub trait CommandsExt: private::Sealed {
         F: FnOnce() -> R + Send + 'static,
         R: 'static;
 
+    /// See [`insert_non_send_resource_replayable`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendRecordPlugin, NonSendRecorder};
+    /// #
+    /// struct MyNonSend(*const u8);
+    ///
+    /// fn create_my_non_send(mut commands: Commands) {
+    ///     commands.insert_non_send_resource_replayable(|| {
+    ///         MyNonSend(std::ptr::null())
+    ///     });
+    /// }
+    ///
+    /// let mut app = App::new();
+    ///
+    /// app.add_plugins(NonSendRecordPlugin)
+    ///     .add_systems(Startup, create_my_non_send);
+    ///
+    /// app.update();
+    ///
+    /// let recording = app.world.resource_mut::<NonSendRecorder>().take_recording();
+    ///
+    /// assert_eq!(recording.commands().len(), 1);
+    /// assert!(recording.commands()[0].is_replayable());
+    /// ```
+    fn insert_non_send_resource_replayable<F, R>(&mut self, func: F)
+    where
+        F: Fn() -> R + Send + Sync + 'static,
+        R: 'static;
+
     /// See [`remove_non_send_resource`].
     ///
     /// ```
@@ -193,31 +715,2099 @@ pub trait CommandsExt: private::Sealed {
     /// # }
     /// ```
     fn remove_non_send_resource<R: 'static>(&mut self);
-}
-
-impl CommandsExt for Commands<'_, '_> {
-    fn init_non_send_resource<R: FromWorld + 'static>(&mut self) {
-        self.add(init_non_send_resource::<R>());
-    }
-
-    fn insert_non_send_resource<F, R>(&mut self, func: F)
-    where
-        F: FnOnce() -> R + Send + 'static,
-        R: 'static,
-    {
-        self.add(insert_non_send_resource(func));
-    }
 
-    fn remove_non_send_resource<R: 'static>(&mut self) {
-        self.add(remove_non_send_resource::<R>());
-    }
-}
+    /// See [`save_non_send_resources`].
+    ///
+    /// ```
+    /// # use std::io::{Read, Write};
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{AppExt, CommandsExt};
+    /// #
+    /// struct Interpreter(std::rc::Rc<String>);
+    ///
+    /// fn save_game(mut commands: Commands) {
+    ///     commands.save_non_send_resources(Vec::new());
+    /// }
+    ///
+    /// App::new()
+    ///     .register_non_send_persistence::<Interpreter>(
+    ///         |interpreter, writer| writer.write_all(interpreter.0.as_bytes()),
+    ///         |reader| {
+    ///             let mut state = String::new();
+    ///             reader.read_to_string(&mut state)?;
+    ///             Ok(Interpreter(state.into()))
+    ///         },
+    ///     )
+    ///     .add_systems(Startup, save_game)
+    ///     .run();
+    /// ```
+    fn save_non_send_resources<W: Write + Send + 'static>(&mut self, writer: W);
 
-// Sealed trait used to prevent others from implementing `CommandsExt`.
-mod private {
-    use bevy_ecs::system::Commands;
+    /// See [`load_non_send_resources`].
+    ///
+    /// ```
+    /// # use std::io::{Read, Write};
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{save_non_send_resources_to, AppExt, CommandsExt};
+    /// #
+    /// struct Interpreter(std::rc::Rc<String>);
+    ///
+    /// fn load_game(mut commands: Commands) {
+    /// #   let save_file = {
+    /// #       let mut world = World::new();
+    /// #       world.init_resource::<bevy_command_non_send::NonSendPersistence>();
+    /// #       world.resource_mut::<bevy_command_non_send::NonSendPersistence>().register::<Interpreter, _, _>(
+    /// #           |interpreter, writer| writer.write_all(interpreter.0.as_bytes()),
+    /// #           |_| unreachable!(),
+    /// #       );
+    /// #       world.insert_non_send_resource(Interpreter(String::from("x = 1").into()));
+    /// #       let mut buffer = Vec::new();
+    /// #       save_non_send_resources_to(&world, &mut buffer).unwrap();
+    /// #       std::io::Cursor::new(buffer)
+    /// #   };
+    ///     commands.load_non_send_resources(save_file);
+    /// }
+    ///
+    /// App::new()
+    ///     .register_non_send_persistence::<Interpreter>(
+    ///         |interpreter, writer| writer.write_all(interpreter.0.as_bytes()),
+    ///         |reader| {
+    ///             let mut state = String::new();
+    ///             reader.read_to_string(&mut state)?;
+    ///             Ok(Interpreter(state.into()))
+    ///         },
+    ///     )
+    ///     .add_systems(Startup, (load_game, check).chain())
+    ///     .run();
+    /// #
+    /// # fn check(interpreter: NonSend<Interpreter>) {
+    /// #     assert_eq!(*interpreter.0, "x = 1");
+    /// # }
+    /// ```
+    fn load_non_send_resources<R: Read + Send + 'static>(&mut self, reader: R);
 
-    pub trait Sealed {}
+    /// See [`insert_non_send_reflect`].
+    ///
+    /// ```
+    /// # use std::any::TypeId;
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, ReflectNonSend};
+    /// #
+    /// #[derive(Reflect, Default)]
+    /// #[reflect(NonSend)]
+    /// struct MyNonSend(u8);
+    ///
+    /// fn create_my_non_send(mut commands: Commands) {
+    ///     commands.insert_non_send_reflect(TypeId::of::<MyNonSend>(), Box::new(MyNonSend(5)));
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .register_type::<MyNonSend>()
+    /// #     .add_systems(Startup, (create_my_non_send, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(my_non_send: NonSend<MyNonSend>) {
+    /// #     assert_eq!(my_non_send.0, 5);
+    /// # }
+    /// ```
+    #[cfg(feature = "bevy_reflect")]
+    fn insert_non_send_reflect(
+        &mut self,
+        type_id: std::any::TypeId,
+        value: Box<dyn bevy_reflect::Reflect>,
+    );
 
-    impl Sealed for Commands<'_, '_> {}
+    /// See [`insert_non_send_resource_catch_unwind`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendFactoryPanicked};
+    /// #
+    /// struct MyNonSend(*const u8);
+    ///
+    /// fn create_my_non_send(mut commands: Commands) {
+    ///     commands.insert_non_send_resource_catch_unwind(|| -> MyNonSend {
+    ///         panic!("Unsupported hardware!");
+    ///     });
+    /// }
+    ///
+    /// fn handle_panics(mut events: EventReader<NonSendFactoryPanicked>) {
+    ///     for event in events.read() {
+    ///         eprintln!("Failed to create {}: {:?}", event.type_name, event.message);
+    ///     }
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_event::<NonSendFactoryPanicked>()
+    /// #     .add_systems(Startup, (create_my_non_send, handle_panics, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(my_non_send: Option<NonSend<MyNonSend>>, events: Res<Events<NonSendFactoryPanicked>>) {
+    /// #     assert!(my_non_send.is_none());
+    /// #     assert_eq!(events.len(), 1);
+    /// # }
+    /// ```
+    fn insert_non_send_resource_catch_unwind<F, R>(&mut self, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static;
+
+    /// See [`try_insert_non_send_resource_catch_unwind`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendErrorHandler};
+    /// #
+    /// struct MyNonSend(*const u8);
+    ///
+    /// fn create_my_non_send(mut commands: Commands) {
+    ///     commands.try_insert_non_send_resource_catch_unwind(|| -> MyNonSend {
+    ///         panic!("Unsupported hardware!");
+    ///     });
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_resource(NonSendErrorHandler::WARN)
+    /// #     .add_systems(Startup, (create_my_non_send, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(my_non_send: Option<NonSend<MyNonSend>>) {
+    /// #     assert!(my_non_send.is_none());
+    /// # }
+    /// ```
+    fn try_insert_non_send_resource_catch_unwind<F, R>(&mut self, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static;
+
+    /// See [`try_init_non_send_resource`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendErrorHandler};
+    /// #
+    /// #[derive(Default)]
+    /// struct MyNonSend(u32);
+    ///
+    /// fn create_my_non_send(mut commands: Commands) {
+    ///     commands.try_init_non_send_resource::<MyNonSend>();
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_resource(NonSendErrorHandler(|_, _| panic!("`MyNonSend` should not exist.")))
+    /// #     .add_systems(Startup, (create_my_non_send, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(my_non_send: NonSend<MyNonSend>) {
+    /// #     assert_eq!(my_non_send.0, 0);
+    /// # }
+    /// ```
+    fn try_init_non_send_resource<R: FromWorld + 'static>(&mut self);
+
+    /// See [`try_insert_non_send_resource`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendErrorHandler};
+    /// #
+    /// struct MyNonSend(u32);
+    ///
+    /// fn create_my_non_send(mut commands: Commands) {
+    ///     // `MyNonSend` already exists, so this will call the error handler.
+    ///     commands.try_insert_non_send_resource(|| MyNonSend(2));
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_resource(NonSendErrorHandler::IGNORE)
+    /// #     .insert_non_send_resource(MyNonSend(1))
+    /// #     .add_systems(Startup, (create_my_non_send, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(my_non_send: NonSend<MyNonSend>) {
+    /// #     assert_eq!(my_non_send.0, 1);
+    /// # }
+    /// ```
+    fn try_insert_non_send_resource<F, R>(&mut self, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static;
+
+    /// See [`try_remove_non_send_resource`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendErrorHandler};
+    /// #
+    /// struct MyNonSend(*const u8);
+    ///
+    /// fn remove_my_non_send(mut commands: Commands) {
+    ///     commands.try_remove_non_send_resource::<MyNonSend>();
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_resource(NonSendErrorHandler(|_, _| panic!("`MyNonSend` should exist.")))
+    /// #     .insert_non_send_resource(MyNonSend(std::ptr::null()))
+    /// #     .add_systems(Startup, (remove_my_non_send, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(my_non_send: Option<NonSend<MyNonSend>>) {
+    /// #     assert!(my_non_send.is_none());
+    /// # }
+    /// ```
+    fn try_remove_non_send_resource<R: 'static>(&mut self);
+
+    /// See [`try_save_non_send_resources`].
+    ///
+    /// ```
+    /// # use std::io::{self, Read, Write};
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{AppExt, CommandsExt, NonSendErrorHandler};
+    /// #
+    /// struct Interpreter(std::rc::Rc<String>);
+    ///
+    /// struct ReadOnlyFile;
+    ///
+    /// impl Write for ReadOnlyFile {
+    ///     fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+    ///         Err(io::ErrorKind::PermissionDenied.into())
+    ///     }
+    ///
+    ///     fn flush(&mut self) -> io::Result<()> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// fn save_game(mut commands: Commands) {
+    ///     // The file cannot be written to, so this will call the error handler.
+    ///     commands.try_save_non_send_resources(ReadOnlyFile);
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_resource(NonSendErrorHandler::WARN)
+    /// #     .register_non_send_persistence::<Interpreter>(
+    /// #         |interpreter, writer| writer.write_all(interpreter.0.as_bytes()),
+    /// #         |reader| {
+    /// #             let mut state = String::new();
+    /// #             reader.read_to_string(&mut state)?;
+    /// #             Ok(Interpreter(state.into()))
+    /// #         },
+    /// #     )
+    /// #     .insert_non_send_resource(Interpreter(String::from("x = 1").into()))
+    /// #     .add_systems(Startup, save_game)
+    /// #     .run();
+    /// ```
+    fn try_save_non_send_resources<W: Write + Send + 'static>(&mut self, writer: W);
+
+    /// See [`try_load_non_send_resources`].
+    ///
+    /// ```
+    /// # use std::io::{Read, Write};
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{AppExt, CommandsExt, NonSendErrorHandler};
+    /// #
+    /// struct Interpreter(std::rc::Rc<String>);
+    ///
+    /// fn load_game(mut commands: Commands) {
+    ///     // The save file is truncated, so this will call the error handler.
+    ///     commands.try_load_non_send_resources(std::io::Cursor::new(vec![1]));
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_resource(NonSendErrorHandler::WARN)
+    /// #     .register_non_send_persistence::<Interpreter>(
+    /// #         |interpreter, writer| writer.write_all(interpreter.0.as_bytes()),
+    /// #         |reader| {
+    /// #             let mut state = String::new();
+    /// #             reader.read_to_string(&mut state)?;
+    /// #             Ok(Interpreter(state.into()))
+    /// #         },
+    /// #     )
+    /// #     .add_systems(Startup, (load_game, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(interpreter: Option<NonSend<Interpreter>>) {
+    /// #     assert!(interpreter.is_none());
+    /// # }
+    /// ```
+    fn try_load_non_send_resources<R: Read + Send + 'static>(&mut self, reader: R);
+
+    /// See [`try_insert_non_send_reflect`].
+    ///
+    /// ```
+    /// # use std::any::TypeId;
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendErrorHandler};
+    /// #
+    /// #[derive(Reflect, Default)]
+    /// struct MyNonSend(u8);
+    ///
+    /// fn create_my_non_send(mut commands: Commands) {
+    ///     // `MyNonSend` does not reflect `NonSend`, so this will call the error handler.
+    ///     commands.try_insert_non_send_reflect(TypeId::of::<MyNonSend>(), Box::new(MyNonSend(5)));
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_resource(NonSendErrorHandler::WARN)
+    /// #     .register_type::<MyNonSend>()
+    /// #     .add_systems(Startup, (create_my_non_send, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(my_non_send: Option<NonSend<MyNonSend>>) {
+    /// #     assert!(my_non_send.is_none());
+    /// # }
+    /// ```
+    #[cfg(feature = "bevy_reflect")]
+    fn try_insert_non_send_reflect(
+        &mut self,
+        type_id: std::any::TypeId,
+        value: Box<dyn bevy_reflect::Reflect>,
+    );
+
+    /// See [`init_non_send_resource_from`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::CommandsExt;
+    /// #
+    /// #[derive(Resource)]
+    /// struct Config {
+    ///     scale: u8,
+    /// }
+    ///
+    /// struct MyNonSend(*const u8, u8);
+    ///
+    /// fn create_my_non_send(mut commands: Commands) {
+    ///     commands.init_non_send_resource_from(|world: &World| {
+    ///         MyNonSend(std::ptr::null(), world.resource::<Config>().scale)
+    ///     });
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_resource(Config { scale: 2 })
+    /// #     .add_systems(Startup, (create_my_non_send, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(my_non_send: NonSend<MyNonSend>) {
+    /// #     assert_eq!(my_non_send.1, 2);
+    /// # }
+    /// ```
+    fn init_non_send_resource_from<F, R>(&mut self, func: F)
+    where
+        F: FnOnce(&World) -> R + Send + 'static,
+        R: 'static;
+
+    /// See [`insert_non_send_with_winit_windows`].
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy::winit::WinitWindows;
+    /// # use bevy_command_non_send::CommandsExt;
+    /// #
+    /// struct NativeMenus(Vec<*const u8>);
+    ///
+    /// fn create_menus(mut commands: Commands) {
+    ///     commands.insert_non_send_with_winit_windows(|windows: &WinitWindows| {
+    ///         NativeMenus(windows.windows.keys().map(|_| std::ptr::null()).collect())
+    ///     });
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_plugins(DefaultPlugins)
+    /// #     .add_systems(Startup, create_menus)
+    /// #     .run();
+    /// ```
+    #[cfg(feature = "winit")]
+    fn insert_non_send_with_winit_windows<F, R>(&mut self, func: F)
+    where
+        F: FnOnce(&bevy_winit::WinitWindows) -> R + Send + 'static,
+        R: 'static;
+
+    /// See [`insert_non_send_with_window`].
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy::window::PrimaryWindow;
+    /// # use bevy_command_non_send::CommandsExt;
+    /// #
+    /// struct ImeHandler(*const u8);
+    ///
+    /// fn create_ime_handler(mut commands: Commands, window: Query<Entity, Added<PrimaryWindow>>) {
+    ///     if let Ok(window) = window.get_single() {
+    ///         commands.insert_non_send_with_window(window, |_window| {
+    ///             ImeHandler(std::ptr::null())
+    ///         });
+    ///     }
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_plugins(DefaultPlugins)
+    /// #     .add_systems(PostUpdate, create_ime_handler)
+    /// #     .run();
+    /// ```
+    #[cfg(feature = "winit")]
+    fn insert_non_send_with_window<F, R>(&mut self, window: bevy_ecs::entity::Entity, func: F)
+    where
+        F: FnOnce(&::winit::window::Window) -> R + Send + 'static,
+        R: 'static;
+
+    /// See [`wrap_non_send_resource`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendGuard};
+    /// #
+    /// struct MyNonSend(*const u8);
+    ///
+    /// fn wrap_my_non_send(mut commands: Commands) {
+    ///     commands.wrap_non_send_resource::<MyNonSend>();
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_non_send_resource(MyNonSend(std::ptr::null()))
+    /// #     .add_systems(Startup, (wrap_my_non_send, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(world: &World) {
+    /// #     assert!(world.get_non_send_resource::<MyNonSend>().is_none());
+    /// #     assert!(world.contains_resource::<NonSendGuard<MyNonSend>>());
+    /// # }
+    /// ```
+    fn wrap_non_send_resource<R: 'static>(&mut self);
+
+    /// See [`unwrap_non_send_resource`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendGuard};
+    /// #
+    /// struct MyNonSend(*const u8);
+    ///
+    /// fn unwrap_my_non_send(mut commands: Commands) {
+    ///     commands.unwrap_non_send_resource::<MyNonSend>();
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_resource(NonSendGuard::new(MyNonSend(std::ptr::null())))
+    /// #     .add_systems(Startup, (unwrap_my_non_send, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(my_non_send: NonSend<MyNonSend>) {
+    /// #     assert!(my_non_send.0.is_null());
+    /// # }
+    /// ```
+    fn unwrap_non_send_resource<R: 'static>(&mut self);
+
+    /// See [`remove_non_send_resource_deferred`].
+    ///
+    /// ```
+    /// # use std::sync::atomic::{AtomicBool, Ordering};
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{AppExt, CommandsExt};
+    /// #
+    /// static DROPPED: AtomicBool = AtomicBool::new(false);
+    ///
+    /// struct GpuBuffer(*const u8);
+    ///
+    /// impl Drop for GpuBuffer {
+    ///     fn drop(&mut self) {
+    ///         DROPPED.store(true, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// fn release_buffer(mut commands: Commands) {
+    ///     commands.remove_non_send_resource_deferred::<GpuBuffer>(Last);
+    /// }
+    ///
+    /// fn check_detached(buffer: Option<NonSend<GpuBuffer>>) {
+    ///     // The buffer can no longer be accessed, but it has not been dropped yet.
+    ///     assert!(buffer.is_none());
+    ///     assert!(!DROPPED.load(Ordering::Relaxed));
+    /// }
+    ///
+    /// let mut app = App::new();
+    ///
+    /// app.insert_non_send_resource(GpuBuffer(std::ptr::null()))
+    ///     // `Last` may not have any systems yet, so make sure it exists.
+    ///     .add_non_send_drop_point(Last)
+    ///     .add_systems(Update, release_buffer)
+    ///     .add_systems(PostUpdate, check_detached);
+    ///
+    /// app.update();
+    ///
+    /// assert!(DROPPED.load(Ordering::Relaxed));
+    /// ```
+    fn remove_non_send_resource_deferred<R: 'static>(&mut self, label: impl ScheduleLabel);
+
+    /// See [`insert_non_send_resource_or_else`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendFallbackUsed};
+    /// #
+    /// enum Renderer {
+    ///     Hardware(*const u8),
+    ///     Software,
+    /// }
+    ///
+    /// fn create_renderer(mut commands: Commands) {
+    ///     commands.insert_non_send_resource_or_else(
+    ///         || Err("Unsupported driver!"),
+    ///         || Renderer::Software,
+    ///     );
+    /// }
+    ///
+    /// fn log_fallbacks(mut events: EventReader<NonSendFallbackUsed<&'static str>>) {
+    ///     for event in events.read() {
+    ///         eprintln!("Falling back for {}: {}", event.type_name, event.error);
+    ///     }
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_event::<NonSendFallbackUsed<&'static str>>()
+    /// #     .add_systems(Startup, (create_renderer, log_fallbacks, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(renderer: NonSend<Renderer>, events: Res<Events<NonSendFallbackUsed<&'static str>>>) {
+    /// #     assert!(matches!(*renderer, Renderer::Software));
+    /// #     assert_eq!(events.len(), 1);
+    /// # }
+    /// ```
+    fn insert_non_send_resource_or_else<P, F, R, E>(&mut self, primary: P, fallback: F)
+    where
+        P: FnOnce() -> Result<R, E> + Send + 'static,
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static,
+        E: Send + Sync + 'static;
+
+    /// See [`try_insert_non_send_resource_or_else`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendErrorHandler};
+    /// #
+    /// struct Renderer(*const u8);
+    ///
+    /// fn create_renderer(mut commands: Commands) {
+    ///     commands.try_insert_non_send_resource_or_else(
+    ///         || Err::<Renderer, _>("Unsupported driver!"),
+    ///         || Err("No software renderer!"),
+    ///     );
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_resource(NonSendErrorHandler::WARN)
+    /// #     .add_event::<bevy_command_non_send::NonSendFallbackUsed<&'static str>>()
+    /// #     .add_systems(Startup, (create_renderer, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(renderer: Option<NonSend<Renderer>>) {
+    /// #     assert!(renderer.is_none());
+    /// # }
+    /// ```
+    fn try_insert_non_send_resource_or_else<P, F, R, E>(&mut self, primary: P, fallback: F)
+    where
+        P: FnOnce() -> Result<R, E> + Send + 'static,
+        F: FnOnce() -> Result<R, E> + Send + 'static,
+        R: 'static,
+        E: fmt::Display + Send + Sync + 'static;
+
+    /// See [`queue_non_send`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::CommandsExt;
+    /// #
+    /// struct TrayIcon(*const u8, u32);
+    ///
+    /// fn rebuild_tray_icon(mut commands: Commands) {
+    ///     commands.queue_non_send("rebuild_tray_icon", |world: &mut World| {
+    ///         world.non_send_resource_mut::<TrayIcon>().1 += 1;
+    ///     });
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_non_send_resource(TrayIcon(std::ptr::null(), 0))
+    /// #     .add_systems(Startup, (rebuild_tray_icon, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(tray_icon: NonSend<TrayIcon>) {
+    /// #     assert_eq!(tray_icon.1, 1);
+    /// # }
+    /// ```
+    fn queue_non_send<F>(&mut self, label: impl Into<Cow<'static, str>>, func: F)
+    where
+        F: FnOnce(&mut World) + Send + 'static;
+
+    /// See [`init_non_send_resource_once`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{is_non_send_initialized_once, CommandsExt};
+    /// #
+    /// struct WindowingLibrary(*const u8);
+    ///
+    /// impl Default for WindowingLibrary {
+    ///     fn default() -> Self {
+    ///         WindowingLibrary(std::ptr::null())
+    ///     }
+    /// }
+    ///
+    /// fn init_library(mut commands: Commands) {
+    ///     commands.init_non_send_resource_once::<WindowingLibrary>();
+    /// }
+    ///
+    /// fn shutdown_library(world: &mut World) {
+    ///     world.remove_non_send_resource::<WindowingLibrary>();
+    /// }
+    ///
+    /// App::new()
+    ///     .add_systems(Startup, (init_library, shutdown_library, init_library, check).chain())
+    ///     .run();
+    ///
+    /// fn check(library: Option<NonSend<WindowingLibrary>>) {
+    ///     // The second `init_library` did nothing.
+    ///     assert!(library.is_none());
+    ///     assert!(is_non_send_initialized_once::<WindowingLibrary>());
+    /// }
+    /// ```
+    fn init_non_send_resource_once<R: FromWorld + 'static>(&mut self);
+
+    /// See [`with_temporary_non_send_resource`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::CommandsExt;
+    /// #
+    /// struct Clipboard(*const u8, String);
+    ///
+    /// #[derive(Resource)]
+    /// struct Pasted(String);
+    ///
+    /// fn paste(mut commands: Commands) {
+    ///     commands.with_temporary_non_send_resource(
+    ///         || Clipboard(std::ptr::null(), "Hello!".to_string()),
+    ///         |world: &mut World| {
+    ///             let text = world.non_send_resource::<Clipboard>().1.clone();
+    ///             world.insert_resource(Pasted(text));
+    ///         },
+    ///     );
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, (paste, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(clipboard: Option<NonSend<Clipboard>>, pasted: Res<Pasted>) {
+    /// #     assert!(clipboard.is_none());
+    /// #     assert_eq!(pasted.0, "Hello!");
+    /// # }
+    /// ```
+    fn with_temporary_non_send_resource<F, S, R>(&mut self, func: F, scope: S)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        S: FnOnce(&mut World) + Send + 'static,
+        R: 'static;
+
+    /// See [`insert_non_send_resource_for_frame`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendFramePlugin};
+    /// #
+    /// struct MonitorLayout(*const u8);
+    ///
+    /// fn query_monitors(mut commands: Commands) {
+    ///     commands.insert_non_send_resource_for_frame(|| {
+    ///         MonitorLayout(std::ptr::null())
+    ///     });
+    /// }
+    ///
+    /// fn use_monitors(layout: NonSend<MonitorLayout>) {
+    ///     assert!(layout.0.is_null());
+    /// }
+    ///
+    /// let mut app = App::new();
+    ///
+    /// app.add_plugins(NonSendFramePlugin)
+    ///     .add_systems(PreUpdate, query_monitors)
+    ///     .add_systems(Update, use_monitors);
+    ///
+    /// app.update();
+    ///
+    /// assert!(!app.world.contains_non_send::<MonitorLayout>());
+    /// ```
+    fn insert_non_send_resource_for_frame<F, R>(&mut self, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static;
+
+    /// See [`ffi_init_once`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendFfiRegistry};
+    /// #
+    /// fn init_audio(mut commands: Commands) {
+    ///     commands.ffi_init_once("openal", || {
+    ///         // Call into the native library here.
+    ///     });
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, (init_audio, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(registry: NonSend<NonSendFfiRegistry>) {
+    /// #     assert!(registry.is_initialized("openal"));
+    /// # }
+    /// ```
+    fn ffi_init_once<F>(&mut self, key: &'static str, init: F)
+    where
+        F: FnOnce() + Send + 'static;
+
+    /// See [`ffi_shutdown`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::CommandsExt;
+    /// #
+    /// fn init_audio(mut commands: Commands) {
+    ///     commands.ffi_init_once("openal", || {});
+    ///
+    ///     commands.ffi_shutdown("openal", || {
+    ///         // Release the native library here.
+    ///     });
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, init_audio)
+    /// #     .run();
+    /// ```
+    fn ffi_shutdown<F>(&mut self, key: &'static str, shutdown: F)
+    where
+        F: FnOnce() + Send + 'static;
+
+    /// See [`insert_non_send_double_buffer`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendDoubleBuffer};
+    /// #
+    /// #[derive(Default)]
+    /// struct NativeMesh(*const u8, Vec<u32>);
+    ///
+    /// fn create_mesh(mut commands: Commands) {
+    ///     commands.insert_non_send_double_buffer(NativeMesh::default);
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, (create_mesh, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(mesh: NonSend<NonSendDoubleBuffer<NativeMesh>>) {
+    /// #     assert!(mesh.front().1.is_empty());
+    /// # }
+    /// ```
+    fn insert_non_send_double_buffer<F, R>(&mut self, func: F)
+    where
+        F: Fn() -> R + Send + 'static,
+        R: 'static;
+
+    /// See [`swap_non_send_buffers`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendDoubleBuffer};
+    /// #
+    /// struct NativeMesh(*const u8, Vec<u32>);
+    ///
+    /// fn publish_mesh(mut commands: Commands) {
+    ///     commands.swap_non_send_buffers::<NativeMesh>();
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_non_send_resource(NonSendDoubleBuffer::new(
+    /// #         NativeMesh(std::ptr::null(), vec![]),
+    /// #         NativeMesh(std::ptr::null(), vec![1]),
+    /// #     ))
+    /// #     .add_systems(Startup, (publish_mesh, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(mesh: NonSend<NonSendDoubleBuffer<NativeMesh>>) {
+    /// #     assert_eq!(mesh.front().1, [1]);
+    /// # }
+    /// ```
+    fn swap_non_send_buffers<R: 'static>(&mut self);
+
+    /// Queues `command`, returning a [`CommandTicket`] that reports when it was applied.
+    ///
+    /// See [`with_ticket`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{insert_non_send_resource, CommandTicket, CommandsExt, TicketStatus};
+    /// #
+    /// struct GlContext(*const u8);
+    ///
+    /// #[derive(Resource)]
+    /// struct ContextTicket(CommandTicket);
+    ///
+    /// fn create_context(mut commands: Commands) {
+    ///     let ticket = commands.add_with_ticket(insert_non_send_resource(|| {
+    ///         GlContext(std::ptr::null())
+    ///     }));
+    ///
+    ///     // The ticket remembers where it was created, which helps finding commands that never run.
+    ///     assert!(ticket.location().file().ends_with(".rs"));
+    ///
+    ///     commands.insert_resource(ContextTicket(ticket));
+    /// }
+    ///
+    /// fn check_context(ticket: Res<ContextTicket>) {
+    ///     assert_eq!(ticket.0.status(), TicketStatus::Succeeded);
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, (create_context, check_context).chain())
+    /// #     .run();
+    /// ```
+    fn add_with_ticket<C: Command>(&mut self, command: C) -> CommandTicket;
+
+    /// Queues [`init_non_send_resource`], returning a [`CommandTicket`] that reports when it was applied.
+    ///
+    /// See [`CommandsExt::add_with_ticket`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandTicket, CommandsExt, TicketStatus};
+    /// #
+    /// #[derive(Default)]
+    /// struct AudioDevice(std::rc::Rc<u8>);
+    ///
+    /// #[derive(Resource)]
+    /// struct DeviceTicket(CommandTicket);
+    ///
+    /// fn open_device(mut commands: Commands) {
+    ///     let ticket = commands.init_non_send_resource_with_ticket::<AudioDevice>();
+    ///     commands.insert_resource(DeviceTicket(ticket));
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, (open_device, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(ticket: Res<DeviceTicket>) {
+    /// #     assert_eq!(ticket.0.status(), TicketStatus::Succeeded);
+    /// # }
+    /// ```
+    fn init_non_send_resource_with_ticket<R: FromWorld + 'static>(&mut self) -> CommandTicket;
+
+    /// Queues [`insert_non_send_resource`], returning a [`CommandTicket`] that reports when it was applied.
+    ///
+    /// See [`CommandsExt::add_with_ticket`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, TicketStatus};
+    /// #
+    /// struct GlContext(*const u8);
+    ///
+    /// fn create_context(mut commands: Commands) {
+    ///     let ticket = commands.insert_non_send_resource_with_ticket(|| {
+    ///         GlContext(std::ptr::null())
+    ///     });
+    ///
+    ///     // The command has only been queued so far.
+    ///     assert_eq!(ticket.status(), TicketStatus::Pending);
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, create_context)
+    /// #     .run();
+    /// ```
+    fn insert_non_send_resource_with_ticket<F, R>(&mut self, func: F) -> CommandTicket
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static;
+
+    /// Queues [`remove_non_send_resource`], returning a [`CommandTicket`] that reports when it was applied.
+    ///
+    /// See [`CommandsExt::add_with_ticket`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandTicket, CommandsExt, TicketStatus};
+    /// #
+    /// struct GlContext(*const u8);
+    ///
+    /// #[derive(Resource)]
+    /// struct TeardownTicket(CommandTicket);
+    ///
+    /// fn destroy_context(mut commands: Commands) {
+    ///     let ticket = commands.remove_non_send_resource_with_ticket::<GlContext>();
+    ///     commands.insert_resource(TeardownTicket(ticket));
+    /// }
+    ///
+    /// fn check_teardown(ticket: Res<TeardownTicket>, context: Option<NonSend<GlContext>>) {
+    ///     assert_eq!(ticket.0.status(), TicketStatus::Succeeded);
+    ///     assert!(context.is_none());
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_non_send_resource(GlContext(std::ptr::null()))
+    /// #     .add_systems(Startup, (destroy_context, check_teardown).chain())
+    /// #     .run();
+    /// ```
+    fn remove_non_send_resource_with_ticket<R: 'static>(&mut self) -> CommandTicket;
+
+    /// See [`run_lua_chunk`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, LuaNonSendPlugin};
+    /// #
+    /// fn reset_score(mut commands: Commands) {
+    ///     commands.run_lua_chunk("score = 0");
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_plugins(LuaNonSendPlugin)
+    /// #     .add_systems(Startup, (reset_score, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(lua: NonSend<mlua::Lua>) {
+    /// #     assert_eq!(lua.globals().get::<_, i32>("score").unwrap(), 0);
+    /// # }
+    /// ```
+    #[cfg(feature = "mlua")]
+    fn run_lua_chunk(&mut self, source: impl Into<String>);
+
+    /// See [`with_lua`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, LuaNonSendPlugin};
+    /// # use mlua::Lua;
+    /// #
+    /// fn register_api(mut commands: Commands) {
+    ///     commands.with_lua(|lua: &Lua| {
+    ///         let greet = lua.create_function(|_, name: String| Ok(format!("Hello, {name}!"))).unwrap();
+    ///         lua.globals().set("greet", greet).unwrap();
+    ///     });
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_plugins(LuaNonSendPlugin)
+    /// #     .add_systems(Startup, (register_api, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(lua: NonSend<Lua>) {
+    /// #     let greeting: String = lua.load("greet('Bevy')").eval().unwrap();
+    /// #     assert_eq!(greeting, "Hello, Bevy!");
+    /// # }
+    /// ```
+    #[cfg(feature = "mlua")]
+    fn with_lua<F>(&mut self, func: F)
+    where
+        F: FnOnce(&mlua::Lua) + Send + 'static;
+
+    /// See [`insert_non_send_with_gil`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::CommandsExt;
+    /// # use pyo3::{prelude::*, types::PyDict};
+    /// #
+    /// struct PythonGlobals(Py<PyDict>);
+    ///
+    /// fn create_globals(mut commands: Commands) {
+    ///     commands.insert_non_send_with_gil(|py: Python| PythonGlobals(PyDict::new_bound(py).unbind()));
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, (create_globals, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(globals: NonSend<PythonGlobals>) {
+    /// #     Python::with_gil(|py| assert!(globals.0.bind(py).is_empty()));
+    /// # }
+    /// ```
+    #[cfg(feature = "pyo3")]
+    fn insert_non_send_with_gil<F, R>(&mut self, func: F)
+    where
+        F: for<'py> FnOnce(pyo3::Python<'py>) -> R + Send + 'static,
+        R: 'static;
+
+    /// See [`with_gil_command`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::CommandsExt;
+    /// # use pyo3::{prelude::*, types::PyDict};
+    /// #
+    /// struct PythonGlobals(Py<PyDict>);
+    ///
+    /// fn set_answer(mut commands: Commands) {
+    ///     commands.with_gil_command(|py: Python, globals: &mut PythonGlobals| {
+    ///         globals.0.bind(py).set_item("answer", 42).unwrap();
+    ///     });
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_non_send_resource(Python::with_gil(|py| PythonGlobals(PyDict::new_bound(py).unbind())))
+    /// #     .add_systems(Startup, (set_answer, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(globals: NonSend<PythonGlobals>) {
+    /// #     Python::with_gil(|py| assert_eq!(globals.0.bind(py).len(), 1));
+    /// # }
+    /// ```
+    #[cfg(feature = "pyo3")]
+    fn with_gil_command<R, F>(&mut self, func: F)
+    where
+        R: 'static,
+        F: for<'py> FnOnce(pyo3::Python<'py>, &mut R) + Send + 'static;
+
+    /// See [`insert_non_send_with_window_handle`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy::window::{PrimaryWindow, RawHandleWrapper};
+    /// # use bevy_command_non_send::CommandsExt;
+    /// # use raw_window_handle::{RawDisplayHandle, RawWindowHandle, WebDisplayHandle, WebWindowHandle};
+    /// #
+    /// struct Surface(*const u8, RawWindowHandle);
+    ///
+    /// fn create_surface(mut commands: Commands, window: Query<Entity, With<PrimaryWindow>>) {
+    ///     commands.insert_non_send_with_window_handle(window.single(), |window, _display| {
+    ///         Surface(std::ptr::null(), window)
+    ///     });
+    /// }
+    /// #
+    /// # let mut app = App::new();
+    /// #
+    /// # app.world.spawn((
+    /// #     PrimaryWindow,
+    /// #     RawHandleWrapper {
+    /// #         window_handle: RawWindowHandle::Web(WebWindowHandle::new(1)),
+    /// #         display_handle: RawDisplayHandle::Web(WebDisplayHandle::new()),
+    /// #     },
+    /// # ));
+    /// #
+    /// # app.add_systems(Startup, (create_surface, check).chain()).run();
+    /// #
+    /// # fn check(surface: NonSend<Surface>) {
+    /// #     assert_eq!(surface.1, RawWindowHandle::Web(WebWindowHandle::new(1)));
+    /// # }
+    /// ```
+    #[cfg(feature = "raw-window-handle")]
+    fn insert_non_send_with_window_handle<F, R>(
+        &mut self,
+        window: bevy_ecs::entity::Entity,
+        func: F,
+    ) where
+        F: FnOnce(raw_window_handle::RawWindowHandle, raw_window_handle::RawDisplayHandle) -> R
+            + Send
+            + 'static,
+        R: 'static;
+
+    /// See [`insert_non_send_with_primary_window_handle`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy::window::{PrimaryWindow, RawHandleWrapper};
+    /// # use bevy_command_non_send::CommandsExt;
+    /// # use raw_window_handle::{RawDisplayHandle, RawWindowHandle, WebDisplayHandle, WebWindowHandle};
+    /// #
+    /// struct Surface(*const u8, RawWindowHandle);
+    ///
+    /// fn create_surface(mut commands: Commands) {
+    ///     commands.insert_non_send_with_primary_window_handle(|window, _display| {
+    ///         Surface(std::ptr::null(), window)
+    ///     });
+    /// }
+    /// #
+    /// # let mut app = App::new();
+    /// #
+    /// # app.world.spawn((
+    /// #     PrimaryWindow,
+    /// #     RawHandleWrapper {
+    /// #         window_handle: RawWindowHandle::Web(WebWindowHandle::new(1)),
+    /// #         display_handle: RawDisplayHandle::Web(WebDisplayHandle::new()),
+    /// #     },
+    /// # ));
+    /// #
+    /// # app.add_systems(Startup, (create_surface, check).chain()).run();
+    /// #
+    /// # fn check(surface: NonSend<Surface>) {
+    /// #     assert_eq!(surface.1, RawWindowHandle::Web(WebWindowHandle::new(1)));
+    /// # }
+    /// ```
+    #[cfg(feature = "raw-window-handle")]
+    fn insert_non_send_with_primary_window_handle<F, R>(&mut self, func: F)
+    where
+        F: FnOnce(raw_window_handle::RawWindowHandle, raw_window_handle::RawDisplayHandle) -> R
+            + Send
+            + 'static,
+        R: 'static;
+
+    /// See [`unload_non_send_library`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::CommandsExt;
+    /// #
+    /// struct MathLibrary(*const u8);
+    ///
+    /// fn unload_math(mut commands: Commands) {
+    ///     // Drops `MathLibrary`, then unloads the library it was loaded from.
+    ///     commands.unload_non_send_library::<MathLibrary>();
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, unload_math)
+    /// #     .run();
+    /// ```
+    #[cfg(feature = "libloading")]
+    fn unload_non_send_library<R: 'static>(&mut self);
+
+    /// See [`init_com_apartment`].
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{ComApartment, CommandsExt};
+    /// #
+    /// fn init_com(mut commands: Commands) {
+    ///     commands.init_com_apartment();
+    /// }
+    ///
+    /// fn use_com(_apartment: NonSend<ComApartment>) {
+    ///     // Create COM objects here.
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, (init_com, use_com).chain())
+    /// #     .run();
+    /// ```
+    #[cfg(all(windows, feature = "windows"))]
+    fn init_com_apartment(&mut self);
+
+    /// See [`insert_non_send_resource_on_os_main_thread`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::CommandsExt;
+    /// #
+    /// struct StatusBarItem(*const u8);
+    ///
+    /// fn create_status_bar_item(mut commands: Commands) {
+    ///     commands.insert_non_send_resource_on_os_main_thread(|| {
+    ///         // Call `NSStatusBar::systemStatusBar()` here.
+    ///         StatusBarItem(std::ptr::null())
+    ///     });
+    /// }
+    /// #
+    /// # // Headless apps, such as this test, must not fail on any platform.
+    /// # App::new()
+    /// #     .add_systems(Startup, (create_status_bar_item, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(item: NonSend<StatusBarItem>) {
+    /// #     assert!(item.0.is_null());
+    /// # }
+    /// ```
+    fn insert_non_send_resource_on_os_main_thread<F, R>(&mut self, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static;
+
+    /// See [`insert_non_send_rebuildable`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendRebuilders, NonSendRebuildPlugin};
+    /// #
+    /// struct ShaderCompiler(*const u8);
+    ///
+    /// fn create_compiler(mut commands: Commands) {
+    ///     commands.insert_non_send_rebuildable(|| ShaderCompiler(std::ptr::null()));
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_plugins(NonSendRebuildPlugin)
+    /// #     .add_systems(Startup, (create_compiler, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(rebuilders: Res<NonSendRebuilders>) {
+    /// #     assert!(rebuilders.contains::<ShaderCompiler>());
+    /// # }
+    /// ```
+    #[cfg(feature = "hot_rebuild")]
+    fn insert_non_send_rebuildable<F, R>(&mut self, func: F)
+    where
+        F: Fn() -> R + Send + Sync + 'static,
+        R: 'static;
+
+    /// See [`rebuild_non_send_resource`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendRebuildPlugin, NonSendRebuilt};
+    /// #
+    /// struct ShaderCompiler(*const u8);
+    ///
+    /// fn reload_compiler(mut commands: Commands) {
+    ///     commands.rebuild_non_send_resource::<ShaderCompiler>();
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_plugins(NonSendRebuildPlugin)
+    /// #     .add_systems(Startup, (create_compiler, reload_compiler, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn create_compiler(mut commands: Commands) {
+    /// #     commands.insert_non_send_rebuildable(|| ShaderCompiler(std::ptr::null()));
+    /// # }
+    /// #
+    /// # fn check(events: Res<Events<NonSendRebuilt>>) {
+    /// #     assert_eq!(events.len(), 1);
+    /// # }
+    /// ```
+    #[cfg(feature = "hot_rebuild")]
+    fn rebuild_non_send_resource<R: 'static>(&mut self);
+
+    /// See [`insert_non_send_labeled`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendKeyed};
+    /// #
+    /// struct GlContext {
+    ///     width: u32,
+    ///     _handle: *const u8,
+    /// }
+    ///
+    /// fn create_contexts(mut commands: Commands) {
+    ///     commands.insert_non_send_labeled("primary_monitor", || GlContext {
+    ///         width: 1920,
+    ///         _handle: std::ptr::null(),
+    ///     });
+    ///
+    ///     commands.insert_non_send_labeled("secondary_monitor", || GlContext {
+    ///         width: 1280,
+    ///         _handle: std::ptr::null(),
+    ///     });
+    /// }
+    ///
+    /// fn draw(contexts: NonSendKeyed<GlContext>) {
+    ///     assert_eq!(contexts.len(), 2);
+    ///     assert_eq!(contexts.get("secondary_monitor").unwrap().width, 1280);
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, (create_contexts, draw).chain())
+    /// #     .run();
+    /// ```
+    fn insert_non_send_labeled<F, R>(&mut self, label: impl Into<NonSendLabel>, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static;
+
+    /// See [`remove_non_send_labeled`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendKeyed};
+    /// #
+    /// struct GlContext(*const u8);
+    ///
+    /// fn close_secondary_monitor(mut commands: Commands) {
+    ///     commands.remove_non_send_labeled::<GlContext>("secondary_monitor");
+    /// }
+    /// #
+    /// # fn create_contexts(mut commands: Commands) {
+    /// #     commands.insert_non_send_labeled("primary_monitor", || GlContext(std::ptr::null()));
+    /// #     commands.insert_non_send_labeled("secondary_monitor", || GlContext(std::ptr::null()));
+    /// # }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, (create_contexts, close_secondary_monitor, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(contexts: NonSendKeyed<GlContext>) {
+    /// #     assert!(contexts.contains("primary_monitor"));
+    /// #     assert!(!contexts.contains("secondary_monitor"));
+    /// # }
+    /// ```
+    fn remove_non_send_labeled<R: 'static>(&mut self, label: impl Into<NonSendLabel>);
+
+    /// See [`try_insert_non_send_labeled`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendErrorHandler, NonSendKeyed};
+    /// #
+    /// struct GlContext(u32);
+    ///
+    /// fn create_contexts(mut commands: Commands) {
+    ///     commands.try_insert_non_send_labeled("primary_monitor", || GlContext(1));
+    ///
+    ///     // The label is already taken, so this will call the error handler.
+    ///     commands.try_insert_non_send_labeled("primary_monitor", || GlContext(2));
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_resource(NonSendErrorHandler::IGNORE)
+    /// #     .add_systems(Startup, (create_contexts, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(contexts: NonSendKeyed<GlContext>) {
+    /// #     assert_eq!(contexts.get("primary_monitor").unwrap().0, 1);
+    /// # }
+    /// ```
+    fn try_insert_non_send_labeled<F, R>(&mut self, label: impl Into<NonSendLabel>, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static;
+
+    /// See [`try_remove_non_send_labeled`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{CommandsExt, NonSendErrorHandler};
+    /// #
+    /// struct GlContext(*const u8);
+    ///
+    /// fn close_secondary_monitor(mut commands: Commands) {
+    ///     // There is no secondary monitor, so this will call the error handler.
+    ///     commands.try_remove_non_send_labeled::<GlContext>("secondary_monitor");
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .insert_resource(NonSendErrorHandler::WARN)
+    /// #     .add_systems(Startup, close_secondary_monitor)
+    /// #     .run();
+    /// ```
+    fn try_remove_non_send_labeled<R: 'static>(&mut self, label: impl Into<NonSendLabel>);
+
+    /// Starts building the insertion of the non-[`Send`] resource `R`. See [`NonSendBuilder`].
+    fn non_send<R: 'static>(&mut self) -> NonSendBuilder<'_, R>;
+}
+
+impl CommandsExt for Commands<'_, '_> {
+    #[track_caller]
+    fn init_non_send_resource<R: FromWorld + 'static>(&mut self) {
+        self.add(NonSendCommand::init::<R>());
+    }
+
+    #[track_caller]
+    fn insert_non_send_resource<F, R>(&mut self, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add(NonSendCommand::insert(func));
+    }
+
+    #[track_caller]
+    fn insert_non_send_resource_replayable<F, R>(&mut self, func: F)
+    where
+        F: Fn() -> R + Send + Sync + 'static,
+        R: 'static,
+    {
+        self.add(NonSendCommand::insert_replayable(func));
+    }
+
+    #[track_caller]
+    fn remove_non_send_resource<R: 'static>(&mut self) {
+        self.add(NonSendCommand::remove::<R>());
+    }
+
+    #[track_caller]
+    fn save_non_send_resources<W: Write + Send + 'static>(&mut self, writer: W) {
+        self.add(save_non_send_resources(writer));
+    }
+
+    #[track_caller]
+    fn load_non_send_resources<R: Read + Send + 'static>(&mut self, reader: R) {
+        self.add(load_non_send_resources(reader));
+    }
+
+    #[cfg(feature = "bevy_reflect")]
+    #[track_caller]
+    fn insert_non_send_reflect(
+        &mut self,
+        type_id: std::any::TypeId,
+        value: Box<dyn bevy_reflect::Reflect>,
+    ) {
+        self.add(insert_non_send_reflect(type_id, value));
+    }
+
+    #[track_caller]
+    fn insert_non_send_resource_catch_unwind<F, R>(&mut self, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add(insert_non_send_resource_catch_unwind(func));
+    }
+
+    #[track_caller]
+    fn try_insert_non_send_resource_catch_unwind<F, R>(&mut self, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add(try_insert_non_send_resource_catch_unwind(func));
+    }
+
+    #[track_caller]
+    fn try_init_non_send_resource<R: FromWorld + 'static>(&mut self) {
+        self.add(try_init_non_send_resource::<R>());
+    }
+
+    #[track_caller]
+    fn try_insert_non_send_resource<F, R>(&mut self, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add(try_insert_non_send_resource(func));
+    }
+
+    #[track_caller]
+    fn try_remove_non_send_resource<R: 'static>(&mut self) {
+        self.add(try_remove_non_send_resource::<R>());
+    }
+
+    #[track_caller]
+    fn try_save_non_send_resources<W: Write + Send + 'static>(&mut self, writer: W) {
+        self.add(try_save_non_send_resources(writer));
+    }
+
+    #[track_caller]
+    fn try_load_non_send_resources<R: Read + Send + 'static>(&mut self, reader: R) {
+        self.add(try_load_non_send_resources(reader));
+    }
+
+    #[cfg(feature = "bevy_reflect")]
+    #[track_caller]
+    fn try_insert_non_send_reflect(
+        &mut self,
+        type_id: std::any::TypeId,
+        value: Box<dyn bevy_reflect::Reflect>,
+    ) {
+        self.add(try_insert_non_send_reflect(type_id, value));
+    }
+
+    #[track_caller]
+    fn init_non_send_resource_from<F, R>(&mut self, func: F)
+    where
+        F: FnOnce(&World) -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add(init_non_send_resource_from(func));
+    }
+
+    #[cfg(feature = "winit")]
+    #[track_caller]
+    fn insert_non_send_with_winit_windows<F, R>(&mut self, func: F)
+    where
+        F: FnOnce(&bevy_winit::WinitWindows) -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add(insert_non_send_with_winit_windows(func));
+    }
+
+    #[cfg(feature = "winit")]
+    #[track_caller]
+    fn insert_non_send_with_window<F, R>(&mut self, window: bevy_ecs::entity::Entity, func: F)
+    where
+        F: FnOnce(&::winit::window::Window) -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add(insert_non_send_with_window(window, func));
+    }
+
+    #[track_caller]
+    fn wrap_non_send_resource<R: 'static>(&mut self) {
+        self.add(wrap_non_send_resource::<R>());
+    }
+
+    #[track_caller]
+    fn unwrap_non_send_resource<R: 'static>(&mut self) {
+        self.add(unwrap_non_send_resource::<R>());
+    }
+
+    #[track_caller]
+    fn remove_non_send_resource_deferred<R: 'static>(&mut self, label: impl ScheduleLabel) {
+        self.add(remove_non_send_resource_deferred::<R>(label));
+    }
+
+    #[track_caller]
+    fn insert_non_send_resource_or_else<P, F, R, E>(&mut self, primary: P, fallback: F)
+    where
+        P: FnOnce() -> Result<R, E> + Send + 'static,
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static,
+        E: Send + Sync + 'static,
+    {
+        self.add(insert_non_send_resource_or_else(primary, fallback));
+    }
+
+    #[track_caller]
+    fn try_insert_non_send_resource_or_else<P, F, R, E>(&mut self, primary: P, fallback: F)
+    where
+        P: FnOnce() -> Result<R, E> + Send + 'static,
+        F: FnOnce() -> Result<R, E> + Send + 'static,
+        R: 'static,
+        E: fmt::Display + Send + Sync + 'static,
+    {
+        self.add(try_insert_non_send_resource_or_else(primary, fallback));
+    }
+
+    #[track_caller]
+    fn queue_non_send<F>(&mut self, label: impl Into<Cow<'static, str>>, func: F)
+    where
+        F: FnOnce(&mut World) + Send + 'static,
+    {
+        self.add(queue_non_send(label, func));
+    }
+
+    #[track_caller]
+    fn init_non_send_resource_once<R: FromWorld + 'static>(&mut self) {
+        self.add(init_non_send_resource_once::<R>());
+    }
+
+    #[track_caller]
+    fn with_temporary_non_send_resource<F, S, R>(&mut self, func: F, scope: S)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        S: FnOnce(&mut World) + Send + 'static,
+        R: 'static,
+    {
+        self.add(with_temporary_non_send_resource(func, scope));
+    }
+
+    #[track_caller]
+    fn insert_non_send_resource_for_frame<F, R>(&mut self, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add(insert_non_send_resource_for_frame(func));
+    }
+
+    #[track_caller]
+    fn ffi_init_once<F>(&mut self, key: &'static str, init: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.add(ffi_init_once(key, init));
+    }
+
+    #[track_caller]
+    fn ffi_shutdown<F>(&mut self, key: &'static str, shutdown: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.add(ffi_shutdown(key, shutdown));
+    }
+
+    #[track_caller]
+    fn insert_non_send_double_buffer<F, R>(&mut self, func: F)
+    where
+        F: Fn() -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add(insert_non_send_double_buffer(func));
+    }
+
+    #[track_caller]
+    fn swap_non_send_buffers<R: 'static>(&mut self) {
+        self.add(swap_non_send_buffers::<R>());
+    }
+
+    #[track_caller]
+    fn add_with_ticket<C: Command>(&mut self, command: C) -> CommandTicket {
+        let (command, ticket) = with_ticket(command);
+        self.add(command);
+        ticket
+    }
+
+    #[track_caller]
+    fn init_non_send_resource_with_ticket<R: FromWorld + 'static>(&mut self) -> CommandTicket {
+        self.add_with_ticket(NonSendCommand::init::<R>())
+    }
+
+    #[track_caller]
+    fn insert_non_send_resource_with_ticket<F, R>(&mut self, func: F) -> CommandTicket
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add_with_ticket(NonSendCommand::insert(func))
+    }
+
+    #[track_caller]
+    fn remove_non_send_resource_with_ticket<R: 'static>(&mut self) -> CommandTicket {
+        self.add_with_ticket(NonSendCommand::remove::<R>())
+    }
+
+    #[cfg(feature = "mlua")]
+    #[track_caller]
+    fn run_lua_chunk(&mut self, source: impl Into<String>) {
+        self.add(run_lua_chunk(source));
+    }
+
+    #[cfg(feature = "mlua")]
+    #[track_caller]
+    fn with_lua<F>(&mut self, func: F)
+    where
+        F: FnOnce(&mlua::Lua) + Send + 'static,
+    {
+        self.add(with_lua(func));
+    }
+
+    #[cfg(feature = "pyo3")]
+    #[track_caller]
+    fn insert_non_send_with_gil<F, R>(&mut self, func: F)
+    where
+        F: for<'py> FnOnce(pyo3::Python<'py>) -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add(insert_non_send_with_gil(func));
+    }
+
+    #[cfg(feature = "pyo3")]
+    #[track_caller]
+    fn with_gil_command<R, F>(&mut self, func: F)
+    where
+        R: 'static,
+        F: for<'py> FnOnce(pyo3::Python<'py>, &mut R) + Send + 'static,
+    {
+        self.add(with_gil_command(func));
+    }
+
+    #[cfg(feature = "raw-window-handle")]
+    #[track_caller]
+    fn insert_non_send_with_window_handle<F, R>(
+        &mut self,
+        window: bevy_ecs::entity::Entity,
+        func: F,
+    ) where
+        F: FnOnce(raw_window_handle::RawWindowHandle, raw_window_handle::RawDisplayHandle) -> R
+            + Send
+            + 'static,
+        R: 'static,
+    {
+        self.add(insert_non_send_with_window_handle(window, func));
+    }
+
+    #[cfg(feature = "raw-window-handle")]
+    #[track_caller]
+    fn insert_non_send_with_primary_window_handle<F, R>(&mut self, func: F)
+    where
+        F: FnOnce(raw_window_handle::RawWindowHandle, raw_window_handle::RawDisplayHandle) -> R
+            + Send
+            + 'static,
+        R: 'static,
+    {
+        self.add(insert_non_send_with_primary_window_handle(func));
+    }
+
+    #[cfg(feature = "libloading")]
+    #[track_caller]
+    fn unload_non_send_library<R: 'static>(&mut self) {
+        self.add(unload_non_send_library::<R>());
+    }
+
+    #[cfg(all(windows, feature = "windows"))]
+    #[track_caller]
+    fn init_com_apartment(&mut self) {
+        self.add(init_com_apartment());
+    }
+
+    #[track_caller]
+    fn insert_non_send_resource_on_os_main_thread<F, R>(&mut self, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add(insert_non_send_resource_on_os_main_thread(func));
+    }
+
+    #[cfg(feature = "hot_rebuild")]
+    #[track_caller]
+    fn insert_non_send_rebuildable<F, R>(&mut self, func: F)
+    where
+        F: Fn() -> R + Send + Sync + 'static,
+        R: 'static,
+    {
+        self.add(insert_non_send_rebuildable(func));
+    }
+
+    #[cfg(feature = "hot_rebuild")]
+    #[track_caller]
+    fn rebuild_non_send_resource<R: 'static>(&mut self) {
+        self.add(rebuild_non_send_resource::<R>());
+    }
+
+    #[track_caller]
+    fn insert_non_send_labeled<F, R>(&mut self, label: impl Into<NonSendLabel>, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add(insert_non_send_labeled(label, func));
+    }
+
+    #[track_caller]
+    fn remove_non_send_labeled<R: 'static>(&mut self, label: impl Into<NonSendLabel>) {
+        self.add(remove_non_send_labeled::<R>(label));
+    }
+
+    #[track_caller]
+    fn try_insert_non_send_labeled<F, R>(&mut self, label: impl Into<NonSendLabel>, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add(try_insert_non_send_labeled(label, func));
+    }
+
+    #[track_caller]
+    fn try_remove_non_send_labeled<R: 'static>(&mut self, label: impl Into<NonSendLabel>) {
+        self.add(try_remove_non_send_labeled::<R>(label));
+    }
+
+    #[track_caller]
+    fn non_send<R: 'static>(&mut self) -> NonSendBuilder<'_, R> {
+        NonSendBuilder::new(self.reborrow(), Location::caller())
+    }
+}
+
+/// Extensions to [`App`] for configuring how this crate manages non-[`Send`] resources.
+pub trait AppExt: private::Sealed {
+    /// Registers `R` as a persistent non-[`Send`] resource that can be saved with [`save_non_send_resources`] and loaded with [`load_non_send_resources`].
+    ///
+    /// See [`NonSendPersistence::register`].
+    fn register_non_send_persistence<R: 'static>(
+        &mut self,
+        serialize_with: impl Fn(&R, &mut dyn Write) -> io::Result<()> + Send + Sync + 'static,
+        deserialize_with: impl Fn(&mut dyn Read) -> io::Result<R> + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Declares that the non-[`Send`] resource `A` must be dropped before `B` when the app exits.
+    ///
+    /// See [`NonSendDropOrder`].
+    fn drop_non_send_before<A: 'static, B: 'static>(&mut self) -> &mut Self;
+
+    /// Adds a system to `label` that drops the non-[`Send`] resources deferred to it by [`remove_non_send_resource_deferred`].
+    ///
+    /// This is only required if `label` may not exist or may be running when the resources are removed.
+    fn add_non_send_drop_point(&mut self, label: impl ScheduleLabel) -> &mut Self;
+
+    /// Registers the default factory used to initialize the non-[`Send`] resource `R`, unless it has been overridden.
+    ///
+    /// See [`NonSendFactories::register`].
+    fn register_non_send_factory<R: 'static>(
+        &mut self,
+        factory: impl Fn() -> R + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Overrides the factory used to initialize the non-[`Send`] resource `R`, such as to replace a real device with a mock in tests.
+    ///
+    /// See [`NonSendFactories::override_factory`].
+    fn override_non_send_factory<R: 'static>(
+        &mut self,
+        factory: impl Fn() -> R + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Rebuilds the non-[`Send`] resource `R` at the end of every frame where `condition` is true.
+    ///
+    /// This adds [`NonSendRebuildPlugin`] if it was not added yet. See [`rebuild_non_send_resource`].
+    #[cfg(feature = "hot_rebuild")]
+    fn rebuild_non_send_when<R: 'static, M>(
+        &mut self,
+        condition: impl bevy_ecs::schedule::Condition<M>,
+    ) -> &mut Self;
+
+    /// Computes the non-[`Send`] resource `R` from the state `S`, analogous to a computed state.
+    ///
+    /// Whenever `S` changes, the previous `R` is dropped on the main thread, and `compute` is called with the new state to construct the next one. If it returns [`None`], `R` does not exist while in that state. This happens during [`StateTransition`](bevy_app::StateTransition), after the [`OnExit`](bevy_ecs::schedule::OnExit) and [`OnEnter`](bevy_ecs::schedule::OnEnter) schedules of `S` have run.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::AppExt;
+    /// #
+    /// #[derive(States, Default, Clone, PartialEq, Eq, Hash, Debug)]
+    /// enum GameMode {
+    ///     #[default]
+    ///     Menu,
+    ///     Replay,
+    ///     Live,
+    /// }
+    ///
+    /// struct CapturePipeline {
+    ///     frame_rate: u32,
+    ///     _handle: *const u8,
+    /// }
+    ///
+    /// let mut app = App::new();
+    ///
+    /// app.init_state::<GameMode>()
+    ///     .add_computed_non_send(|mode: &GameMode| match mode {
+    ///         GameMode::Menu => None,
+    ///         GameMode::Replay => Some(CapturePipeline { frame_rate: 30, _handle: std::ptr::null() }),
+    ///         GameMode::Live => Some(CapturePipeline { frame_rate: 60, _handle: std::ptr::null() }),
+    ///     });
+    ///
+    /// app.update();
+    /// assert!(!app.world.contains_non_send::<CapturePipeline>());
+    ///
+    /// app.world.resource_mut::<NextState<GameMode>>().set(GameMode::Live);
+    /// app.update();
+    /// assert_eq!(app.world.non_send_resource::<CapturePipeline>().frame_rate, 60);
+    /// ```
+    fn add_computed_non_send<S: States, R: 'static>(
+        &mut self,
+        compute: impl Fn(&S) -> Option<R> + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Keeps the [`Send`] resource `S` updated with a projection of the non-[`Send`] resource `R`.
+    ///
+    /// Whenever `R` is inserted or changed, `project` is called on the main thread and its result replaces `S`. When `R` is removed, `S` is removed too. Changes are detected with change ticks at the end of each frame, in [`Last`], so they are seen by other systems in the next frame. This lets systems that run in parallel read a cheap snapshot of `R` without touching the original.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{AppExt, CommandsExt};
+    /// #
+    /// struct AudioDevice {
+    ///     volume: f32,
+    ///     _handle: *const u8,
+    /// }
+    ///
+    /// #[derive(Resource)]
+    /// struct AudioSnapshot {
+    ///     volume: f32,
+    /// }
+    ///
+    /// // This system does not access `AudioDevice`, so it can run on any thread.
+    /// fn show_volume(snapshot: Option<Res<AudioSnapshot>>) {
+    ///     if let Some(snapshot) = snapshot {
+    ///         println!("Volume: {}", snapshot.volume);
+    ///     }
+    /// }
+    ///
+    /// let mut app = App::new();
+    ///
+    /// app.add_non_send_projection(|device: &AudioDevice| AudioSnapshot { volume: device.volume })
+    ///     .insert_non_send_resource(AudioDevice { volume: 0.5, _handle: std::ptr::null() })
+    ///     .add_systems(Update, show_volume);
+    ///
+    /// app.update();
+    /// assert_eq!(app.world.resource::<AudioSnapshot>().volume, 0.5);
+    ///
+    /// app.world.non_send_resource_mut::<AudioDevice>().volume = 1.0;
+    /// app.update();
+    /// assert_eq!(app.world.resource::<AudioSnapshot>().volume, 1.0);
+    /// ```
+    fn add_non_send_projection<R: 'static, S: Resource>(
+        &mut self,
+        project: impl Fn(&R) -> S + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Expects the non-[`Send`] resource `R` to be inserted within the given number of frames, warning if it is not.
+    ///
+    /// This adds [`NonSendWatchdogPlugin`] if it was not added yet.
+    fn expect_non_send<R: 'static>(&mut self, within_frames: u32) -> &mut Self;
+
+    /// Registers the non-[`Send`] resource `R` to be captured by [`snapshot_non_send`] and rolled back by [`restore_non_send`], by cloning it.
+    ///
+    /// See [`NonSendSnapshots::register_clone`].
+    ///
+    /// ```
+    /// # use std::rc::Rc;
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{restore_non_send, snapshot_non_send, AppExt};
+    /// #
+    /// #[derive(Clone)]
+    /// struct Selection(Rc<Vec<u32>>);
+    ///
+    /// let mut app = App::new();
+    ///
+    /// app.register_non_send_snapshot::<Selection>();
+    ///
+    /// // The selection does not exist yet when the snapshot is taken.
+    /// let snapshot = snapshot_non_send(&app.world);
+    ///
+    /// app.insert_non_send_resource(Selection(Rc::new(vec![1, 2, 3])));
+    ///
+    /// restore_non_send(&mut app.world, &snapshot);
+    ///
+    /// assert!(!app.world.contains_non_send::<Selection>());
+    /// ```
+    #[cfg(feature = "snapshot")]
+    fn register_non_send_snapshot<R: Clone + 'static>(&mut self) -> &mut Self;
+
+    /// Registers the non-[`Send`] resource `R` to be captured by [`snapshot_non_send`] and rolled back by [`restore_non_send`], by serializing it.
+    ///
+    /// See [`NonSendSnapshots::register_serialized`].
+    #[cfg(feature = "snapshot")]
+    fn register_non_send_snapshot_with<R: 'static>(
+        &mut self,
+        serialize_with: impl Fn(&R) -> Vec<u8> + Send + Sync + 'static,
+        deserialize_with: impl Fn(&[u8]) -> R + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl AppExt for App {
+    fn register_non_send_persistence<R: 'static>(
+        &mut self,
+        serialize_with: impl Fn(&R, &mut dyn Write) -> io::Result<()> + Send + Sync + 'static,
+        deserialize_with: impl Fn(&mut dyn Read) -> io::Result<R> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(NonSendPersistence::default)
+            .register(serialize_with, deserialize_with);
+
+        self
+    }
+
+    fn drop_non_send_before<A: 'static, B: 'static>(&mut self) -> &mut Self {
+        if !self.world.contains_resource::<NonSendDropOrder>() {
+            self.init_resource::<NonSendDropOrder>()
+                .add_systems(Last, drop_order::teardown_on_exit);
+        }
+
+        self.world
+            .resource_mut::<NonSendDropOrder>()
+            .drop_before::<A, B>();
+
+        self
+    }
+
+    fn add_non_send_drop_point(&mut self, label: impl ScheduleLabel) -> &mut Self {
+        let label = label.intern();
+
+        if deferred_drop::mark_registered(&mut self.world, label) {
+            self.add_systems(label, deferred_drop::drop_deferred(label));
+        }
+
+        self
+    }
+
+    fn register_non_send_factory<R: 'static>(
+        &mut self,
+        factory: impl Fn() -> R + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(NonSendFactories::default)
+            .register(factory);
+
+        self
+    }
+
+    fn override_non_send_factory<R: 'static>(
+        &mut self,
+        factory: impl Fn() -> R + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(NonSendFactories::default)
+            .override_factory(factory);
+
+        self
+    }
+
+    #[cfg(feature = "hot_rebuild")]
+    fn rebuild_non_send_when<R: 'static, M>(
+        &mut self,
+        condition: impl bevy_ecs::schedule::Condition<M>,
+    ) -> &mut Self {
+        use bevy_ecs::schedule::IntoSystemConfigs;
+
+        if !self.is_plugin_added::<NonSendRebuildPlugin>() {
+            self.add_plugins(NonSendRebuildPlugin);
+        }
+
+        self.add_systems(
+            Last,
+            (|mut commands: Commands| commands.add(rebuild_non_send_resource::<R>()))
+                .run_if(condition),
+        )
+    }
+
+    #[track_caller]
+    fn add_computed_non_send<S: States, R: 'static>(
+        &mut self,
+        compute: impl Fn(&S) -> Option<R> + Send + Sync + 'static,
+    ) -> &mut Self {
+        computed::add_computed_non_send(self, compute, Location::caller());
+
+        self
+    }
+
+    fn add_non_send_projection<R: 'static, S: Resource>(
+        &mut self,
+        project: impl Fn(&R) -> S + Send + Sync + 'static,
+    ) -> &mut Self {
+        projection::add_non_send_projection(self, project);
+
+        self
+    }
+
+    #[track_caller]
+    fn expect_non_send<R: 'static>(&mut self, within_frames: u32) -> &mut Self {
+        if !self.is_plugin_added::<NonSendWatchdogPlugin>() {
+            self.add_plugins(NonSendWatchdogPlugin);
+        }
+
+        self.world
+            .resource_mut::<NonSendWatchdog>()
+            .expect::<R>(within_frames);
+
+        self
+    }
+
+    #[cfg(feature = "snapshot")]
+    fn register_non_send_snapshot<R: Clone + 'static>(&mut self) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(NonSendSnapshots::default)
+            .register_clone::<R>();
+
+        self
+    }
+
+    #[cfg(feature = "snapshot")]
+    fn register_non_send_snapshot_with<R: 'static>(
+        &mut self,
+        serialize_with: impl Fn(&R) -> Vec<u8> + Send + Sync + 'static,
+        deserialize_with: impl Fn(&[u8]) -> R + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(NonSendSnapshots::default)
+            .register_serialized(serialize_with, deserialize_with);
+
+        self
+    }
+}
+
+// Re-exports used by this crate's macros. Not public API.
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "auto_register")]
+    pub use inventory;
+}
+
+// Sealed trait used to prevent others from implementing `CommandsExt` and `AppExt`.
+mod private {
+    use bevy_app::App;
+    use bevy_ecs::system::Commands;
+
+    pub trait Sealed {}
+
+    impl Sealed for Commands<'_, '_> {}
+
+    impl Sealed for App {}
 }
@@ -0,0 +1,152 @@
+use std::{ffi::OsString, marker::PhantomData, mem::ManuallyDrop, panic::Location};
+
+use bevy_ecs::{system::Command, world::World};
+use libloading::Library;
+
+use crate::{
+    error, tracking, NonSendCommand, NonSendCommandError, NonSendCommandKind, NonSendErrorContext,
+};
+
+/// A non-[`Send`] resource that keeps the dynamic library providing the non-[`Send`] resource `R` loaded.
+///
+/// This is inserted by [`load_non_send_from_library`]. The library is only unloaded by [`unload_non_send_library`], which drops `R` first. If this resource is dropped any other way, such as when the [`World`] is dropped, the library is intentionally leaked so that it cannot be unloaded while `R` may still reference its code.
+pub struct NonSendLibrary<R> {
+    library: ManuallyDrop<Library>,
+    path: OsString,
+    _marker: PhantomData<fn() -> R>,
+}
+
+impl<R> NonSendLibrary<R> {
+    /// Returns the loaded library.
+    pub fn library(&self) -> &Library {
+        &self.library
+    }
+
+    /// Returns the path the library was loaded from.
+    pub fn path(&self) -> &OsString {
+        &self.path
+    }
+}
+
+/// Creates a [`Command`] that loads a dynamic library and inserts the non-[`Send`] resource `R` created from it.
+///
+/// `loader` is executed on the main thread with the loaded [`Library`], and usually looks up symbols with [`Library::get`]. The library is kept alive in a [`NonSendLibrary<R>`] resource. If `R` was already loaded from a library, it is unloaded first with [`unload_non_send_library`].
+///
+/// If the library cannot be loaded, a [`NonSendCommandError::Library`] error is passed to the [`NonSendErrorHandler`](crate::NonSendErrorHandler).
+///
+/// # Safety
+///
+/// Loading a library runs its initialization routines, which may be arbitrary code. See [`Library::new`] for the requirements. `R` must not be moved out of the [`World`] if it references code or data owned by the library, such as function pointers.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{load_non_send_from_library, unload_non_send_library};
+/// # use libloading::Library;
+/// #
+/// struct MathLibrary {
+///     cos: unsafe extern "C" fn(f64) -> f64,
+///     _marker: *const u8,
+/// }
+///
+/// fn load_math(mut commands: Commands) {
+///     // SAFETY: The C math library does not have any unsafe initialization routines, and `cos`
+///     // has the given signature.
+///     commands.add(unsafe {
+///         load_non_send_from_library("libm.so.6", |library: &Library| MathLibrary {
+///             cos: *library.get(b"cos\0").unwrap(),
+///             _marker: std::ptr::null(),
+///         })
+///     });
+/// }
+///
+/// fn use_math(math: NonSend<MathLibrary>) {
+///     assert_eq!(unsafe { (math.cos)(0.0) }, 1.0);
+/// }
+///
+/// fn unload_math(mut commands: Commands) {
+///     commands.add(unload_non_send_library::<MathLibrary>());
+/// }
+/// #
+/// # #[cfg(target_os = "linux")]
+/// # App::new()
+/// #     .add_systems(Startup, (load_math, use_math, unload_math, check).chain())
+/// #     .run();
+/// #
+/// # fn check(math: Option<NonSend<MathLibrary>>) {
+/// #     assert!(math.is_none());
+/// # }
+/// ```
+#[track_caller]
+pub unsafe fn load_non_send_from_library<F, R>(path: impl Into<OsString>, loader: F) -> impl Command
+where
+    F: FnOnce(&Library) -> R + Send + 'static,
+    R: 'static,
+{
+    let path = path.into();
+    let location = Location::caller();
+    let context = NonSendErrorContext {
+        command: "load_non_send_from_library",
+        location,
+    };
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| {
+            unload::<R>(world, context);
+
+            // SAFETY: Upheld by the caller of `load_non_send_from_library`.
+            let library = match unsafe { Library::new(&path) } {
+                Ok(library) => library,
+                Err(error) => {
+                    error::handle_error(world, Err(NonSendCommandError::Library(error)), context);
+                    return;
+                }
+            };
+
+            let resource = loader(&library);
+
+            world.insert_non_send_resource(NonSendLibrary::<R> {
+                library: ManuallyDrop::new(library),
+                path,
+                _marker: PhantomData,
+            });
+
+            world.insert_non_send_resource(resource);
+            tracking::track::<R>(world, location, None);
+        },
+    )
+}
+
+/// Creates a [`Command`] that removes the non-[`Send`] resource `R`, and then unloads the library it was loaded from.
+///
+/// `R` is always dropped before the library is unloaded. Does nothing if `R` was not loaded with [`load_non_send_from_library`]. If the library fails to unload, a [`NonSendCommandError::Library`] error is passed to the [`NonSendErrorHandler`](crate::NonSendErrorHandler).
+#[track_caller]
+pub fn unload_non_send_library<R: 'static>() -> impl Command {
+    let context = NonSendErrorContext {
+        command: "unload_non_send_library",
+        location: Location::caller(),
+    };
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Remove,
+        context.location,
+        move |world: &mut World| {
+            unload::<R>(world, context);
+        },
+    )
+}
+
+fn unload<R: 'static>(world: &mut World, context: NonSendErrorContext) {
+    let Some(mut library) = world.remove_non_send_resource::<NonSendLibrary<R>>() else {
+        return;
+    };
+
+    tracking::remove::<R>(world);
+
+    // SAFETY: `R` was dropped above, and `library` is dropped immediately after.
+    let library = unsafe { ManuallyDrop::take(&mut library.library) };
+
+    let result = library.close().map_err(NonSendCommandError::Library);
+    error::handle_error(world, result, context);
+}
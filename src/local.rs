@@ -0,0 +1,80 @@
+use std::ops::{Deref, DerefMut};
+
+use bevy_ecs::{
+    component::Tick,
+    system::{SystemMeta, SystemParam},
+    world::{unsafe_world_cell::UnsafeWorldCell, FromWorld, World},
+};
+
+use crate::NonSendGuard;
+
+/// A system parameter like [`Local`](bevy_ecs::system::Local), but whose value does not need to be [`Send`].
+///
+/// The value is created when the system is initialized, with the factory registered in [`NonSendFactories`](crate::NonSendFactories) if there is one, or otherwise with [`FromWorld`], and is stored in the system itself rather than the [`World`]. Systems with this parameter are always run on the main thread.
+///
+/// The value is kept in a [`NonSendGuard`], so it must also be dropped on the main thread. This is the case as long as the schedule containing the system is dropped along with the [`World`] on the main thread.
+///
+/// ```
+/// # use std::{cell::Cell, rc::Rc};
+/// # use std::sync::atomic::{AtomicU32, Ordering};
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::NonSendLocal;
+/// #
+/// # static FRAMES: AtomicU32 = AtomicU32::new(0);
+/// #
+/// #[derive(Default)]
+/// struct FrameCounter(Rc<Cell<u32>>);
+///
+/// fn count_frames(counter: NonSendLocal<FrameCounter>) {
+///     counter.0.set(counter.0.get() + 1);
+/// #   FRAMES.store(counter.0.get(), Ordering::Relaxed);
+/// }
+///
+/// let mut app = App::new();
+///
+/// app.add_systems(Update, count_frames);
+///
+/// app.update();
+/// app.update();
+/// # assert_eq!(FRAMES.load(Ordering::Relaxed), 2);
+/// ```
+#[derive(Debug)]
+pub struct NonSendLocal<'s, T: FromWorld + 'static>(&'s mut T);
+
+impl<T: FromWorld + 'static> Deref for NonSendLocal<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<T: FromWorld + 'static> DerefMut for NonSendLocal<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0
+    }
+}
+
+// SAFETY: Only local state is accessed, and the system is marked as non-send so that state is only
+// accessed on the main thread.
+unsafe impl<T: FromWorld + 'static> SystemParam for NonSendLocal<'_, T> {
+    type State = NonSendGuard<T>;
+    type Item<'w, 's> = NonSendLocal<'s, T>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        system_meta.set_non_send();
+
+        let value = crate::factory::build::<T>(world).unwrap_or_else(|| T::from_world(world));
+
+        NonSendGuard::new(value)
+    }
+
+    unsafe fn get_param<'w, 's>(
+        state: &'s mut Self::State,
+        _system_meta: &SystemMeta,
+        _world: UnsafeWorldCell<'w>,
+        _change_tick: Tick,
+    ) -> Self::Item<'w, 's> {
+        NonSendLocal(state.get_mut())
+    }
+}
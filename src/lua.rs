@@ -0,0 +1,116 @@
+use std::panic::Location;
+
+use bevy_app::{App, Plugin};
+use bevy_ecs::{system::Command, world::World};
+use mlua::Lua;
+
+use crate::{error, main_thread, NonSendCommandError, NonSendErrorContext};
+
+/// A [`Plugin`] that inserts a [`Lua`] state as a non-[`Send`] resource.
+///
+/// [`Lua`] is not [`Send`], so it must only be used on the main thread. Use [`run_lua_chunk`] and [`with_lua`] to access it from any system, or [`NonSend<Lua>`](bevy_ecs::system::NonSend) to access it directly.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{run_lua_chunk, LuaNonSendPlugin};
+/// # use mlua::Lua;
+/// #
+/// fn run_script(mut commands: Commands) {
+///     commands.add(run_lua_chunk("score = 40 + 2"));
+/// }
+///
+/// fn read_score(lua: NonSend<Lua>) {
+///     let score: i32 = lua.globals().get("score").unwrap();
+///
+///     assert_eq!(score, 42);
+/// }
+///
+/// App::new()
+///     .add_plugins(LuaNonSendPlugin)
+///     .add_systems(Startup, (run_script, read_score).chain())
+///     .run();
+/// ```
+pub struct LuaNonSendPlugin;
+
+impl Plugin for LuaNonSendPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_non_send_resource(Lua::new());
+    }
+}
+
+/// Creates a [`Command`] that executes a chunk of Lua source code on the main thread.
+///
+/// If the [`Lua`] state does not exist or the chunk fails, the error is passed to the [`NonSendErrorHandler`](crate::NonSendErrorHandler).
+#[track_caller]
+pub fn run_lua_chunk(source: impl Into<String>) -> impl Command {
+    let source = source.into();
+    let context = NonSendErrorContext {
+        command: "run_lua_chunk",
+        location: Location::caller(),
+    };
+
+    main_thread::deferrable(move |world: &mut World| {
+        let result = match world.get_non_send_resource::<Lua>() {
+            Some(lua) => lua
+                .load(source.as_str())
+                .exec()
+                .map_err(NonSendCommandError::Lua),
+            None => Err(NonSendCommandError::ResourceMissing {
+                type_name: std::any::type_name::<Lua>(),
+            }),
+        };
+
+        error::handle_error(world, result, context);
+    })
+}
+
+/// Creates a [`Command`] that calls `func` with the [`Lua`] state on the main thread.
+///
+/// If the [`Lua`] state does not exist, the error is passed to the [`NonSendErrorHandler`](crate::NonSendErrorHandler).
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{with_lua, LuaNonSendPlugin};
+/// # use mlua::Lua;
+/// #
+/// fn register_api(mut commands: Commands) {
+///     commands.add(with_lua(|lua: &Lua| {
+///         let greet = lua.create_function(|_, name: String| Ok(format!("Hello, {name}!"))).unwrap();
+///         lua.globals().set("greet", greet).unwrap();
+///     }));
+/// }
+/// #
+/// # App::new()
+/// #     .add_plugins(LuaNonSendPlugin)
+/// #     .add_systems(Startup, (register_api, check).chain())
+/// #     .run();
+/// #
+/// # fn check(lua: NonSend<Lua>) {
+/// #     let greeting: String = lua.load("greet('Bevy')").eval().unwrap();
+/// #     assert_eq!(greeting, "Hello, Bevy!");
+/// # }
+/// ```
+#[track_caller]
+pub fn with_lua<F>(func: F) -> impl Command
+where
+    F: FnOnce(&Lua) + Send + 'static,
+{
+    let context = NonSendErrorContext {
+        command: "with_lua",
+        location: Location::caller(),
+    };
+
+    main_thread::deferrable(move |world: &mut World| {
+        let result = match world.get_non_send_resource::<Lua>() {
+            Some(lua) => {
+                func(lua);
+                Ok(())
+            }
+            None => Err(NonSendCommandError::ResourceMissing {
+                type_name: std::any::type_name::<Lua>(),
+            }),
+        };
+
+        error::handle_error(world, result, context);
+    })
+}
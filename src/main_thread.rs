@@ -0,0 +1,449 @@
+use std::{
+    panic::Location,
+    sync::Mutex,
+    thread::{self, ThreadId},
+};
+
+use bevy_app::{App, First, Last, Plugin, PreStartup};
+use bevy_ecs::{
+    system::{Command, Res, Resource},
+    world::World,
+};
+use bevy_utils::tracing::debug;
+
+use crate::{
+    error, ticket, tracking, NonSendCommand, NonSendCommandError, NonSendCommandKind,
+    NonSendErrorContext, TicketedCommand,
+};
+
+/// A [`Resource`] containing the [`ThreadId`] of the main thread, which is the only thread that can access non-[`Send`] resources of this [`World`].
+///
+/// This is inserted by [`MainThreadPlugin`]. Each [`World`] records its own main thread, so apps built on different threads do not interfere.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct MainThreadMarker {
+    id: ThreadId,
+    updating_on_main: bool,
+}
+
+impl MainThreadMarker {
+    /// Returns the [`ThreadId`] of the main thread.
+    pub fn id(&self) -> ThreadId {
+        self.id
+    }
+
+    /// Returns true if the current thread is the main thread.
+    pub fn is_current(&self) -> bool {
+        thread::current().id() == self.id
+    }
+
+    /// Returns true if the app was last updated from the main thread.
+    ///
+    /// See [`on_main_thread`].
+    pub fn is_updating_on_main(&self) -> bool {
+        self.updating_on_main
+    }
+}
+
+/// A [`Plugin`] that records the current thread as the main thread.
+///
+/// This inserts the [`MainThreadMarker`] resource and enables [`is_main_thread`] and [`on_main_thread`]. It must be added from the thread that runs the app.
+///
+/// The main thread is recorded per [`World`], so every app that should know its main thread must add this plugin.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{is_main_thread, MainThreadPlugin};
+/// #
+/// struct MyNonSend(*const u8);
+///
+/// // Shared library code that may be called from any thread.
+/// fn create_my_non_send(world: &mut World) {
+///     if is_main_thread(world) {
+///         world.insert_non_send_resource(MyNonSend(std::ptr::null()));
+///     } else {
+///         // Queue a command using this crate instead.
+///     }
+/// }
+///
+/// App::new()
+///     .add_plugins(MainThreadPlugin)
+///     // Exclusive systems always run on the main thread.
+///     .add_systems(Update, create_my_non_send)
+///     .run();
+/// ```
+pub struct MainThreadPlugin;
+
+impl Plugin for MainThreadPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MainThreadMarker {
+            id: thread::current().id(),
+            updating_on_main: true,
+        })
+        .add_systems(PreStartup, record_update_thread)
+        .add_systems(First, record_update_thread);
+    }
+}
+
+/// Records whether the app is being updated from the main thread.
+///
+/// Exclusive systems always run on the thread that runs the schedule, unlike run conditions, which the multi-threaded executor may evaluate on any thread.
+fn record_update_thread(world: &mut World) {
+    if let Some(mut marker) = world.get_resource_mut::<MainThreadMarker>() {
+        marker.updating_on_main = marker.is_current();
+    }
+}
+
+/// Returns true if the current thread is the main thread of `world`.
+///
+/// This always returns false if [`MainThreadPlugin`] has not been added to the app owning `world`.
+pub fn is_main_thread(world: &World) -> bool {
+    world
+        .get_resource::<MainThreadMarker>()
+        .is_some_and(MainThreadMarker::is_current)
+}
+
+/// A run condition that returns true if the app is being updated from the main thread.
+///
+/// The thread is recorded at the start of every update, in [`PreStartup`] and [`First`]. Returns false if [`MainThreadPlugin`] was not added, or if the app is run from a different thread than the one that added it.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{on_main_thread, MainThreadPlugin};
+/// #
+/// fn create_tray_icon() {
+///     // Calls into a platform API that must be used from the main thread.
+/// }
+///
+/// App::new()
+///     .add_plugins(MainThreadPlugin)
+///     .add_systems(Update, create_tray_icon.run_if(on_main_thread))
+///     .run();
+/// ```
+pub fn on_main_thread(marker: Option<Res<MainThreadMarker>>) -> bool {
+    marker.is_some_and(|marker| marker.is_updating_on_main())
+}
+
+type DeferredCommand = Box<dyn FnOnce(&mut World) + Send>;
+
+/// A [`Resource`] holding commands that were applied off the main thread, to be applied again on the main thread.
+///
+/// While this resource exists, every command created by this crate that is applied on a thread other than the one recorded in [`MainThreadMarker`] is pushed to this queue instead of running. This includes [`NonSendCommand`](crate::NonSendCommand)s as well as free-function commands such as [`queue_non_send`](crate::queue_non_send) and [`save_non_send_resources`](crate::save_non_send_resources), but not arbitrary closures added to [`Commands`](bevy_ecs::system::Commands). This is inserted by [`MainThreadDeferralPlugin`].
+///
+/// A command wrapped with [`with_ticket`](crate::with_ticket) keeps its [`CommandTicket`](crate::CommandTicket) when it is deferred, so the ticket stays pending until the command is applied from this queue, and reports any failure at that point.
+#[derive(Resource, Default)]
+pub struct MainThreadQueue {
+    commands: Mutex<Vec<DeferredCommand>>,
+}
+
+impl MainThreadQueue {
+    /// Pushes a command to be applied on the main thread.
+    pub fn push(&self, command: impl Command) {
+        self.commands
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(Box::new(move |world| command.apply(world)));
+    }
+
+    /// Returns the number of commands waiting to be applied.
+    pub fn len(&self) -> usize {
+        self.commands
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .len()
+    }
+
+    /// Returns true if no commands are waiting to be applied.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A [`Plugin`] that defers this crate's commands applied off the main thread to the [`MainThreadQueue`], instead of running them on the wrong thread.
+///
+/// This is meant for custom runners and test harnesses that may apply command queues on another thread. The queue is applied in [`First`] and [`Last`] whenever they run on the main thread, and can also be applied manually with [`run_main_thread_queue`]. This adds [`MainThreadPlugin`] if it was not added yet.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::ecs::system::CommandQueue;
+/// # use bevy_command_non_send::{insert_non_send_resource, MainThreadDeferralPlugin, MainThreadQueue, NonSendCommand};
+/// #
+/// struct MyNonSend(*const u8);
+///
+/// let mut app = App::new();
+///
+/// app.add_plugins(MainThreadDeferralPlugin);
+///
+/// let mut queue = CommandQueue::default();
+/// queue.push(NonSendCommand::insert(|| MyNonSend(std::ptr::null())));
+///
+/// // A custom runner applies the queue on another thread.
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| queue.apply(&mut app.world));
+/// });
+///
+/// assert!(!app.world.contains_non_send::<MyNonSend>());
+/// assert_eq!(app.world.resource::<MainThreadQueue>().len(), 1);
+///
+/// // The deferred command is applied on the main thread during the next update.
+/// app.update();
+///
+/// assert!(app.world.contains_non_send::<MyNonSend>());
+/// ```
+///
+/// Tickets of deferred commands are completed once the command is applied on the main thread:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::ecs::system::CommandQueue;
+/// # use bevy_command_non_send::{insert_non_send_resource, with_ticket, MainThreadDeferralPlugin, TicketStatus};
+/// #
+/// # struct MyNonSend(*const u8);
+/// #
+/// # let mut app = App::new();
+/// # app.add_plugins(MainThreadDeferralPlugin);
+/// #
+/// let (command, ticket) = with_ticket(insert_non_send_resource(|| MyNonSend(std::ptr::null())));
+///
+/// let mut queue = CommandQueue::default();
+/// queue.push(command);
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| queue.apply(&mut app.world));
+/// });
+///
+/// assert_eq!(ticket.status(), TicketStatus::Pending);
+///
+/// app.update();
+///
+/// assert_eq!(ticket.status(), TicketStatus::Succeeded);
+/// ```
+pub struct MainThreadDeferralPlugin;
+
+impl Plugin for MainThreadDeferralPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<MainThreadPlugin>() {
+            app.add_plugins(MainThreadPlugin);
+        }
+
+        app.init_resource::<MainThreadQueue>()
+            .add_systems(First, run_main_thread_queue)
+            .add_systems(Last, run_main_thread_queue);
+    }
+}
+
+/// Applies all commands in the [`MainThreadQueue`], if called on the main thread.
+///
+/// Does nothing if called on another thread, or if [`MainThreadDeferralPlugin`] was not added.
+pub fn run_main_thread_queue(world: &mut World) {
+    if !is_main_thread(world) {
+        return;
+    }
+
+    let Some(queue) = world.get_resource::<MainThreadQueue>() else {
+        return;
+    };
+
+    let commands = std::mem::take(
+        &mut *queue
+            .commands
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()),
+    );
+
+    for command in commands {
+        command(world);
+    }
+}
+
+/// Pushes `command` to the [`MainThreadQueue`] if deferral is enabled and this is not the main thread, otherwise returns it.
+///
+/// `location` is the location of the code that created the command, and is only used for logging.
+pub(crate) fn defer_off_main_thread<C: Command>(
+    world: &World,
+    command: C,
+    location: &'static Location<'static>,
+) -> Option<C> {
+    let (Some(queue), Some(marker)) = (
+        world.get_resource::<MainThreadQueue>(),
+        world.get_resource::<MainThreadMarker>(),
+    ) else {
+        return Some(command);
+    };
+
+    if marker.is_current() {
+        return Some(command);
+    }
+
+    debug!(
+        "Deferring non-send command created at {location} and applied on thread {:?} to the main thread.",
+        thread::current().id(),
+    );
+
+    match ticket::take_current_ticket() {
+        Some(ticket) => queue.push(TicketedCommand::new(command, ticket)),
+        None => queue.push(command),
+    }
+
+    None
+}
+
+/// Wraps one of this crate's commands that is not a [`NonSendCommand`](crate::NonSendCommand), so that it is deferred like one.
+#[track_caller]
+pub(crate) fn deferrable(command: impl Command) -> impl Command {
+    let location = Location::caller();
+
+    move |world: &mut World| {
+        if let Some(command) = defer_off_main_thread(world, command, location) {
+            command.apply(world);
+        }
+    }
+}
+
+/// Returns whether the current thread is the operating system's main thread, or [`None`] if this cannot be determined on the current platform.
+///
+/// This is stricter than [`is_main_thread`], which only checks that the current thread is the one running the [`App`]. UI frameworks such as AppKit, UIKit, and Win32 instead require the thread the process started on, which is not the same thread when the app is run from a spawned thread or the default test harness.
+///
+/// - On macOS and iOS, this uses `pthread_main_np`.
+/// - On other platforms, including Windows, this returns [`None`]. Windows has no direct way to query the thread the process started on.
+///
+/// ```
+/// # use bevy_command_non_send::is_os_main_thread;
+/// #
+/// // A spawned thread is never the OS main thread.
+/// let spawned = std::thread::spawn(is_os_main_thread).join().unwrap();
+/// assert_ne!(spawned, Some(true));
+///
+/// if cfg!(any(target_os = "macos", target_os = "ios")) {
+///     assert_eq!(is_os_main_thread(), Some(true));
+/// } else {
+///     assert_eq!(is_os_main_thread(), None);
+/// }
+/// ```
+pub fn is_os_main_thread() -> Option<bool> {
+    os_main_thread()
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn os_main_thread() -> Option<bool> {
+    extern "C" {
+        fn pthread_main_np() -> std::ffi::c_int;
+    }
+
+    // SAFETY: `pthread_main_np` has no preconditions.
+    Some(unsafe { pthread_main_np() } != 0)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+fn os_main_thread() -> Option<bool> {
+    None
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource whose factory calls platform UI functions, such as AppKit, UIKit, or Win32.
+///
+/// In debug builds, this verifies with [`is_os_main_thread`] that commands are being applied on the operating system's main thread before `func` is run. If they are not, `func` is not run and a [`NonSendCommandError::NotOsMainThread`] error is passed to the [`NonSendErrorHandler`](crate::NonSendErrorHandler). On platforms where this cannot be determined, and in release builds, this behaves like [`insert_non_send_resource`](crate::insert_non_send_resource).
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::insert_non_send_resource_on_os_main_thread;
+/// #
+/// struct StatusBarItem(*const u8);
+///
+/// fn create_status_bar_item(mut commands: Commands) {
+///     commands.add(
+///         insert_non_send_resource_on_os_main_thread(|| {
+///             // Call `NSStatusBar::systemStatusBar()` here.
+///             StatusBarItem(std::ptr::null())
+///         })
+///     );
+/// }
+/// #
+/// # // Headless apps, such as this test, must not fail on any platform.
+/// # App::new()
+/// #     .add_systems(Startup, (create_status_bar_item, check).chain())
+/// #     .run();
+/// #
+/// # fn check(item: NonSend<StatusBarItem>) {
+/// #     assert!(item.0.is_null());
+/// # }
+/// ```
+#[track_caller]
+pub fn insert_non_send_resource_on_os_main_thread<F, R>(func: F) -> impl Command
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: 'static,
+{
+    let location = Location::caller();
+    let context = NonSendErrorContext {
+        command: "insert_non_send_resource_on_os_main_thread",
+        location,
+    };
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| {
+            if cfg!(debug_assertions) && is_os_main_thread() == Some(false) {
+                let error = NonSendCommandError::NotOsMainThread {
+                    type_name: std::any::type_name::<R>(),
+                    thread_name: thread::current().name().map(str::to_owned),
+                };
+
+                error::handle_error(world, Err(error), context);
+                return;
+            }
+
+            world.insert_non_send_resource((func)());
+            tracking::track::<R>(world, location, None);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn main_thread_is_recorded_per_world() {
+        let mut app = App::new();
+        app.add_plugins(MainThreadPlugin);
+
+        // An app built on another thread records that thread instead.
+        let other = thread::spawn(|| {
+            let mut app = App::new();
+            app.add_plugins(MainThreadPlugin);
+            app.world.resource::<MainThreadMarker>().id()
+        })
+        .join()
+        .unwrap();
+
+        assert!(is_main_thread(&app.world));
+        assert_eq!(
+            app.world.resource::<MainThreadMarker>().id(),
+            thread::current().id(),
+        );
+        assert_ne!(other, thread::current().id());
+        assert!(!is_main_thread(&World::new()));
+    }
+
+    #[test]
+    fn on_main_thread_follows_the_updating_thread() {
+        let mut app = App::new();
+        app.add_plugins(MainThreadPlugin);
+
+        thread::scope(|scope| {
+            scope.spawn(|| app.update());
+        });
+
+        assert!(!app
+            .world
+            .resource::<MainThreadMarker>()
+            .is_updating_on_main());
+
+        app.update();
+
+        assert!(app
+            .world
+            .resource::<MainThreadMarker>()
+            .is_updating_on_main());
+    }
+}
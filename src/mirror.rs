@@ -0,0 +1,341 @@
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    panic::Location,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+};
+
+use bevy_app::{App, AppLabel, InternedAppLabel, Plugin};
+use bevy_ecs::{
+    system::{Command, Resource},
+    world::World,
+};
+use bevy_utils::synccell::SyncCell;
+
+use crate::{tracking, NonSendCommand, NonSendCommandKind};
+
+type MirrorOp = Box<dyn FnOnce(&mut World) + Send>;
+pub(crate) type Projection = (InternedAppLabel, fn(&mut World));
+
+/// A [`Plugin`] that allows non-[`Send`] resources to be mirrored into the [`World`] of a sub-app.
+///
+/// The sub-app must already exist when this plugin is added. Operations are queued from the main world and applied to the sub-app's world during its main schedule, which runs after the main world's update.
+///
+/// # Removal
+///
+/// When a mirrored resource is removed from the main world through this crate, such as with [`remove_non_send_resource`](crate::remove_non_send_resource) or [`remove_tracked_non_send`](crate::remove_tracked_non_send), its copy is also removed from the sub-app. Removing it directly with [`World::remove_non_send_resource`] bypasses this crate, so the sub-app keeps a stale copy until it is removed with [`remove_non_send_projected`].
+///
+/// See [`insert_non_send_mirrored`].
+pub struct NonSendMirrorPlugin {
+    label: InternedAppLabel,
+}
+
+impl NonSendMirrorPlugin {
+    /// Creates a plugin that mirrors non-[`Send`] resources into the sub-app with the given label.
+    pub fn new(label: impl AppLabel) -> Self {
+        NonSendMirrorPlugin {
+            label: label.intern(),
+        }
+    }
+}
+
+impl Plugin for NonSendMirrorPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = mpsc::channel();
+
+        app.world
+            .get_resource_or_insert_with(NonSendMirror::default)
+            .senders
+            .insert(self.label, sender);
+
+        let sub_app = app.sub_app_mut(self.label);
+        let schedule = sub_app.main_schedule_label;
+
+        sub_app
+            .insert_resource(MirrorReceiver(SyncCell::new(receiver)))
+            .add_systems(schedule, apply_mirrored);
+    }
+}
+
+/// Channels used to send operations to mirrored sub-apps, and the sub-app copies of each mirrored resource.
+#[derive(Resource, Default)]
+struct NonSendMirror {
+    senders: HashMap<InternedAppLabel, Sender<MirrorOp>>,
+    projections: HashMap<TypeId, Vec<Projection>>,
+}
+
+#[derive(Resource)]
+struct MirrorReceiver(SyncCell<Receiver<MirrorOp>>);
+
+fn apply_mirrored(world: &mut World) {
+    let ops: Vec<MirrorOp> = world
+        .resource_mut::<MirrorReceiver>()
+        .0
+        .get()
+        .try_iter()
+        .collect();
+
+    for op in ops {
+        op(world);
+    }
+}
+
+/// Sends `op` to the sub-app with the given label.
+///
+/// # Panics
+///
+/// Panics if [`NonSendMirrorPlugin`] was not added for the sub-app.
+fn send_to_sub_app(world: &World, label: InternedAppLabel, op: MirrorOp) {
+    world
+        .get_resource::<NonSendMirror>()
+        .and_then(|mirror| mirror.senders.get(&label))
+        .unwrap_or_else(|| {
+            panic!("Sub-app {label:?} is not mirrored. Did you add `NonSendMirrorPlugin`?")
+        })
+        .send(op)
+        .expect("Mirrored sub-app should not be dropped while the main world exists.");
+}
+
+/// Removes the sub-app copies of `R`, because `R` was removed from the main world.
+pub(crate) fn removed<R: 'static>(world: &mut World) {
+    let Some(projections) = world
+        .get_resource_mut::<NonSendMirror>()
+        .and_then(|mut mirror| mirror.projections.remove(&TypeId::of::<R>()))
+    else {
+        return;
+    };
+
+    for (label, remove) in projections {
+        send_to_sub_app(world, label, Box::new(remove));
+    }
+}
+
+/// Removes and returns the sub-app copies registered for `R`, without removing them from the sub-apps.
+pub(crate) fn take_projections<R: 'static>(world: &mut World) -> Vec<Projection> {
+    world
+        .get_resource_mut::<NonSendMirror>()
+        .and_then(|mut mirror| mirror.projections.remove(&TypeId::of::<R>()))
+        .unwrap_or_default()
+}
+
+/// Registers sub-app copies of `R` again that were taken with [`take_projections`].
+pub(crate) fn restore_projections<R: 'static>(world: &mut World, projections: Vec<Projection>) {
+    if projections.is_empty() {
+        return;
+    }
+
+    world
+        .resource_mut::<NonSendMirror>()
+        .projections
+        .insert(TypeId::of::<R>(), projections);
+}
+
+fn remove_projection<RS: 'static>(sub_world: &mut World) {
+    sub_world.remove_non_send_resource::<RS>();
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource into both the main [`World`] and the [`World`] of a sub-app.
+///
+/// `func` is called once for each world, so each world gets its own value. The sub-app must be mirrored with [`NonSendMirrorPlugin`].
+///
+/// See [`insert_non_send_projected`] to insert different types into each world.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::app::{AppLabel, SubApp};
+/// # use bevy_command_non_send::{insert_non_send_mirrored, NonSendMirrorPlugin};
+/// #
+/// #[derive(AppLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+/// struct PresentationApp;
+///
+/// struct PlatformSurface(*const u8);
+///
+/// fn create_surface(mut commands: Commands) {
+///     commands.add(
+///         insert_non_send_mirrored(PresentationApp, || {
+///             PlatformSurface(std::ptr::null())
+///         })
+///     );
+/// }
+///
+/// let mut app = App::new();
+///
+/// app.insert_sub_app(PresentationApp, SubApp::new(App::empty(), |_, _| {}));
+///
+/// app.add_plugins(NonSendMirrorPlugin::new(PresentationApp))
+///     .add_systems(Update, create_surface);
+///
+/// app.update();
+///
+/// assert!(app.world.contains_non_send::<PlatformSurface>());
+/// assert!(app.sub_app(PresentationApp).world.contains_non_send::<PlatformSurface>());
+/// ```
+#[track_caller]
+pub fn insert_non_send_mirrored<F, R>(label: impl AppLabel, func: F) -> impl Command
+where
+    F: Fn() -> R + Send + Sync + 'static,
+    R: 'static,
+{
+    let func = Arc::new(func);
+    let sub_func = Arc::clone(&func);
+
+    insert_non_send_projected(label, move || func(), move || sub_func())
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource into the main [`World`], and a projection of it into the [`World`] of a sub-app.
+///
+/// `main` creates the resource for the main world, and `sub` creates the resource for the sub-app. `sub` is only sent to the sub-app after `main` returned. The sub-app must be mirrored with [`NonSendMirrorPlugin`].
+#[track_caller]
+pub fn insert_non_send_projected<FM, FS, RM, RS>(
+    label: impl AppLabel,
+    main: FM,
+    sub: FS,
+) -> impl Command
+where
+    FM: FnOnce() -> RM + Send + 'static,
+    FS: FnOnce() -> RS + Send + 'static,
+    RM: 'static,
+    RS: 'static,
+{
+    let label = label.intern();
+    let location = Location::caller();
+
+    NonSendCommand::new::<RM>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| {
+            world.insert_non_send_resource(main());
+            tracking::track::<RM>(world, location, None);
+
+            send_to_sub_app(
+                world,
+                label,
+                Box::new(move |sub_world: &mut World| {
+                    sub_world.insert_non_send_resource(sub());
+                }),
+            );
+
+            let mut mirror = world.resource_mut::<NonSendMirror>();
+            let projections = mirror.projections.entry(TypeId::of::<RM>()).or_default();
+
+            projections.retain(|&(projected, _)| projected != label);
+            projections.push((label, remove_projection::<RS>));
+        },
+    )
+}
+
+/// Creates a [`Command`] for removing a non-[`Send`] resource from both the main [`World`] and the [`World`] of a sub-app.
+///
+/// See [`insert_non_send_mirrored`].
+#[track_caller]
+pub fn remove_non_send_mirrored<R: 'static>(label: impl AppLabel) -> impl Command {
+    remove_non_send_projected::<R, R>(label)
+}
+
+/// Creates a [`Command`] for removing `RM` from the main [`World`] and `RS` from the [`World`] of a sub-app.
+///
+/// `RS` is removed even if it was not inserted with [`insert_non_send_projected`], which can be used to clean up a stale copy. See [`insert_non_send_projected`].
+#[track_caller]
+pub fn remove_non_send_projected<RM: 'static, RS: 'static>(label: impl AppLabel) -> impl Command {
+    let label = label.intern();
+
+    NonSendCommand::new::<RM>(
+        NonSendCommandKind::Remove,
+        Location::caller(),
+        move |world: &mut World| {
+            send_to_sub_app(world, label, Box::new(remove_projection::<RS>));
+
+            tracking::remove::<RM>(world);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::SubApp;
+
+    use super::*;
+    use crate::{NonSendTracker, NonSendTrackingPlugin};
+
+    #[derive(AppLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    struct Presentation;
+
+    struct Surface;
+
+    struct SurfaceProjection;
+
+    fn app() -> App {
+        let mut app = App::new();
+
+        app.insert_sub_app(Presentation, SubApp::new(App::empty(), |_, _| {}));
+        app.add_plugins((
+            NonSendTrackingPlugin,
+            NonSendMirrorPlugin::new(Presentation),
+        ));
+        app
+    }
+
+    fn is_projected(app: &App) -> bool {
+        app.sub_app(Presentation)
+            .world
+            .contains_non_send::<SurfaceProjection>()
+    }
+
+    fn projected_app() -> App {
+        let mut app = app();
+
+        insert_non_send_projected(Presentation, || Surface, || SurfaceProjection)
+            .apply(&mut app.world);
+        app.update();
+
+        assert!(is_projected(&app));
+        app
+    }
+
+    #[test]
+    fn remove_command_removes_projection() {
+        let mut app = projected_app();
+
+        crate::remove_non_send_resource::<Surface>().apply(&mut app.world);
+        app.update();
+
+        assert!(!is_projected(&app));
+    }
+
+    #[test]
+    fn tracker_removal_removes_projection() {
+        let mut app = projected_app();
+
+        let type_id = TypeId::of::<Surface>();
+        assert!(crate::remove_tracked_non_send(&mut app.world, type_id));
+        assert!(app
+            .world
+            .resource::<NonSendTracker>()
+            .get(type_id)
+            .is_none());
+        app.update();
+
+        assert!(!is_projected(&app));
+    }
+
+    #[test]
+    fn panicking_main_does_not_reach_sub_app() {
+        let mut app = app();
+
+        let command = insert_non_send_projected(
+            Presentation,
+            || -> Surface { panic!("failed to create the surface") },
+            || SurfaceProjection,
+        );
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            command.apply(&mut app.world);
+        }));
+        app.update();
+
+        assert!(result.is_err());
+        assert!(!is_projected(&app));
+    }
+}
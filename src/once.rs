@@ -0,0 +1,90 @@
+use std::{
+    any::TypeId,
+    collections::HashSet,
+    panic::Location,
+    sync::{Mutex, OnceLock},
+};
+
+use bevy_ecs::{
+    system::Command,
+    world::{FromWorld, World},
+};
+
+use crate::{tracking, NonSendCommand, NonSendCommandKind};
+
+/// Types that have been initialized by `init_non_send_resource_once`, for the whole process.
+static TOMBSTONES: OnceLock<Mutex<HashSet<TypeId>>> = OnceLock::new();
+
+/// Marks `R` as initialized, returning false if it already was.
+fn mark<R: 'static>() -> bool {
+    TOMBSTONES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(TypeId::of::<R>())
+}
+
+/// Returns true if [`init_non_send_resource_once`] has initialized `R` in this process.
+pub fn is_non_send_initialized_once<R: 'static>() -> bool {
+    TOMBSTONES.get().is_some_and(|tombstones| {
+        tombstones
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .contains(&TypeId::of::<R>())
+    })
+}
+
+/// Creates a [`Command`] for initializing a non-[`Send`] resource only the first time it is applied in the process.
+///
+/// This is like [`init_non_send_resource`](crate::init_non_send_resource), but once `R` has been initialized a per-type tombstone is left behind. Later applications of this command do nothing, even if the resource was removed in the meantime, or belongs to a different [`World`]. This is meant for native libraries that can only be initialized once per process.
+///
+/// The tombstone is set before [`FromWorld`] is called, so `R` is not initialized again if it panics. Resources initialized this way cannot be reinitialized with [`reinit_tracked_non_send`](crate::reinit_tracked_non_send).
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{init_non_send_resource_once, is_non_send_initialized_once};
+/// #
+/// struct WindowingLibrary(*const u8);
+///
+/// impl Default for WindowingLibrary {
+///     fn default() -> Self {
+///         WindowingLibrary(std::ptr::null())
+///     }
+/// }
+///
+/// fn init_library(mut commands: Commands) {
+///     commands.add(init_non_send_resource_once::<WindowingLibrary>());
+/// }
+///
+/// fn shutdown_library(world: &mut World) {
+///     world.remove_non_send_resource::<WindowingLibrary>();
+/// }
+///
+/// App::new()
+///     .add_systems(Startup, (init_library, shutdown_library, init_library, check).chain())
+///     .run();
+///
+/// fn check(library: Option<NonSend<WindowingLibrary>>) {
+///     // The second `init_library` did nothing.
+///     assert!(library.is_none());
+///     assert!(is_non_send_initialized_once::<WindowingLibrary>());
+/// }
+/// ```
+#[track_caller]
+pub fn init_non_send_resource_once<R: FromWorld + 'static>() -> impl Command {
+    let location = Location::caller();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Init,
+        location,
+        move |world: &mut World| {
+            if mark::<R>() && !world.contains_non_send::<R>() {
+                if !crate::factory::create::<R>(world) {
+                    world.init_non_send_resource::<R>();
+                }
+
+                tracking::track::<R>(world, location, None);
+            }
+        },
+    )
+}
@@ -0,0 +1,404 @@
+use std::{
+    io::{self, Read, Write},
+    panic::Location,
+};
+
+use bevy_ecs::{
+    system::{Command, Resource},
+    world::World,
+};
+use bevy_utils::tracing::error;
+
+use crate::{
+    error::{handle_error, NonSendCommandError, NonSendErrorContext},
+    main_thread, tracking,
+};
+
+type SaveFn = dyn Fn(&World) -> Option<io::Result<Vec<u8>>> + Send + Sync;
+type LoadFn =
+    dyn Fn(&mut World, &mut dyn Read, &'static Location<'static>) -> io::Result<()> + Send + Sync;
+
+/// A [`Resource`] containing the serialization functions of persistent non-[`Send`] resources.
+///
+/// Types are registered with [`AppExt::register_non_send_persistence`](crate::AppExt::register_non_send_persistence), and are saved and loaded with [`save_non_send_resources`] and [`load_non_send_resources`].
+#[derive(Resource, Default)]
+pub struct NonSendPersistence {
+    entries: Vec<PersistenceEntry>,
+}
+
+struct PersistenceEntry {
+    key: &'static str,
+    save: Box<SaveFn>,
+    load: Box<LoadFn>,
+}
+
+impl NonSendPersistence {
+    /// Registers `R` as a persistent non-[`Send`] resource.
+    ///
+    /// `serialize_with` writes the resource to a byte buffer, and `deserialize_with` reads it back. Resources are identified by their type name, so renaming a type will invalidate previously saved data.
+    pub fn register<R, S, D>(&mut self, serialize_with: S, deserialize_with: D)
+    where
+        R: 'static,
+        S: Fn(&R, &mut dyn Write) -> io::Result<()> + Send + Sync + 'static,
+        D: Fn(&mut dyn Read) -> io::Result<R> + Send + Sync + 'static,
+    {
+        let key = std::any::type_name::<R>();
+
+        self.entries.retain(|entry| entry.key != key);
+        self.entries.push(PersistenceEntry {
+            key,
+            save: Box::new(move |world| {
+                let resource = world.get_non_send_resource::<R>()?;
+                let mut buffer = Vec::new();
+
+                Some(serialize_with(resource, &mut buffer).map(|()| buffer))
+            }),
+            load: Box::new(move |world, reader, location| {
+                let resource = deserialize_with(reader)?;
+                world.insert_non_send_resource(resource);
+                tracking::track::<R>(world, location, None);
+
+                Ok(())
+            }),
+        });
+    }
+}
+
+/// Writes all registered non-[`Send`] resources that currently exist in the [`World`] to `writer`.
+///
+/// Each resource is written as its type name followed by the bytes produced by its `serialize_with` function, both prefixed with their length.
+pub fn save_non_send_resources_to(world: &World, writer: &mut dyn Write) -> io::Result<()> {
+    let Some(persistence) = world.get_resource::<NonSendPersistence>() else {
+        return Ok(());
+    };
+
+    for entry in &persistence.entries {
+        let Some(payload) = (entry.save)(world) else {
+            continue;
+        };
+
+        let payload = payload?;
+
+        write_len(writer, entry.key.len())?;
+        writer.write_all(entry.key.as_bytes())?;
+        write_len(writer, payload.len())?;
+        writer.write_all(&payload)?;
+    }
+
+    writer.flush()
+}
+
+/// Reads non-[`Send`] resources previously written by [`save_non_send_resources_to`] from `reader`, inserting them into the [`World`].
+///
+/// Entries whose type is not registered are skipped. Entries that are truncated, or whose length prefix exceeds the remaining data, fail with [`io::ErrorKind::InvalidData`].
+#[track_caller]
+pub fn load_non_send_resources_from(world: &mut World, reader: &mut dyn Read) -> io::Result<()> {
+    load_from(world, reader, Location::caller())
+}
+
+/// Loads the resources read from `reader`, recording `location` as where they were inserted.
+fn load_from(
+    world: &mut World,
+    reader: &mut dyn Read,
+    location: &'static Location<'static>,
+) -> io::Result<()> {
+    // Temporarily take the registry out of the world so `load` functions can access it mutably.
+    let Some(persistence) = world.remove_resource::<NonSendPersistence>() else {
+        return Ok(());
+    };
+
+    let result = load_entries(world, &persistence, reader, location);
+
+    world.insert_resource(persistence);
+
+    result
+}
+
+fn load_entries(
+    world: &mut World,
+    persistence: &NonSendPersistence,
+    reader: &mut dyn Read,
+    location: &'static Location<'static>,
+) -> io::Result<()> {
+    while let Some(key_len) = read_len(reader)? {
+        let key = read_bytes(reader, key_len)?;
+
+        let payload_len = read_len(reader)?.ok_or_else(truncated)?;
+        let payload = read_bytes(reader, payload_len)?;
+
+        let Some(entry) = persistence
+            .entries
+            .iter()
+            .find(|entry| entry.key.as_bytes() == key)
+        else {
+            continue;
+        };
+
+        (entry.load)(world, &mut payload.as_slice(), location)?;
+    }
+
+    Ok(())
+}
+
+fn write_len(writer: &mut dyn Write, len: usize) -> io::Result<()> {
+    writer.write_all(&(len as u64).to_le_bytes())
+}
+
+/// Reads a length prefix, returning [`None`] if the reader is already at its end.
+fn read_len(reader: &mut dyn Read) -> io::Result<Option<usize>> {
+    let mut bytes = [0; 8];
+    let mut read = 0;
+
+    while read < bytes.len() {
+        match reader.read(&mut bytes[read..]) {
+            Ok(0) if read == 0 => return Ok(None),
+            Ok(0) => return Err(truncated()),
+            Ok(n) => read += n,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+
+    usize::try_from(u64::from_le_bytes(bytes))
+        .map(Some)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// Reads exactly `len` bytes.
+///
+/// The buffer only grows with the data actually read, so a corrupted length prefix cannot cause a huge allocation.
+fn read_bytes(reader: &mut dyn Read, len: usize) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+
+    if bytes.len() != len {
+        return Err(truncated());
+    }
+
+    Ok(bytes)
+}
+
+fn truncated() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "non-send resource save data is truncated",
+    )
+}
+
+/// Creates a [`Command`] for saving all persistent non-[`Send`] resources to `writer`.
+///
+/// Resources must first be registered with [`AppExt::register_non_send_persistence`](crate::AppExt::register_non_send_persistence). Serialization runs on the main thread. Errors are logged.
+///
+/// See [`save_non_send_resources_to`] for more details.
+///
+/// ```
+/// # use std::io::{Read, Write};
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{save_non_send_resources, AppExt};
+/// #
+/// struct Interpreter(std::rc::Rc<String>);
+///
+/// fn save_game(mut commands: Commands) {
+///     commands.add(
+///         save_non_send_resources(Vec::new())
+///     );
+/// }
+///
+/// App::new()
+///     .register_non_send_persistence::<Interpreter>(
+///         |interpreter, writer| writer.write_all(interpreter.0.as_bytes()),
+///         |reader| {
+///             let mut state = String::new();
+///             reader.read_to_string(&mut state)?;
+///             Ok(Interpreter(state.into()))
+///         },
+///     )
+///     .add_systems(Startup, save_game)
+///     .run();
+/// ```
+#[track_caller]
+pub fn save_non_send_resources<W: Write + Send + 'static>(mut writer: W) -> impl Command {
+    main_thread::deferrable(move |world: &mut World| {
+        if let Err(error) = save_non_send_resources_to(world, &mut writer) {
+            error!("Failed to save non-send resources: {error}");
+        }
+    })
+}
+
+/// Creates a [`Command`] for loading persistent non-[`Send`] resources from `reader`.
+///
+/// Resources must first be registered with [`AppExt::register_non_send_persistence`](crate::AppExt::register_non_send_persistence). Deserialization runs on the main thread. Errors are logged.
+///
+/// See [`load_non_send_resources_from`] for more details.
+///
+/// ```
+/// # use std::io::{Read, Write};
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{load_non_send_resources, save_non_send_resources_to, AppExt};
+/// #
+/// struct Interpreter(std::rc::Rc<String>);
+///
+/// fn load_game(mut commands: Commands) {
+/// #   let save_file = {
+/// #       let mut world = World::new();
+/// #       world.init_resource::<bevy_command_non_send::NonSendPersistence>();
+/// #       world.resource_mut::<bevy_command_non_send::NonSendPersistence>().register::<Interpreter, _, _>(
+/// #           |interpreter, writer| writer.write_all(interpreter.0.as_bytes()),
+/// #           |_| unreachable!(),
+/// #       );
+/// #       world.insert_non_send_resource(Interpreter(String::from("x = 1").into()));
+/// #       let mut buffer = Vec::new();
+/// #       save_non_send_resources_to(&world, &mut buffer).unwrap();
+/// #       std::io::Cursor::new(buffer)
+/// #   };
+///     commands.add(
+///         load_non_send_resources(save_file)
+///     );
+/// }
+///
+/// App::new()
+///     .register_non_send_persistence::<Interpreter>(
+///         |interpreter, writer| writer.write_all(interpreter.0.as_bytes()),
+///         |reader| {
+///             let mut state = String::new();
+///             reader.read_to_string(&mut state)?;
+///             Ok(Interpreter(state.into()))
+///         },
+///     )
+///     .add_systems(Startup, (load_game, check).chain())
+///     .run();
+/// #
+/// # fn check(interpreter: NonSend<Interpreter>) {
+/// #     assert_eq!(*interpreter.0, "x = 1");
+/// # }
+/// ```
+#[track_caller]
+pub fn load_non_send_resources<R: Read + Send + 'static>(mut reader: R) -> impl Command {
+    let location = Location::caller();
+
+    main_thread::deferrable(move |world: &mut World| {
+        if let Err(error) = load_from(world, &mut reader, location) {
+            error!("Failed to load non-send resources: {error}");
+        }
+    })
+}
+
+/// Creates a [`Command`] for saving all persistent non-[`Send`] resources to `writer`, passing any failure to the [`NonSendErrorHandler`](crate::NonSendErrorHandler).
+///
+/// See [`save_non_send_resources`] for more details.
+#[track_caller]
+pub fn try_save_non_send_resources<W: Write + Send + 'static>(mut writer: W) -> impl Command {
+    let context = NonSendErrorContext {
+        command: "try_save_non_send_resources",
+        location: Location::caller(),
+    };
+
+    main_thread::deferrable(move |world: &mut World| {
+        let result =
+            save_non_send_resources_to(world, &mut writer).map_err(NonSendCommandError::Io);
+        handle_error(world, result, context);
+    })
+}
+
+/// Creates a [`Command`] for loading persistent non-[`Send`] resources from `reader`, passing any failure to the [`NonSendErrorHandler`](crate::NonSendErrorHandler).
+///
+/// See [`load_non_send_resources`] for more details.
+#[track_caller]
+pub fn try_load_non_send_resources<R: Read + Send + 'static>(mut reader: R) -> impl Command {
+    let context = NonSendErrorContext {
+        command: "try_load_non_send_resources",
+        location: Location::caller(),
+    };
+
+    main_thread::deferrable(move |world: &mut World| {
+        let result =
+            load_from(world, &mut reader, context.location).map_err(NonSendCommandError::Io);
+        handle_error(world, result, context);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+
+    use super::*;
+    use crate::NonSendTracker;
+
+    struct Interpreter(String);
+
+    fn world() -> World {
+        let mut world = World::new();
+
+        world.init_resource::<NonSendTracker>();
+        world.init_resource::<NonSendPersistence>();
+        world
+            .resource_mut::<NonSendPersistence>()
+            .register::<Interpreter, _, _>(
+                |interpreter, writer| writer.write_all(interpreter.0.as_bytes()),
+                |reader| {
+                    let mut state = String::new();
+                    reader.read_to_string(&mut state)?;
+                    Ok(Interpreter(state))
+                },
+            );
+
+        world
+    }
+
+    fn save(state: &str) -> Vec<u8> {
+        let mut world = world();
+        world.insert_non_send_resource(Interpreter(state.to_string()));
+
+        let mut buffer = Vec::new();
+        save_non_send_resources_to(&world, &mut buffer).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn load_tracks_resources() {
+        let mut world = world();
+
+        load_non_send_resources_from(&mut world, &mut save("x = 1").as_slice()).unwrap();
+
+        assert_eq!(world.non_send_resource::<Interpreter>().0, "x = 1");
+        assert!(world
+            .resource::<NonSendTracker>()
+            .get(TypeId::of::<Interpreter>())
+            .is_some());
+    }
+
+    #[test]
+    fn huge_length_is_invalid_data() {
+        let mut world = world();
+        let mut data = (u32::MAX as u64).to_le_bytes().to_vec();
+        data.extend_from_slice(b"Interpreter");
+
+        let error = load_non_send_resources_from(&mut world, &mut data.as_slice()).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn truncated_payload_is_invalid_data() {
+        let mut world = world();
+        let mut data = save("x = 1");
+        data.truncate(data.len() - 1);
+
+        let error = load_non_send_resources_from(&mut world, &mut data.as_slice()).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(world.get_non_send_resource::<Interpreter>().is_none());
+    }
+
+    #[test]
+    fn missing_payload_length_is_invalid_data() {
+        let mut world = world();
+        let mut data = save("x = 1");
+        let key_len = std::any::type_name::<Interpreter>().len();
+        data.truncate(8 + key_len + 3);
+
+        let error = load_non_send_resources_from(&mut world, &mut data.as_slice()).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,72 @@
+use std::panic::Location;
+
+use bevy_ecs::{
+    system::{Commands, In, IntoSystem, System},
+    world::World,
+};
+
+use crate::{tracking, NonSendCommand, NonSendCommandKind};
+
+/// An extension trait for piping the output of a system into a non-[`Send`] resource.
+pub trait PipeIntoNonSend<Out, Marker>: IntoSystem<(), Out, Marker> + Sized {
+    /// Pipes the output of this system into the non-[`Send`] resource `R`, converting it with [`From`].
+    ///
+    /// Each time the system runs, its output is converted and inserted on the main thread when commands are applied, replacing the previous value. The system itself does not need to run on the main thread, so only the final insertion is bound to it.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::PipeIntoNonSend;
+    /// #
+    /// struct PipelineDescriptor {
+    ///     shader_count: u32,
+    /// }
+    ///
+    /// struct NativePipeline {
+    ///     shader_count: u32,
+    ///     _handle: *const u8,
+    /// }
+    ///
+    /// impl From<PipelineDescriptor> for NativePipeline {
+    ///     fn from(descriptor: PipelineDescriptor) -> Self {
+    ///         NativePipeline {
+    ///             shader_count: descriptor.shader_count,
+    ///             _handle: std::ptr::null(),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// // This system can run on any thread.
+    /// fn describe_pipeline() -> PipelineDescriptor {
+    ///     PipelineDescriptor { shader_count: 3 }
+    /// }
+    ///
+    /// App::new()
+    ///     .add_systems(Startup, (describe_pipeline.pipe_into_non_send::<NativePipeline>(), check).chain())
+    ///     .run();
+    ///
+    /// fn check(pipeline: NonSend<NativePipeline>) {
+    ///     assert_eq!(pipeline.shader_count, 3);
+    /// }
+    /// ```
+    #[track_caller]
+    fn pipe_into_non_send<R>(self) -> impl System<In = (), Out = ()>
+    where
+        Out: Send + 'static,
+        R: From<Out> + 'static,
+    {
+        let location = Location::caller();
+
+        self.pipe(move |In(output): In<Out>, mut commands: Commands| {
+            commands.add(NonSendCommand::new::<R>(
+                NonSendCommandKind::Insert,
+                location,
+                move |world: &mut World| {
+                    world.insert_non_send_resource(R::from(output));
+                    tracking::track::<R>(world, location, None);
+                },
+            ));
+        })
+    }
+}
+
+impl<Out, Marker, S: IntoSystem<(), Out, Marker>> PipeIntoNonSend<Out, Marker> for S {}
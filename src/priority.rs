@@ -0,0 +1,148 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use bevy_ecs::{
+    system::{
+        Command, CommandQueue, Commands, Deferred, Resource, SystemBuffer, SystemMeta, SystemParam,
+    },
+    world::{FromWorld, World},
+};
+
+use crate::NonSendCommand;
+
+/// A high-priority command queue shared by every [`PriorityCommands`] in a [`World`].
+///
+/// This is stored as a [`Resource`], and each [`PriorityCommands`] holds a handle to it. Whenever the buffers of a system using [`PriorityCommands`] are applied, the whole queue is drained first, including commands queued by other systems.
+#[derive(Resource, Clone)]
+pub struct PriorityQueue(Arc<Mutex<CommandQueue>>);
+
+impl FromWorld for PriorityQueue {
+    fn from_world(world: &mut World) -> Self {
+        world
+            .get_resource_or_insert_with(|| PriorityQueue(Arc::default()))
+            .clone()
+    }
+}
+
+impl SystemBuffer for PriorityQueue {
+    fn apply(&mut self, _system_meta: &SystemMeta, world: &mut World) {
+        // Take the queue before applying it, so commands can queue more priority commands without deadlocking.
+        let mut queue = std::mem::take(&mut *self.0.lock().unwrap_or_else(PoisonError::into_inner));
+
+        queue.apply(world);
+    }
+}
+
+/// A [`SystemParam`] that wraps [`Commands`], applying non-[`Send`] insertions before the other commands of every system that uses it.
+///
+/// [`PriorityCommands::init_non_send_resource`] and [`PriorityCommands::insert_non_send_resource`] are routed to a high-priority [`PriorityQueue`] shared by the whole [`World`]. Every other command goes through the wrapped [`Commands`], which [`PriorityCommands`] dereferences to. This lets commands that spawn entities rely on the non-[`Send`] resource already existing, regardless of the order they were queued in, even if another system queued the insertion.
+///
+/// The priority queue is drained when the first system using [`PriorityCommands`] applies its buffers. Commands of systems that only use plain [`Commands`] and are applied before that system are not covered, so they cannot rely on the insertion.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::PriorityCommands;
+/// #
+/// struct MyNonSend(*const u8);
+///
+/// fn setup(mut commands: PriorityCommands) {
+///     commands.add(|world: &mut World| {
+///         // This runs after `MyNonSend` is inserted, even though it was queued first.
+///         assert!(world.contains_non_send::<MyNonSend>());
+///     });
+///
+///     commands.insert_non_send_resource(|| MyNonSend(std::ptr::null()));
+/// }
+/// #
+/// # App::new()
+/// #     .add_systems(Startup, setup)
+/// #     .run();
+/// ```
+#[derive(SystemParam)]
+pub struct PriorityCommands<'w, 's> {
+    // Fields are applied in declaration order, so the priority queue must come first.
+    priority: Deferred<'s, PriorityQueue>,
+    commands: Commands<'w, 's>,
+}
+
+impl PriorityCommands<'_, '_> {
+    /// Queues a [`NonSendCommand::init`] that is applied before the other commands of every system using [`PriorityCommands`].
+    ///
+    /// See [`init_non_send_resource`](crate::init_non_send_resource).
+    #[track_caller]
+    pub fn init_non_send_resource<R: FromWorld + 'static>(&mut self) {
+        self.add_priority(NonSendCommand::init::<R>());
+    }
+
+    /// Queues a [`NonSendCommand::insert`] that is applied before the other commands of every system using [`PriorityCommands`].
+    ///
+    /// See [`insert_non_send_resource`](crate::insert_non_send_resource).
+    #[track_caller]
+    pub fn insert_non_send_resource<F, R>(&mut self, func: F)
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: 'static,
+    {
+        self.add_priority(NonSendCommand::insert(func));
+    }
+
+    /// Queues an arbitrary [`Command`] that is applied before the other commands of every system using [`PriorityCommands`].
+    pub fn add_priority<C: Command>(&mut self, command: C) {
+        self.priority
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(command);
+    }
+}
+
+impl<'w, 's> Deref for PriorityCommands<'w, 's> {
+    type Target = Commands<'w, 's>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.commands
+    }
+}
+
+impl DerefMut for PriorityCommands<'_, '_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.commands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::schedule::{IntoSystemConfigs, Schedule};
+
+    use super::*;
+
+    struct MyNonSend;
+
+    #[derive(Resource)]
+    struct Existed(bool);
+
+    fn spawn(mut commands: PriorityCommands) {
+        commands.add(|world: &mut World| {
+            let existed = world.contains_non_send::<MyNonSend>();
+            world.insert_resource(Existed(existed));
+        });
+    }
+
+    fn insert(mut commands: PriorityCommands) {
+        commands.insert_non_send_resource(|| MyNonSend);
+    }
+
+    #[test]
+    fn priority_commands_are_ordered_across_systems() {
+        let mut world = World::new();
+        let mut schedule = Schedule::default();
+
+        // `spawn` is applied first, but `insert` still queued its insertion before the sync point.
+        schedule.add_systems((spawn, insert).chain_ignore_deferred());
+        schedule.run(&mut world);
+
+        assert!(world.resource::<Existed>().0);
+    }
+}
@@ -0,0 +1,244 @@
+use std::{
+    any::TypeId,
+    cmp::Reverse,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use bevy_app::{App, Plugin};
+use bevy_ecs::{system::Resource, world::World};
+use bevy_utils::tracing::info;
+
+use crate::NonSendCommand;
+
+/// The upper bounds of each [`NonSendTiming`] histogram bucket, except the last one, which has no upper bound.
+pub const TIMING_BUCKETS: [Duration; 5] = [
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+];
+
+/// How long the [`NonSendCommand`]s of a single resource type took to apply.
+#[derive(Clone, Debug)]
+pub struct NonSendTiming {
+    type_name: &'static str,
+    count: u32,
+    total: Duration,
+    max: Duration,
+    histogram: [u32; TIMING_BUCKETS.len() + 1],
+}
+
+impl NonSendTiming {
+    fn new(type_name: &'static str) -> Self {
+        NonSendTiming {
+            type_name,
+            count: 0,
+            total: Duration::ZERO,
+            max: Duration::ZERO,
+            histogram: [0; TIMING_BUCKETS.len() + 1],
+        }
+    }
+
+    fn add(&mut self, elapsed: Duration) {
+        let bucket = TIMING_BUCKETS
+            .iter()
+            .position(|&bound| elapsed < bound)
+            .unwrap_or(TIMING_BUCKETS.len());
+
+        self.count += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+        self.histogram[bucket] += 1;
+    }
+
+    /// Returns the name of the resource type.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns how many commands were measured.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Returns the total time spent applying commands.
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// Returns the longest time spent applying a single command.
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Returns the average time spent applying a single command.
+    pub fn mean(&self) -> Duration {
+        self.total.checked_div(self.count).unwrap_or_default()
+    }
+
+    /// Returns how many commands fell in each bucket of [`TIMING_BUCKETS`].
+    ///
+    /// The last element counts the commands that took at least one second.
+    pub fn histogram(&self) -> &[u32] {
+        &self.histogram
+    }
+}
+
+/// A [`Resource`] that measures how long each [`NonSendCommand`] takes to apply, grouped by resource type.
+///
+/// The measured time includes running the factory and inserting the resource into the [`World`]. Only [`NonSendCommand`]s are measured, so the commands listed as exceptions in its documentation, such as [`save_non_send_resources`](crate::save_non_send_resources), are not. Nothing is measured unless this resource exists, which is usually inserted by the [`NonSendProfilerPlugin`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{CommandsExt, NonSendProfiler, NonSendProfilerPlugin};
+/// #
+/// struct AudioDevice(*const u8);
+///
+/// fn open_audio_device(mut commands: Commands) {
+///     commands.insert_non_send_resource(|| {
+///         std::thread::sleep(std::time::Duration::from_millis(5));
+///         AudioDevice(std::ptr::null())
+///     });
+/// }
+///
+/// let mut app = App::new();
+///
+/// app.add_plugins(NonSendProfilerPlugin)
+///     .add_systems(Startup, open_audio_device);
+///
+/// app.update();
+///
+/// let profiler = app.world.resource::<NonSendProfiler>();
+/// let timing = profiler.timing::<AudioDevice>().unwrap();
+///
+/// assert_eq!(timing.count(), 1);
+/// assert!(timing.max() >= std::time::Duration::from_millis(5));
+///
+/// // Logs the slowest resource types first.
+/// profiler.log_report();
+/// ```
+#[derive(Resource, Default, Debug)]
+pub struct NonSendProfiler {
+    timings: HashMap<TypeId, NonSendTiming>,
+    // Samples that have not been reported as diagnostics yet.
+    #[cfg(feature = "bevy_diagnostic")]
+    pending: Vec<(TypeId, Duration)>,
+}
+
+impl NonSendProfiler {
+    /// Returns the timing of the resource type `R`, if any of its commands were measured.
+    pub fn timing<R: 'static>(&self) -> Option<&NonSendTiming> {
+        self.timings.get(&TypeId::of::<R>())
+    }
+
+    /// Returns the timings of all measured resource types, sorted by total time with the slowest first.
+    pub fn timings(&self) -> Vec<&NonSendTiming> {
+        let mut timings: Vec<_> = self.timings.values().collect();
+        timings.sort_by_key(|timing| Reverse(timing.total));
+        timings
+    }
+
+    /// Logs the timings of all measured resource types, with the slowest first.
+    pub fn log_report(&self) {
+        for timing in self.timings() {
+            info!(
+                "{}: {} commands, {:?} total, {:?} mean, {:?} max, histogram {:?}",
+                timing.type_name,
+                timing.count,
+                timing.total,
+                timing.mean(),
+                timing.max,
+                timing.histogram,
+            );
+        }
+    }
+
+    /// Discards all measurements.
+    pub fn clear(&mut self) {
+        self.timings.clear();
+
+        #[cfg(feature = "bevy_diagnostic")]
+        self.pending.clear();
+    }
+
+    fn record(&mut self, type_id: TypeId, type_name: &'static str, elapsed: Duration) {
+        self.timings
+            .entry(type_id)
+            .or_insert_with(|| NonSendTiming::new(type_name))
+            .add(elapsed);
+
+        #[cfg(feature = "bevy_diagnostic")]
+        self.pending.push((type_id, elapsed));
+    }
+}
+
+/// Applies `command`, measuring it if the [`NonSendProfiler`] exists.
+pub(crate) fn apply_profiled(command: NonSendCommand, world: &mut World) {
+    if !world.contains_resource::<NonSendProfiler>() {
+        command.apply_uninspected(world);
+        return;
+    }
+
+    let type_id = command.type_id();
+    let type_name = command.type_name();
+
+    let start = Instant::now();
+    command.apply_uninspected(world);
+    let elapsed = start.elapsed();
+
+    if let Some(mut profiler) = world.get_resource_mut::<NonSendProfiler>() {
+        profiler.record(type_id, type_name, elapsed);
+    }
+}
+
+/// A [`Plugin`] that enables the [`NonSendProfiler`].
+///
+/// With the `bevy_diagnostic` feature, every measurement is also reported in milliseconds to the `DiagnosticsStore`, under the path `non_send/<type name>`.
+pub struct NonSendProfilerPlugin;
+
+impl Plugin for NonSendProfilerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NonSendProfiler>();
+
+        #[cfg(feature = "bevy_diagnostic")]
+        app.add_systems(bevy_app::Last, diagnostics::report_timings);
+    }
+}
+
+#[cfg(feature = "bevy_diagnostic")]
+mod diagnostics {
+    use bevy_diagnostic::{Diagnostic, DiagnosticMeasurement, DiagnosticPath, DiagnosticsStore};
+    use bevy_ecs::system::ResMut;
+    use bevy_utils::Instant;
+
+    use super::NonSendProfiler;
+
+    pub(super) fn report_timings(
+        mut profiler: ResMut<NonSendProfiler>,
+        store: Option<ResMut<DiagnosticsStore>>,
+    ) {
+        let pending = std::mem::take(&mut profiler.pending);
+
+        let Some(mut store) = store else {
+            return;
+        };
+
+        for (type_id, elapsed) in pending {
+            let path =
+                DiagnosticPath::from_components(["non_send", profiler.timings[&type_id].type_name]);
+
+            if store.get(&path).is_none() {
+                store.add(Diagnostic::new(path.clone()).with_suffix("ms"));
+            }
+
+            if let Some(diagnostic) = store.get_mut(&path) {
+                diagnostic.add_measurement(DiagnosticMeasurement {
+                    time: Instant::now(),
+                    value: elapsed.as_secs_f64() * 1000.0,
+                });
+            }
+        }
+    }
+}
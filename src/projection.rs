@@ -0,0 +1,47 @@
+use std::marker::PhantomData;
+
+use bevy_app::{App, Last};
+use bevy_ecs::system::{Commands, Local, NonSend, Res, Resource};
+
+type ProjectFn<R, S> = dyn Fn(&R) -> S + Send + Sync;
+
+/// A [`Resource`] containing the function that projects the non-[`Send`] resource `R` into the [`Send`] resource `S`.
+///
+/// See [`AppExt::add_non_send_projection`](crate::AppExt::add_non_send_projection).
+#[derive(Resource)]
+struct NonSendProjection<R: 'static, S> {
+    project: Box<ProjectFn<R, S>>,
+    _marker: PhantomData<fn() -> R>,
+}
+
+/// Registers `project` and the system that keeps `S` updated. See `AppExt::add_non_send_projection`.
+pub(crate) fn add_non_send_projection<R, S>(
+    app: &mut App,
+    project: impl Fn(&R) -> S + Send + Sync + 'static,
+) where
+    R: 'static,
+    S: Resource,
+{
+    app.insert_resource(NonSendProjection::<R, S> {
+        project: Box::new(project),
+        _marker: PhantomData,
+    })
+    .add_systems(Last, update_projection::<R, S>);
+}
+
+fn update_projection<R: 'static, S: Resource>(
+    mut commands: Commands,
+    resource: Option<NonSend<R>>,
+    projection: Res<NonSendProjection<R, S>>,
+    mut existed: Local<bool>,
+) {
+    match &resource {
+        Some(resource) if resource.is_changed() || !*existed => {
+            commands.insert_resource((projection.project)(resource));
+        }
+        None if *existed => commands.remove_resource::<S>(),
+        _ => {}
+    }
+
+    *existed = resource.is_some();
+}
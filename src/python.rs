@@ -0,0 +1,99 @@
+use std::panic::Location;
+
+use bevy_ecs::{system::Command, world::World};
+use pyo3::Python;
+
+use crate::{
+    error, main_thread, tracking, NonSendCommand, NonSendCommandError, NonSendCommandKind,
+    NonSendErrorContext,
+};
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource created while holding the Python GIL on the main thread.
+///
+/// This is meant for resources that hold Python interpreter state, such as `Py<PyModule>` handles or types that wrap `Bound` values, which must only be touched from the main thread.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{insert_non_send_with_gil, with_gil_command};
+/// # use pyo3::prelude::*;
+/// #
+/// struct PythonScripts {
+///     globals: Py<pyo3::types::PyDict>,
+///     runs: u32,
+/// }
+///
+/// fn create_scripts(mut commands: Commands) {
+///     commands.add(insert_non_send_with_gil(|py: Python| PythonScripts {
+///         globals: pyo3::types::PyDict::new_bound(py).unbind(),
+///         runs: 0,
+///     }));
+/// }
+///
+/// fn run_script(mut commands: Commands) {
+///     commands.add(with_gil_command(|py: Python, scripts: &mut PythonScripts| {
+///         py.run_bound("answer = 6 * 7", Some(scripts.globals.bind(py)), None).unwrap();
+///         scripts.runs += 1;
+///     }));
+/// }
+/// #
+/// # App::new()
+/// #     .add_systems(Startup, (create_scripts, run_script, check).chain())
+/// #     .run();
+/// #
+/// # fn check(scripts: NonSend<PythonScripts>) {
+/// #     assert_eq!(scripts.runs, 1);
+/// #
+/// #     Python::with_gil(|py| {
+/// #         let answer: i32 = scripts.globals.bind(py).get_item("answer").unwrap().unwrap().extract().unwrap();
+/// #         assert_eq!(answer, 42);
+/// #     });
+/// # }
+/// ```
+#[track_caller]
+pub fn insert_non_send_with_gil<F, R>(func: F) -> impl Command
+where
+    F: for<'py> FnOnce(Python<'py>) -> R + Send + 'static,
+    R: 'static,
+{
+    let location = Location::caller();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| {
+            let resource = Python::with_gil(func);
+
+            world.insert_non_send_resource(resource);
+            tracking::track::<R>(world, location, None);
+        },
+    )
+}
+
+/// Creates a [`Command`] that acquires the Python GIL on the main thread and calls `func` with the non-[`Send`] resource `R`.
+///
+/// If `R` does not exist, a [`NonSendCommandError::ResourceMissing`] error is passed to the [`NonSendErrorHandler`](crate::NonSendErrorHandler). See [`insert_non_send_with_gil`].
+#[track_caller]
+pub fn with_gil_command<R, F>(func: F) -> impl Command
+where
+    R: 'static,
+    F: for<'py> FnOnce(Python<'py>, &mut R) + Send + 'static,
+{
+    let context = NonSendErrorContext {
+        command: "with_gil_command",
+        location: Location::caller(),
+    };
+
+    main_thread::deferrable(move |world: &mut World| {
+        let result = match world.get_non_send_resource_mut::<R>() {
+            Some(mut resource) => {
+                Python::with_gil(|py| func(py, &mut resource));
+                Ok(())
+            }
+            None => Err(NonSendCommandError::ResourceMissing {
+                type_name: std::any::type_name::<R>(),
+            }),
+        };
+
+        error::handle_error(world, result, context);
+    })
+}
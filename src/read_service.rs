@@ -0,0 +1,165 @@
+use std::{
+    marker::PhantomData,
+    sync::mpsc::{self, Receiver, RecvError, Sender, SyncSender, TryRecvError},
+};
+
+use bevy_app::{App, Last, Plugin};
+use bevy_ecs::system::{NonSend, ResMut, Resource};
+use bevy_utils::synccell::SyncCell;
+
+/// A request to read a value of type `T` from the non-[`Send`] resource `R`.
+///
+/// Requests are created with [`ReadRequest::new`] and submitted with [`NonSendReadService::submit`], or both at once with [`NonSendReadService::read`].
+pub struct ReadRequest<R, T> {
+    func: Box<dyn FnOnce(&R) -> T + Send>,
+    reply: SyncSender<T>,
+}
+
+impl<R, T: Send + 'static> ReadRequest<R, T> {
+    /// Creates a request that calls `func` with the resource on the main thread, and the [`ReadReply`] that will receive its result.
+    pub fn new(func: impl FnOnce(&R) -> T + Send + 'static) -> (Self, ReadReply<T>) {
+        let (reply, receiver) = mpsc::sync_channel(1);
+
+        let request = ReadRequest {
+            func: Box::new(func),
+            reply,
+        };
+
+        (request, ReadReply { receiver })
+    }
+}
+
+type BoxedRequest<R> = Box<dyn AnswerRead<R>>;
+
+/// Type-erased [`ReadRequest`]s for the same resource.
+trait AnswerRead<R>: Send {
+    fn answer(self: Box<Self>, resource: &R);
+}
+
+impl<R, T: Send> AnswerRead<R> for ReadRequest<R, T> {
+    fn answer(self: Box<Self>, resource: &R) {
+        // The requester may have stopped waiting, which is fine.
+        let _ = self.reply.send((self.func)(resource));
+    }
+}
+
+/// The pending result of a [`ReadRequest`].
+pub struct ReadReply<T> {
+    receiver: Receiver<T>,
+}
+
+impl<T> ReadReply<T> {
+    /// Blocks the current thread until the request is answered.
+    ///
+    /// Returns an error if the request was dropped without being answered, such as when the app exits first.
+    ///
+    /// This must not be called from the main thread, as that would prevent the request from ever being answered.
+    pub fn recv(self) -> Result<T, RecvError> {
+        self.receiver.recv()
+    }
+
+    /// Returns the result if the request has been answered, without blocking.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.receiver.try_recv()
+    }
+}
+
+/// A [`Resource`] and [`Send`] handle that lets any thread read from the non-[`Send`] resource `R`.
+///
+/// Requests are answered on the main thread by [`NonSendReadServicePlugin`] during [`Last`]. If `R` does not exist, requests stay pending until it is inserted. Clone this handle to pass it to worker threads.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{NonSendReadService, NonSendReadServicePlugin};
+/// #
+/// struct GlContext(*const u8, u32);
+///
+/// fn spawn_worker(service: Res<NonSendReadService<GlContext>>) {
+///     let service = service.clone();
+///
+///     std::thread::spawn(move || {
+///         let max_texture_size = service.read(|context: &GlContext| context.1).recv().unwrap();
+///
+///         assert_eq!(max_texture_size, 4096);
+///     });
+/// }
+/// #
+/// # let mut app = App::new();
+/// # app.insert_non_send_resource(GlContext(std::ptr::null(), 4096))
+/// #     .add_plugins(NonSendReadServicePlugin::<GlContext>::default())
+/// #     .add_systems(Startup, spawn_worker);
+/// # app.update();
+/// #
+/// # let reply = app.world.resource::<NonSendReadService<GlContext>>().read(|context| context.1);
+/// # app.update();
+/// # assert_eq!(reply.recv(), Ok(4096));
+/// ```
+#[derive(Resource)]
+pub struct NonSendReadService<R: 'static> {
+    sender: Sender<BoxedRequest<R>>,
+}
+
+impl<R: 'static> Clone for NonSendReadService<R> {
+    fn clone(&self) -> Self {
+        NonSendReadService {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<R: 'static> NonSendReadService<R> {
+    /// Submits a [`ReadRequest`] to be answered on the main thread.
+    pub fn submit<T: Send + 'static>(&self, request: ReadRequest<R, T>) {
+        // If the app has exited, the request is dropped and its reply reports an error.
+        let _ = self.sender.send(Box::new(request));
+    }
+
+    /// Submits a request that calls `func` with the resource on the main thread, returning a [`ReadReply`] for its result.
+    pub fn read<T: Send + 'static>(
+        &self,
+        func: impl FnOnce(&R) -> T + Send + 'static,
+    ) -> ReadReply<T> {
+        let (request, reply) = ReadRequest::new(func);
+        self.submit(request);
+        reply
+    }
+}
+
+/// A [`Plugin`] that inserts a [`NonSendReadService<R>`] and answers its requests on the main thread.
+pub struct NonSendReadServicePlugin<R> {
+    _marker: PhantomData<fn() -> R>,
+}
+
+impl<R> Default for NonSendReadServicePlugin<R> {
+    fn default() -> Self {
+        NonSendReadServicePlugin {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<R: 'static> Plugin for NonSendReadServicePlugin<R> {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = mpsc::channel();
+
+        app.insert_resource(NonSendReadService::<R> { sender })
+            .insert_resource(ReadReceiver(SyncCell::new(receiver)))
+            .add_systems(Last, answer_read_requests::<R>);
+    }
+}
+
+#[derive(Resource)]
+struct ReadReceiver<R: 'static>(SyncCell<Receiver<BoxedRequest<R>>>);
+
+fn answer_read_requests<R: 'static>(
+    mut receiver: ResMut<ReadReceiver<R>>,
+    resource: Option<NonSend<R>>,
+) {
+    let Some(resource) = resource else {
+        return;
+    };
+
+    for request in receiver.0.get().try_iter() {
+        request.answer(&resource);
+    }
+}
@@ -0,0 +1,200 @@
+use std::{any::TypeId, collections::HashMap, panic::Location, sync::Arc};
+
+use bevy_app::{App, Plugin};
+use bevy_ecs::{
+    event::{Event, Events},
+    system::{Command, Resource},
+    world::World,
+};
+use bevy_utils::tracing::{info_span, warn};
+
+use crate::{main_thread, tracking, NonSendCommand, NonSendCommandKind};
+
+type RebuildFn = Arc<dyn Fn(&mut World) + Send + Sync>;
+
+#[derive(Clone)]
+struct Rebuilder {
+    type_name: &'static str,
+    remove: fn(&mut World),
+    rebuild: RebuildFn,
+}
+
+/// An [`Event`] sent after a non-[`Send`] resource was rebuilt, so that dependents can rebind to it.
+///
+/// See [`NonSendRebuildPlugin`].
+#[derive(Event, Clone, Debug)]
+pub struct NonSendRebuilt {
+    /// The [`TypeId`] of the rebuilt resource.
+    pub type_id: TypeId,
+
+    /// The type name of the rebuilt resource.
+    pub type_name: &'static str,
+}
+
+impl NonSendRebuilt {
+    /// Returns true if the rebuilt resource is `R`.
+    pub fn is<R: 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<R>()
+    }
+}
+
+/// A [`Resource`] containing the factories of all non-[`Send`] resources inserted with [`insert_non_send_rebuildable`].
+#[derive(Resource, Default)]
+pub struct NonSendRebuilders {
+    entries: HashMap<TypeId, Rebuilder>,
+}
+
+impl NonSendRebuilders {
+    /// Returns true if `R` can be rebuilt.
+    pub fn contains<R: 'static>(&self) -> bool {
+        self.entries.contains_key(&TypeId::of::<R>())
+    }
+}
+
+/// A [`Plugin`] that enables rebuilding non-[`Send`] resources while the app is running, for iterating on them without restarting.
+///
+/// Resources inserted with [`insert_non_send_rebuildable`] keep their factory. When they are rebuilt, the old value is dropped and the factory is run again on the main thread, after which a [`NonSendRebuilt`] event is sent.
+///
+/// Rebuilds are triggered by [`rebuild_non_send_resource`], [`rebuild_all_non_send_resources`], or a run condition passed to [`AppExt::rebuild_non_send_when`](crate::AppExt::rebuild_non_send_when), such as `input_just_pressed(KeyCode::F5)` or `on_event::<AssetEvent<Shader>>()`.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{
+/// #     insert_non_send_rebuildable, rebuild_non_send_resource, NonSendRebuildPlugin, NonSendRebuilt,
+/// # };
+/// # use std::sync::atomic::{AtomicU32, Ordering};
+/// #
+/// static BUILDS: AtomicU32 = AtomicU32::new(0);
+///
+/// struct ShaderCompiler {
+///     generation: u32,
+///     _handle: *const u8,
+/// }
+///
+/// fn create_compiler(mut commands: Commands) {
+///     commands.add(insert_non_send_rebuildable(|| ShaderCompiler {
+///         generation: BUILDS.fetch_add(1, Ordering::Relaxed),
+///         _handle: std::ptr::null(),
+///     }));
+/// }
+///
+/// fn reload_compiler(mut commands: Commands) {
+///     commands.add(rebuild_non_send_resource::<ShaderCompiler>());
+/// }
+///
+/// fn rebind(mut events: EventReader<NonSendRebuilt>, compiler: NonSend<ShaderCompiler>) {
+///     for event in events.read() {
+///         if event.is::<ShaderCompiler>() {
+///             assert_eq!(compiler.generation, 1);
+///         }
+///     }
+/// }
+///
+/// App::new()
+///     .add_plugins(NonSendRebuildPlugin)
+///     .add_systems(Startup, (create_compiler, reload_compiler, rebind).chain())
+///     .run();
+/// ```
+pub struct NonSendRebuildPlugin;
+
+impl Plugin for NonSendRebuildPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NonSendRebuilders>()
+            .add_event::<NonSendRebuilt>();
+    }
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource that can be rebuilt later by running `func` again.
+///
+/// This behaves like [`insert_non_send_resource`](crate::insert_non_send_resource), except that `func` may be called more than once. It is only kept if [`NonSendRebuildPlugin`] was added.
+#[track_caller]
+pub fn insert_non_send_rebuildable<F, R>(func: F) -> impl Command
+where
+    F: Fn() -> R + Send + Sync + 'static,
+    R: 'static,
+{
+    let location = Location::caller();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| {
+            let rebuild: RebuildFn = Arc::new(move |world| {
+                world.insert_non_send_resource(func());
+                tracking::track::<R>(world, location, None);
+            });
+
+            rebuild(world);
+
+            if let Some(mut rebuilders) = world.get_resource_mut::<NonSendRebuilders>() {
+                rebuilders.entries.insert(
+                    TypeId::of::<R>(),
+                    Rebuilder {
+                        type_name: std::any::type_name::<R>(),
+                        remove: |world| {
+                            world.remove_non_send_resource::<R>();
+                            tracking::untrack::<R>(world);
+                        },
+                        rebuild,
+                    },
+                );
+            }
+        },
+    )
+}
+
+/// Creates a [`Command`] that drops the non-[`Send`] resource `R` and runs its factory again.
+///
+/// Warns if `R` was not inserted with [`insert_non_send_rebuildable`]. See [`NonSendRebuildPlugin`].
+#[track_caller]
+pub fn rebuild_non_send_resource<R: 'static>() -> impl Command {
+    main_thread::deferrable(|world: &mut World| {
+        let rebuilder = world
+            .get_resource::<NonSendRebuilders>()
+            .and_then(|rebuilders| rebuilders.entries.get(&TypeId::of::<R>()))
+            .cloned();
+
+        match rebuilder {
+            Some(rebuilder) => rebuild(world, TypeId::of::<R>(), rebuilder),
+            None => warn!(
+                "Cannot rebuild `{}`, because it was not inserted with `insert_non_send_rebuildable`. Did you add `NonSendRebuildPlugin`?",
+                std::any::type_name::<R>(),
+            ),
+        }
+    })
+}
+
+/// Creates a [`Command`] that rebuilds every non-[`Send`] resource inserted with [`insert_non_send_rebuildable`].
+///
+/// See [`rebuild_non_send_resource`].
+#[track_caller]
+pub fn rebuild_all_non_send_resources() -> impl Command {
+    main_thread::deferrable(|world: &mut World| {
+        let Some(rebuilders) = world.get_resource::<NonSendRebuilders>() else {
+            return;
+        };
+
+        let rebuilders: Vec<_> = rebuilders
+            .entries
+            .iter()
+            .map(|(&type_id, rebuilder)| (type_id, rebuilder.clone()))
+            .collect();
+
+        for (type_id, rebuilder) in rebuilders {
+            rebuild(world, type_id, rebuilder);
+        }
+    })
+}
+
+/// Drops the old value before running the factory again, so both never exist at the same time.
+fn rebuild(world: &mut World, type_id: TypeId, rebuilder: Rebuilder) {
+    let type_name = rebuilder.type_name;
+    let _span = info_span!("rebuild_non_send_resource", type_name).entered();
+
+    (rebuilder.remove)(world);
+    (rebuilder.rebuild)(world);
+
+    if let Some(mut events) = world.get_resource_mut::<Events<NonSendRebuilt>>() {
+        events.send(NonSendRebuilt { type_id, type_name });
+    }
+}
@@ -0,0 +1,263 @@
+use std::{any::TypeId, fmt, panic::Location};
+
+use bevy_app::{App, First, Last, Plugin};
+use bevy_ecs::{
+    system::{ResMut, Resource},
+    world::{Mut, World},
+};
+use bevy_utils::tracing::warn;
+
+use crate::{command::ReplayFn, NonSendCommand, NonSendCommandKind};
+
+/// A [`NonSendCommand`] captured by a [`NonSendRecorder`].
+#[derive(Clone)]
+pub struct RecordedNonSendCommand {
+    /// The frame the command was applied in, counted by the [`NonSendRecordPlugin`].
+    pub frame: u32,
+
+    /// Which operation the command performed.
+    pub kind: NonSendCommandKind,
+
+    /// The name of the resource type.
+    pub type_name: &'static str,
+
+    /// The [`TypeId`] of the resource type.
+    pub type_id: TypeId,
+
+    /// Where the command was created.
+    pub location: &'static Location<'static>,
+
+    replay: Option<ReplayFn>,
+}
+
+impl RecordedNonSendCommand {
+    /// Returns true if this command can be re-applied by [`NonSendRecording::replay`].
+    ///
+    /// Commands created with [`NonSendCommand::insert`] consume their closure and cannot be replayed. Use [`NonSendCommand::insert_replayable`] or [`CommandsExt::insert_non_send_resource_replayable`](crate::CommandsExt::insert_non_send_resource_replayable) instead.
+    pub fn is_replayable(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Re-applies this command to `world`, returning false if it is not replayable.
+    pub fn replay(&self, world: &mut World) -> bool {
+        match &self.replay {
+            Some(replay) => {
+                replay(world);
+                true
+            }
+            None => {
+                warn!(
+                    "Cannot replay {:?} of `{}` queued at {}, because its closure was consumed. Use `NonSendCommand::insert_replayable` to record it.",
+                    self.kind, self.type_name, self.location,
+                );
+                false
+            }
+        }
+    }
+}
+
+impl fmt::Debug for RecordedNonSendCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecordedNonSendCommand")
+            .field("frame", &self.frame)
+            .field("kind", &self.kind)
+            .field("type_name", &self.type_name)
+            .field("location", &self.location)
+            .field("replayable", &self.is_replayable())
+            .finish()
+    }
+}
+
+/// An ordered sequence of [`NonSendCommand`]s captured by a [`NonSendRecorder`].
+#[derive(Clone, Default, Debug)]
+pub struct NonSendRecording {
+    commands: Vec<RecordedNonSendCommand>,
+}
+
+impl NonSendRecording {
+    /// Returns the recorded commands, in the order they were applied.
+    pub fn commands(&self) -> &[RecordedNonSendCommand] {
+        &self.commands
+    }
+
+    /// Returns the recorded commands that were applied in `frame`.
+    pub fn commands_in_frame(&self, frame: u32) -> impl Iterator<Item = &RecordedNonSendCommand> {
+        self.commands
+            .iter()
+            .filter(move |command| command.frame == frame)
+    }
+
+    /// Returns the last frame a command was recorded in, or [`None`] if the recording is empty.
+    pub fn last_frame(&self) -> Option<u32> {
+        self.commands.last().map(|command| command.frame)
+    }
+
+    /// Re-applies every recorded command to `world` in order, skipping the ones that are not replayable.
+    ///
+    /// This must be called on the main thread. Use [`NonSendReplayPlugin`] to re-apply each command in the same frame it was recorded in.
+    pub fn replay(&self, world: &mut World) {
+        for command in &self.commands {
+            command.replay(world);
+        }
+    }
+
+    /// Re-applies the commands recorded in `frame` to `world` in order, skipping the ones that are not replayable.
+    pub fn replay_frame(&self, frame: u32, world: &mut World) {
+        for command in self.commands_in_frame(frame) {
+            command.replay(world);
+        }
+    }
+}
+
+/// A [`Resource`] that records every [`NonSendCommand`] applied while it exists.
+///
+/// Commands are recorded after they are allowed by the [`NonSendCommandInspector`](crate::NonSendCommandInspector), and only [`NonSendCommand`]s are recorded, not the closures returned by [`insert_non_send_resource`](crate::insert_non_send_resource) and friends. It is usually added by the [`NonSendRecordPlugin`], which also counts frames.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{CommandsExt, NonSendRecordPlugin, NonSendRecorder, NonSendReplayPlugin};
+/// #
+/// struct Counter(u32);
+///
+/// fn create_counter(mut commands: Commands) {
+///     commands.insert_non_send_resource_replayable(|| Counter(42));
+/// }
+///
+/// let mut app = App::new();
+///
+/// app.add_plugins(NonSendRecordPlugin)
+///     .add_systems(Startup, create_counter);
+///
+/// app.update();
+///
+/// let recording = app.world.resource_mut::<NonSendRecorder>().take_recording();
+///
+/// // Re-apply the recorded commands into a fresh world.
+/// let mut replay = App::new();
+///
+/// replay.add_plugins(NonSendReplayPlugin::new(recording));
+/// replay.update();
+///
+/// assert_eq!(replay.world.non_send_resource::<Counter>().0, 42);
+/// ```
+#[derive(Resource, Default, Debug)]
+pub struct NonSendRecorder {
+    frame: u32,
+    paused: bool,
+    recording: NonSendRecording,
+}
+
+impl NonSendRecorder {
+    /// Returns the current frame number.
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// Returns true if recording is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pauses or resumes recording.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Returns everything recorded so far.
+    pub fn recording(&self) -> &NonSendRecording {
+        &self.recording
+    }
+
+    /// Takes everything recorded so far, leaving the recording empty.
+    pub fn take_recording(&mut self) -> NonSendRecording {
+        std::mem::take(&mut self.recording)
+    }
+
+    pub(crate) fn record(&mut self, command: &NonSendCommand) {
+        if self.paused {
+            return;
+        }
+
+        self.recording.commands.push(RecordedNonSendCommand {
+            frame: self.frame,
+            kind: command.kind(),
+            type_name: command.type_name(),
+            type_id: command.type_id(),
+            location: command.location(),
+            replay: command.apply_fn().replay.clone(),
+        });
+    }
+}
+
+/// A [`Plugin`] that adds a [`NonSendRecorder`] and advances its frame number at the end of every frame.
+///
+/// # Limitations
+///
+/// Most insertions cannot be replayed. Commands such as [`CommandsExt::insert_non_send_resource`](crate::CommandsExt::insert_non_send_resource) take an [`FnOnce`] factory that is consumed when the command is applied, so they are recorded without a way to apply them again, and a [`NonSendReplayPlugin`] skips them with a warning. The same goes for the other insert commands, such as the labeled, fallback, or builder ones.
+///
+/// Only [`init_non_send_resource`](crate::init_non_send_resource), [`remove_non_send_resource`](crate::remove_non_send_resource), and insertions made with [`CommandsExt::insert_non_send_resource_replayable`](crate::CommandsExt::insert_non_send_resource_replayable) or [`NonSendCommand::insert_replayable`] are replayed. Use [`RecordedNonSendCommand::is_replayable`] to check a recording.
+pub struct NonSendRecordPlugin;
+
+impl Plugin for NonSendRecordPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NonSendRecorder>()
+            .add_systems(Last, advance_recorder_frame);
+    }
+}
+
+fn advance_recorder_frame(mut recorder: ResMut<NonSendRecorder>) {
+    recorder.frame += 1;
+}
+
+/// A [`Plugin`] that re-applies a [`NonSendRecording`] frame by frame, in the same frames the commands were recorded in.
+///
+/// Commands are re-applied at the start of each frame, in [`First`]. Commands that are not replayable are skipped with a warning. See [`NonSendRecorder`].
+pub struct NonSendReplayPlugin {
+    recording: NonSendRecording,
+}
+
+impl NonSendReplayPlugin {
+    /// Creates a [`NonSendReplayPlugin`] that replays `recording`.
+    pub fn new(recording: NonSendRecording) -> Self {
+        NonSendReplayPlugin { recording }
+    }
+}
+
+impl Plugin for NonSendReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(NonSendReplayer {
+            frame: 0,
+            recording: self.recording.clone(),
+        })
+        .add_systems(First, replay_frame);
+    }
+}
+
+/// A [`Resource`] that tracks the progress of a [`NonSendReplayPlugin`].
+#[derive(Resource, Debug)]
+pub struct NonSendReplayer {
+    frame: u32,
+    recording: NonSendRecording,
+}
+
+impl NonSendReplayer {
+    /// Returns the next frame that will be replayed.
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// Returns true if every recorded frame has been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.recording
+            .last_frame()
+            .map_or(true, |last| self.frame > last)
+    }
+}
+
+fn replay_frame(world: &mut World) {
+    world.resource_scope(|world, mut replayer: Mut<NonSendReplayer>| {
+        let frame = replayer.frame;
+        replayer.recording.replay_frame(frame, world);
+        replayer.frame += 1;
+    });
+}
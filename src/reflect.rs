@@ -0,0 +1,174 @@
+use std::{any::TypeId, panic::Location, sync::Arc};
+
+use bevy_ecs::{reflect::AppTypeRegistry, system::Command, world::World};
+use bevy_reflect::{FromReflect, FromType, Reflect};
+
+use crate::{
+    error::{handle_error, NonSendCommandError, NonSendErrorContext},
+    main_thread,
+};
+
+type InsertFn = dyn Fn(&mut World, &dyn Reflect) -> Result<(), NonSendCommandError> + Send + Sync;
+
+/// Type data that allows a reflected value to be inserted as a non-[`Send`] resource.
+///
+/// Any type that implements [`FromReflect`] can register this with `#[reflect(NonSend)]`, in which case the reflected type itself is stored as the resource. Because [`Reflect`] requires [`Send`], truly non-[`Send`] resources should instead be built from a reflected descriptor using [`ReflectNonSend::from_descriptor`].
+#[derive(Clone)]
+pub struct ReflectNonSend {
+    insert: Arc<InsertFn>,
+}
+
+impl ReflectNonSend {
+    /// Creates type data for the descriptor `D`, which is converted into the non-[`Send`] resource `R` using `build`.
+    ///
+    /// This must be registered manually on `D`'s type registration.
+    ///
+    /// ```
+    /// # use std::any::TypeId;
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::ReflectNonSend;
+    /// #
+    /// struct MyNonSend(*const u8);
+    ///
+    /// #[derive(Reflect)]
+    /// struct MyNonSendDescriptor;
+    ///
+    /// let registry = AppTypeRegistry::default();
+    /// let mut registry = registry.write();
+    ///
+    /// registry.register::<MyNonSendDescriptor>();
+    /// registry
+    ///     .get_mut(TypeId::of::<MyNonSendDescriptor>())
+    ///     .unwrap()
+    ///     .insert(ReflectNonSend::from_descriptor(|_: MyNonSendDescriptor| {
+    ///         MyNonSend(std::ptr::null())
+    ///     }));
+    /// ```
+    pub fn from_descriptor<D, R>(build: fn(D) -> R) -> Self
+    where
+        D: FromReflect,
+        R: 'static,
+    {
+        ReflectNonSend {
+            insert: Arc::new(move |world, reflected| {
+                let descriptor = D::from_reflect(reflected).ok_or_else(|| {
+                    NonSendCommandError::FromReflectFailed {
+                        type_path: reflected.reflect_type_path().to_string(),
+                    }
+                })?;
+
+                world.insert_non_send_resource(build(descriptor));
+
+                Ok(())
+            }),
+        }
+    }
+
+    /// Converts `reflected` into the concrete resource and inserts it into the [`World`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `reflected` cannot be converted into the registered type.
+    pub fn insert(&self, world: &mut World, reflected: &dyn Reflect) {
+        if let Err(error) = self.try_insert(world, reflected) {
+            panic!("{error}");
+        }
+    }
+
+    /// Converts `reflected` into the concrete resource and inserts it into the [`World`], returning an error if the conversion fails.
+    pub fn try_insert(
+        &self,
+        world: &mut World,
+        reflected: &dyn Reflect,
+    ) -> Result<(), NonSendCommandError> {
+        (self.insert)(world, reflected)
+    }
+}
+
+impl<R: FromReflect> FromType<R> for ReflectNonSend {
+    fn from_type() -> Self {
+        ReflectNonSend::from_descriptor::<R, R>(|resource| resource)
+    }
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource in the [`World`] from a reflected value.
+///
+/// The type registered under `type_id` in the [`AppTypeRegistry`] must have [`ReflectNonSend`] type data. The concrete resource is constructed using [`FromReflect`] on the main thread.
+///
+/// # Panics
+///
+/// Panics if the [`AppTypeRegistry`] does not exist, if `type_id` is not registered, if it does not have [`ReflectNonSend`] type data, or if `value` cannot be converted into the registered type.
+///
+/// ```
+/// # use std::any::TypeId;
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{insert_non_send_reflect, ReflectNonSend};
+/// #
+/// #[derive(Reflect, Default)]
+/// #[reflect(NonSend)]
+/// struct MyNonSend(u8);
+///
+/// fn create_my_non_send(mut commands: Commands) {
+///     commands.add(
+///         insert_non_send_reflect(TypeId::of::<MyNonSend>(), Box::new(MyNonSend(5)))
+///     );
+/// }
+/// #
+/// # App::new()
+/// #     .register_type::<MyNonSend>()
+/// #     .add_systems(Startup, (create_my_non_send, check).chain())
+/// #     .run();
+/// #
+/// # fn check(my_non_send: NonSend<MyNonSend>) {
+/// #     assert_eq!(my_non_send.0, 5);
+/// # }
+/// ```
+#[track_caller]
+pub fn insert_non_send_reflect(type_id: TypeId, value: Box<dyn Reflect>) -> impl Command {
+    main_thread::deferrable(move |world: &mut World| {
+        assert!(
+            world.contains_resource::<AppTypeRegistry>(),
+            "`AppTypeRegistry` must exist to insert reflected non-send resources."
+        );
+
+        if let Err(error) = insert_reflect(world, type_id, &*value) {
+            panic!("{error}");
+        }
+    })
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource in the [`World`] from a reflected value, passing any failure to the [`NonSendErrorHandler`](crate::NonSendErrorHandler).
+///
+/// See [`insert_non_send_reflect`] for more details.
+#[track_caller]
+pub fn try_insert_non_send_reflect(type_id: TypeId, value: Box<dyn Reflect>) -> impl Command {
+    let context = NonSendErrorContext {
+        command: "try_insert_non_send_reflect",
+        location: Location::caller(),
+    };
+
+    main_thread::deferrable(move |world: &mut World| {
+        let result = insert_reflect(world, type_id, &*value);
+        handle_error(world, result, context);
+    })
+}
+
+fn insert_reflect(
+    world: &mut World,
+    type_id: TypeId,
+    value: &dyn Reflect,
+) -> Result<(), NonSendCommandError> {
+    let not_registered = || NonSendCommandError::NotRegistered {
+        type_path: value.reflect_type_path().to_string(),
+    };
+
+    let reflect_non_send = world
+        .get_resource::<AppTypeRegistry>()
+        .ok_or_else(not_registered)?
+        .read()
+        .get_type_data::<ReflectNonSend>(type_id)
+        .ok_or_else(not_registered)?
+        .clone();
+
+    reflect_non_send.try_insert(world, value)
+}
@@ -0,0 +1,257 @@
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    panic::{self, AssertUnwindSafe, Location},
+};
+
+use bevy_app::{App, Last, Plugin};
+use bevy_ecs::{
+    schedule::ScheduleLabel,
+    system::{Command, Resource},
+    world::World,
+};
+
+use crate::{tracking, NonSendCommand, NonSendCommandKind};
+
+/// Creates a [`Command`] that inserts a non-[`Send`] resource, runs `scope`, and then removes and drops the resource.
+///
+/// The resource never outlives `scope`, even if `scope` panics. It is tracked while `scope` runs, and removed like [`remove_non_send_resource`](crate::remove_non_send_resource) afterwards. If a resource of the same type already existed, it is restored afterwards along with its tracking information and removal hooks. Both closures are executed on the main thread.
+///
+/// See [`with_temporary_non_send_resource_in`] to run a schedule instead of a closure.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::with_temporary_non_send_resource;
+/// #
+/// struct Clipboard(*const u8, String);
+///
+/// #[derive(Resource)]
+/// struct Pasted(String);
+///
+/// fn paste(mut commands: Commands) {
+///     commands.add(
+///         with_temporary_non_send_resource(
+///             || Clipboard(std::ptr::null(), "Hello!".to_string()),
+///             |world: &mut World| {
+///                 let text = world.non_send_resource::<Clipboard>().1.clone();
+///                 world.insert_resource(Pasted(text));
+///             },
+///         )
+///     );
+/// }
+/// #
+/// # App::new()
+/// #     .add_systems(Startup, (paste, check).chain())
+/// #     .run();
+/// #
+/// # fn check(clipboard: Option<NonSend<Clipboard>>, pasted: Res<Pasted>) {
+/// #     assert!(clipboard.is_none());
+/// #     assert_eq!(pasted.0, "Hello!");
+/// # }
+/// ```
+#[track_caller]
+pub fn with_temporary_non_send_resource<F, S, R>(func: F, scope: S) -> impl Command
+where
+    F: FnOnce() -> R + Send + 'static,
+    S: FnOnce(&mut World) + Send + 'static,
+    R: 'static,
+{
+    let location = Location::caller();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| {
+            let previous = tracking::take::<R>(world);
+
+            world.insert_non_send_resource(func());
+            tracking::track::<R>(world, location, None);
+
+            let result = panic::catch_unwind(AssertUnwindSafe(|| scope(&mut *world)));
+
+            tracking::remove::<R>(world);
+
+            if let Some(previous) = previous {
+                tracking::restore(world, previous);
+            }
+
+            if let Err(payload) = result {
+                panic::resume_unwind(payload);
+            }
+        },
+    )
+}
+
+/// Creates a [`Command`] that inserts a non-[`Send`] resource, runs the schedule `label`, and then removes and drops the resource.
+///
+/// See [`with_temporary_non_send_resource`] for more details.
+///
+/// # Panics
+///
+/// Panics if the schedule does not exist.
+#[track_caller]
+pub fn with_temporary_non_send_resource_in<F, R>(func: F, label: impl ScheduleLabel) -> impl Command
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: 'static,
+{
+    with_temporary_non_send_resource(func, move |world: &mut World| {
+        world.run_schedule(label);
+    })
+}
+
+/// A [`Plugin`] that removes non-[`Send`] resources inserted with [`insert_non_send_resource_for_frame`] at the end of every frame.
+///
+/// Resources are removed in [`Last`]. Resources inserted by commands applied after that point are removed at the end of the next frame instead.
+pub struct NonSendFramePlugin;
+
+impl Plugin for NonSendFramePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FrameScopedNonSend>()
+            .add_systems(Last, remove_frame_scoped);
+    }
+}
+
+/// Removal functions for the non-[`Send`] resources inserted for the current frame.
+#[derive(Resource, Default)]
+struct FrameScopedNonSend {
+    removals: HashMap<TypeId, fn(&mut World)>,
+}
+
+fn remove_frame_scoped(world: &mut World) {
+    let removals = std::mem::take(&mut world.resource_mut::<FrameScopedNonSend>().removals);
+
+    for remove in removals.into_values() {
+        remove(world);
+    }
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource that is automatically removed at the end of the frame.
+///
+/// This is meant for per-frame platform data, such as monitor layouts or raw input snapshots, that should never leak into the next frame. Inserting the resource again in the same frame replaces it, and it is still removed only once.
+///
+/// # Panics
+///
+/// Panics if [`NonSendFramePlugin`] was not added.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{insert_non_send_resource_for_frame, NonSendFramePlugin};
+/// #
+/// struct MonitorLayout(*const u8);
+///
+/// fn query_monitors(mut commands: Commands) {
+///     commands.add(
+///         insert_non_send_resource_for_frame(|| {
+///             MonitorLayout(std::ptr::null())
+///         })
+///     );
+/// }
+///
+/// fn use_monitors(layout: NonSend<MonitorLayout>) {
+///     assert!(layout.0.is_null());
+/// }
+///
+/// let mut app = App::new();
+///
+/// app.add_plugins(NonSendFramePlugin)
+///     .add_systems(PreUpdate, query_monitors)
+///     .add_systems(Update, use_monitors);
+///
+/// app.update();
+///
+/// assert!(!app.world.contains_non_send::<MonitorLayout>());
+/// ```
+#[track_caller]
+pub fn insert_non_send_resource_for_frame<F, R>(func: F) -> impl Command
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: 'static,
+{
+    let location = Location::caller();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| {
+            assert!(
+                world.contains_resource::<FrameScopedNonSend>(),
+                "Frame-scoped non-send resources require `NonSendFramePlugin`."
+            );
+
+            world.insert_non_send_resource(func());
+            tracking::track::<R>(world, location, None);
+
+            world
+                .resource_mut::<FrameScopedNonSend>()
+                .removals
+                .entry(TypeId::of::<R>())
+                .or_insert(|world| {
+                    tracking::remove::<R>(world);
+                });
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NonSendTracker, NonSendTrackingPlugin};
+
+    struct Clipboard(&'static str);
+
+    #[test]
+    fn temporary_resource_is_tracked_and_previous_is_restored() {
+        let mut app = App::new();
+        app.add_plugins(NonSendTrackingPlugin);
+
+        crate::insert_non_send_resource(|| Clipboard("previous")).apply(&mut app.world);
+        let previous = app
+            .world
+            .resource::<NonSendTracker>()
+            .get(TypeId::of::<Clipboard>())
+            .unwrap()
+            .location();
+
+        let command = with_temporary_non_send_resource(
+            || Clipboard("temporary"),
+            move |world: &mut World| {
+                let tracked = world
+                    .resource::<NonSendTracker>()
+                    .get(TypeId::of::<Clipboard>())
+                    .unwrap()
+                    .location();
+
+                assert_eq!(world.non_send_resource::<Clipboard>().0, "temporary");
+                assert_ne!(tracked, previous);
+            },
+        );
+        command.apply(&mut app.world);
+
+        let tracked = app
+            .world
+            .resource::<NonSendTracker>()
+            .get(TypeId::of::<Clipboard>())
+            .unwrap()
+            .location();
+
+        assert_eq!(app.world.non_send_resource::<Clipboard>().0, "previous");
+        assert_eq!(tracked, previous);
+    }
+
+    #[test]
+    fn temporary_resource_is_untracked_afterwards() {
+        let mut app = App::new();
+        app.add_plugins(NonSendTrackingPlugin);
+
+        with_temporary_non_send_resource(|| Clipboard("temporary"), |_: &mut World| {})
+            .apply(&mut app.world);
+
+        assert!(!app.world.contains_non_send::<Clipboard>());
+        assert!(app
+            .world
+            .resource::<NonSendTracker>()
+            .get(TypeId::of::<Clipboard>())
+            .is_none());
+    }
+}
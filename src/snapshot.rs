@@ -0,0 +1,199 @@
+use std::{
+    any::{Any, TypeId},
+    panic::Location,
+};
+
+use bevy_ecs::{system::Resource, world::World};
+
+use crate::tracking;
+
+type CaptureFn = dyn Fn(&World) -> Option<Box<dyn Any>> + Send + Sync;
+type RestoreFn = dyn Fn(&mut World, &dyn Any, &'static Location<'static>) + Send + Sync;
+type RemoveFn = fn(&mut World);
+
+/// A [`Resource`] containing the functions used to snapshot and restore non-[`Send`] resources.
+///
+/// Types are registered with [`AppExt::register_non_send_snapshot`](crate::AppExt::register_non_send_snapshot) or [`AppExt::register_non_send_snapshot_with`](crate::AppExt::register_non_send_snapshot_with), and are captured with [`snapshot_non_send`] and rolled back with [`restore_non_send`].
+#[derive(Resource, Default)]
+pub struct NonSendSnapshots {
+    entries: Vec<SnapshotEntry>,
+}
+
+struct SnapshotEntry {
+    type_id: TypeId,
+    capture: Box<CaptureFn>,
+    restore: Box<RestoreFn>,
+    remove: RemoveFn,
+}
+
+impl NonSendSnapshots {
+    /// Registers `R` to be snapshotted by cloning it.
+    pub fn register_clone<R: Clone + 'static>(&mut self) {
+        self.register_entry::<R>(
+            Box::new(|world| {
+                let resource = world.get_non_send_resource::<R>()?;
+
+                Some(Box::new(resource.clone()))
+            }),
+            Box::new(|world, captured, location| {
+                // The type may have been registered again differently since the snapshot was taken.
+                let Some(captured) = captured.downcast_ref::<R>() else {
+                    return;
+                };
+
+                world.insert_non_send_resource(captured.clone());
+                tracking::track::<R>(world, location, None);
+            }),
+        );
+    }
+
+    /// Registers `R` to be snapshotted by serializing it.
+    ///
+    /// This is meant for types that cannot be cloned, such as wrappers around native handles. `serialize_with` captures the state of the resource, and `deserialize_with` recreates a resource from it.
+    pub fn register_serialized<R, S, D>(&mut self, serialize_with: S, deserialize_with: D)
+    where
+        R: 'static,
+        S: Fn(&R) -> Vec<u8> + Send + Sync + 'static,
+        D: Fn(&[u8]) -> R + Send + Sync + 'static,
+    {
+        self.register_entry::<R>(
+            Box::new(move |world| {
+                let resource = world.get_non_send_resource::<R>()?;
+
+                Some(Box::new(serialize_with(resource)))
+            }),
+            Box::new(move |world, captured, location| {
+                let Some(captured) = captured.downcast_ref::<Vec<u8>>() else {
+                    return;
+                };
+
+                world.insert_non_send_resource(deserialize_with(captured));
+                tracking::track::<R>(world, location, None);
+            }),
+        );
+    }
+
+    fn register_entry<R: 'static>(&mut self, capture: Box<CaptureFn>, restore: Box<RestoreFn>) {
+        let type_id = TypeId::of::<R>();
+
+        self.entries.retain(|entry| entry.type_id != type_id);
+        self.entries.push(SnapshotEntry {
+            type_id,
+            capture,
+            restore,
+            remove: remove_and_untrack::<R>,
+        });
+    }
+}
+
+/// The captured state of the registered non-[`Send`] resources, created by [`snapshot_non_send`].
+///
+/// A snapshot holds copies of non-[`Send`] values, so it is not [`Send`] either. It is usually kept on the main thread, such as in an undo stack stored as a non-[`Send`] resource.
+pub struct NonSendSnapshot {
+    entries: Vec<(TypeId, Option<Box<dyn Any>>)>,
+}
+
+impl NonSendSnapshot {
+    /// Returns the number of registered resources that existed when the snapshot was taken.
+    pub fn len(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|(_, captured)| captured.is_some())
+            .count()
+    }
+
+    /// Returns true if none of the registered resources existed when the snapshot was taken.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Captures the current state of all non-[`Send`] resources registered in [`NonSendSnapshots`].
+///
+/// Registered resources that do not exist are recorded as missing, so that restoring the snapshot removes them.
+///
+/// # Panics
+///
+/// Panics if called on a thread other than the main thread, and a registered resource exists.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{restore_non_send, snapshot_non_send, AppExt};
+/// #
+/// struct Document {
+///     text: String,
+///     _handle: *const u8,
+/// }
+///
+/// let mut app = App::new();
+///
+/// app.register_non_send_snapshot_with(
+///     |document: &Document| document.text.clone().into_bytes(),
+///     |bytes| Document {
+///         text: String::from_utf8(bytes.to_vec()).unwrap(),
+///         _handle: std::ptr::null(),
+///     },
+/// )
+/// .insert_non_send_resource(Document { text: "Hello".to_string(), _handle: std::ptr::null() });
+///
+/// let snapshot = snapshot_non_send(&app.world);
+///
+/// app.world.non_send_resource_mut::<Document>().text.push_str(", world!");
+///
+/// // Undo the edit.
+/// restore_non_send(&mut app.world, &snapshot);
+///
+/// assert_eq!(app.world.non_send_resource::<Document>().text, "Hello");
+/// ```
+pub fn snapshot_non_send(world: &World) -> NonSendSnapshot {
+    let entries = world
+        .get_resource::<NonSendSnapshots>()
+        .map(|snapshots| {
+            snapshots
+                .entries
+                .iter()
+                .map(|entry| (entry.type_id, (entry.capture)(world)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    NonSendSnapshot { entries }
+}
+
+/// Rolls the registered non-[`Send`] resources back to the state captured in `snapshot`.
+///
+/// Resources that existed when the snapshot was taken are replaced with a restored copy, and those that did not are removed. Resources registered after the snapshot was taken are left untouched. The same snapshot may be restored several times.
+///
+/// # Panics
+///
+/// Panics if called on a thread other than the main thread.
+#[track_caller]
+pub fn restore_non_send(world: &mut World, snapshot: &NonSendSnapshot) {
+    let location = Location::caller();
+
+    // Temporarily take the registry out of the world so `restore` functions can access it mutably.
+    let Some(snapshots) = world.remove_resource::<NonSendSnapshots>() else {
+        return;
+    };
+
+    for (type_id, captured) in &snapshot.entries {
+        let Some(entry) = snapshots
+            .entries
+            .iter()
+            .find(|entry| entry.type_id == *type_id)
+        else {
+            continue;
+        };
+
+        match captured {
+            Some(captured) => (entry.restore)(world, captured.as_ref(), location),
+            None => (entry.remove)(world),
+        }
+    }
+
+    world.insert_resource(snapshots);
+}
+
+fn remove_and_untrack<R: 'static>(world: &mut World) {
+    tracking::remove::<R>(world);
+}
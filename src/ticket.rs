@@ -0,0 +1,401 @@
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    panic::{self, AssertUnwindSafe, Location},
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+};
+
+use bevy_ecs::{system::Command, world::World};
+
+thread_local! {
+    /// Set when a command applied inside a [`TicketedCommand`] reports an error.
+    static FAILED: Cell<bool> = const { Cell::new(false) };
+
+    /// The ticket of the [`TicketedCommand`] currently being applied, taken when the command is deferred.
+    static CURRENT: RefCell<Option<CommandTicket>> = const { RefCell::new(None) };
+}
+
+/// Records that the command currently being applied failed, so its ticket reports [`TicketStatus::Failed`].
+pub(crate) fn report_failure() {
+    FAILED.with(|failed| failed.set(true));
+}
+
+/// Takes the ticket of the command currently being applied, so that it is completed later instead of when the command returns.
+///
+/// This is used when a command is deferred to the [`MainThreadQueue`](crate::MainThreadQueue), which then carries the ticket along with the command.
+pub(crate) fn take_current_ticket() -> Option<CommandTicket> {
+    CURRENT.with(|current| current.borrow_mut().take())
+}
+
+/// The status of a command tracked by a [`CommandTicket`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum TicketStatus {
+    /// The command has not been applied yet.
+    Pending,
+
+    /// The command was applied successfully.
+    Succeeded,
+
+    /// The command was applied, but it panicked or reported a [`NonSendCommandError`](crate::NonSendCommandError).
+    Failed,
+
+    /// The command was dropped without being applied, such as when its [`World`] was dropped first.
+    Dropped,
+
+    /// The command was cancelled with [`CommandTicket::cancel`] before it was applied.
+    Cancelled,
+}
+
+impl TicketStatus {
+    /// Returns true if the status is no longer [`TicketStatus::Pending`].
+    pub fn is_done(self) -> bool {
+        self != TicketStatus::Pending
+    }
+}
+
+struct TicketState {
+    inner: Mutex<TicketInner>,
+    condvar: Condvar,
+    location: &'static Location<'static>,
+}
+
+struct TicketInner {
+    status: TicketStatus,
+    // Set once the command starts being applied, after which it can no longer be cancelled.
+    started: bool,
+    wakers: Vec<Waker>,
+}
+
+/// A cheap, [`Send`] token used to learn when a queued command was applied, and whether it succeeded.
+///
+/// Tickets are created with [`with_ticket`] or [`CommandsExt::add_with_ticket`](crate::CommandsExt::add_with_ticket). They can be cloned, polled with [`status`](Self::status), waited on with [`wait`](Self::wait), or awaited, which resolves to the final [`TicketStatus`].
+///
+/// A command that has not been applied yet can be cancelled with [`cancel`](Self::cancel), so that it never runs.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{insert_non_send_resource, CommandTicket, CommandsExt, TicketStatus};
+/// #
+/// struct GlContext(*const u8);
+///
+/// #[derive(Resource)]
+/// struct ContextTicket(CommandTicket);
+///
+/// fn create_context(mut commands: Commands) {
+///     let ticket = commands.add_with_ticket(insert_non_send_resource(|| {
+///         GlContext(std::ptr::null())
+///     }));
+///
+///     // The ticket can be sent to a background thread, or polled later.
+///     commands.insert_resource(ContextTicket(ticket));
+/// }
+///
+/// fn check_context(ticket: Res<ContextTicket>) {
+///     assert_eq!(ticket.0.status(), TicketStatus::Succeeded);
+/// }
+/// #
+/// # App::new()
+/// #     .add_systems(Startup, (create_context, check_context).chain())
+/// #     .run();
+/// ```
+#[derive(Clone)]
+pub struct CommandTicket {
+    state: Arc<TicketState>,
+}
+
+impl CommandTicket {
+    fn new(location: &'static Location<'static>) -> Self {
+        CommandTicket {
+            state: Arc::new(TicketState {
+                inner: Mutex::new(TicketInner {
+                    status: TicketStatus::Pending,
+                    started: false,
+                    wakers: Vec::new(),
+                }),
+                condvar: Condvar::new(),
+                location,
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TicketInner> {
+        self.state
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns the current status of the command.
+    pub fn status(&self) -> TicketStatus {
+        self.lock().status
+    }
+
+    /// Returns true if the command is no longer pending.
+    pub fn is_done(&self) -> bool {
+        self.status().is_done()
+    }
+
+    /// Returns the location of the code that created this ticket.
+    ///
+    /// This helps finding the command behind a ticket that stays pending.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.state.location
+    }
+
+    /// Blocks the current thread until the command is no longer pending, returning its final status.
+    ///
+    /// This must not be called from the main thread while the command is pending, as that would prevent it from ever being applied.
+    pub fn wait(&self) -> TicketStatus {
+        let mut inner = self.lock();
+
+        while inner.status == TicketStatus::Pending {
+            inner = self
+                .state
+                .condvar
+                .wait(inner)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+
+        inner.status
+    }
+
+    /// Cancels the command, so that it does nothing when it is applied.
+    ///
+    /// Returns true if the command was cancelled, or false if it had already started being applied or was otherwise done.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_command_non_send::{insert_non_send_resource, CommandsExt, TicketStatus};
+    /// #
+    /// struct GlContext(*const u8);
+    ///
+    /// fn create_context_and_close(mut commands: Commands) {
+    ///     let ticket = commands.add_with_ticket(insert_non_send_resource(|| {
+    ///         GlContext(std::ptr::null())
+    ///     }));
+    ///
+    ///     // The window was closed before the context was created.
+    ///     assert!(ticket.cancel());
+    ///     assert_eq!(ticket.status(), TicketStatus::Cancelled);
+    /// }
+    /// #
+    /// # App::new()
+    /// #     .add_systems(Startup, (create_context_and_close, check).chain())
+    /// #     .run();
+    /// #
+    /// # fn check(context: Option<NonSend<GlContext>>) {
+    /// #     assert!(context.is_none());
+    /// # }
+    /// ```
+    pub fn cancel(&self) -> bool {
+        self.finish(TicketStatus::Cancelled, |inner| !inner.started)
+    }
+
+    /// Marks the command as started, returning false if it was cancelled.
+    fn start(&self) -> bool {
+        let mut inner = self.lock();
+
+        if inner.status == TicketStatus::Cancelled {
+            return false;
+        }
+
+        inner.started = true;
+        true
+    }
+
+    /// Sets the final status of the command, if it is still pending.
+    fn complete(&self, status: TicketStatus) {
+        self.finish(status, |_| true);
+    }
+
+    /// Sets the final status of the command and wakes up everyone waiting on it, if it is still pending and `allowed` returns true.
+    fn finish(&self, status: TicketStatus, allowed: impl FnOnce(&TicketInner) -> bool) -> bool {
+        let wakers = {
+            let mut inner = self.lock();
+
+            if inner.status.is_done() || !allowed(&inner) {
+                return false;
+            }
+
+            inner.status = status;
+            std::mem::take(&mut inner.wakers)
+        };
+
+        self.state.condvar.notify_all();
+
+        for waker in wakers {
+            waker.wake();
+        }
+
+        true
+    }
+}
+
+impl Future for CommandTicket {
+    type Output = TicketStatus;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut inner = self.lock();
+
+        if inner.status.is_done() {
+            return Poll::Ready(inner.status);
+        }
+
+        if !inner.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            inner.wakers.push(cx.waker().clone());
+        }
+
+        Poll::Pending
+    }
+}
+
+/// A [`Command`] that completes a [`CommandTicket`] once it is applied. See [`with_ticket`].
+pub struct TicketedCommand<C> {
+    command: Option<C>,
+    ticket: CommandTicket,
+}
+
+impl<C> TicketedCommand<C> {
+    /// Wraps `command` so that it completes an existing `ticket`.
+    pub(crate) fn new(command: C, ticket: CommandTicket) -> Self {
+        TicketedCommand {
+            command: Some(command),
+            ticket,
+        }
+    }
+}
+
+impl<C: Command> Command for TicketedCommand<C> {
+    fn apply(mut self, world: &mut World) {
+        let Some(command) = self.command.take() else {
+            return;
+        };
+
+        if !self.ticket.start() {
+            return;
+        }
+
+        let previous = FAILED.with(|failed| failed.replace(false));
+        let previous_ticket = CURRENT.with(|current| current.replace(Some(self.ticket.clone())));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| command.apply(world)));
+        let failed = FAILED.with(|failed| failed.replace(previous));
+        // The ticket was taken if the command was deferred, and is completed once it is applied again.
+        let deferred = CURRENT
+            .with(|current| current.replace(previous_ticket))
+            .is_none();
+
+        match result {
+            Ok(()) if deferred => {}
+            Ok(()) if !failed => self.ticket.complete(TicketStatus::Succeeded),
+            Ok(()) => self.ticket.complete(TicketStatus::Failed),
+            Err(payload) => {
+                self.ticket.complete(TicketStatus::Failed);
+                panic::resume_unwind(payload);
+            }
+        }
+    }
+}
+
+impl<C> Drop for TicketedCommand<C> {
+    fn drop(&mut self) {
+        if self.command.is_some() {
+            self.ticket.complete(TicketStatus::Dropped);
+        }
+    }
+}
+
+/// Wraps `command` so that it completes a [`CommandTicket`] once it is applied.
+///
+/// The command fails if it panics, or if it reports a [`NonSendCommandError`](crate::NonSendCommandError) to the [`NonSendErrorHandler`](crate::NonSendErrorHandler). If the command is deferred to the [`MainThreadQueue`](crate::MainThreadQueue), the ticket stays pending until the command is applied on the main thread.
+#[track_caller]
+pub fn with_ticket<C: Command>(command: C) -> (TicketedCommand<C>, CommandTicket) {
+    let ticket = CommandTicket::new(Location::caller());
+
+    (TicketedCommand::new(command, ticket.clone()), ticket)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::system::Resource;
+
+    use super::*;
+
+    struct GlContext;
+
+    #[derive(Resource)]
+    struct Ticket(CommandTicket);
+
+    fn insert_context() -> (TicketedCommand<impl Command>, CommandTicket) {
+        with_ticket(crate::insert_non_send_resource(|| GlContext))
+    }
+
+    #[test]
+    fn cancelled_command_does_nothing() {
+        let mut world = World::new();
+        let (command, ticket) = insert_context();
+
+        assert!(ticket.cancel());
+        command.apply(&mut world);
+
+        assert_eq!(ticket.status(), TicketStatus::Cancelled);
+        assert_eq!(ticket.wait(), TicketStatus::Cancelled);
+        assert!(!world.contains_non_send::<GlContext>());
+    }
+
+    #[test]
+    fn applied_command_cannot_be_cancelled() {
+        let mut world = World::new();
+        let (command, ticket) = insert_context();
+
+        command.apply(&mut world);
+
+        assert!(!ticket.cancel());
+        assert_eq!(ticket.status(), TicketStatus::Succeeded);
+    }
+
+    #[test]
+    fn started_command_cannot_be_cancelled() {
+        let mut world = World::new();
+        let (command, ticket) = with_ticket(|world: &mut World| {
+            let ticket = world.remove_resource::<Ticket>().unwrap().0;
+            assert!(!ticket.cancel());
+        });
+
+        world.insert_resource(Ticket(ticket.clone()));
+        command.apply(&mut world);
+
+        assert_eq!(ticket.status(), TicketStatus::Succeeded);
+    }
+
+    #[test]
+    fn cancelling_twice_fails() {
+        let (_command, ticket) = insert_context();
+
+        assert!(ticket.cancel());
+        assert!(!ticket.cancel());
+    }
+
+    #[test]
+    fn dropped_command_is_reported() {
+        let (command, ticket) = insert_context();
+
+        drop(command);
+
+        assert_eq!(ticket.status(), TicketStatus::Dropped);
+        assert!(!ticket.cancel());
+    }
+
+    #[test]
+    fn panicking_command_fails() {
+        let mut world = World::new();
+        let (command, ticket) = with_ticket(|_: &mut World| panic!("failed to create the context"));
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| command.apply(&mut world)));
+
+        assert!(result.is_err());
+        assert_eq!(ticket.status(), TicketStatus::Failed);
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    panic::Location,
+    time::{Duration, Instant},
+};
+
+use bevy_app::{App, Plugin};
+use bevy_ecs::{
+    component::Tick,
+    system::Resource,
+    world::{FromWorld, World},
+};
+
+type ReinitFn = fn(&mut World, &'static Location<'static>);
+
+/// A [`Resource`] that records every non-[`Send`] resource inserted through this crate's commands.
+///
+/// Tracking is opt-in: commands only record resources when this resource exists in the [`World`]. The easiest way to enable it is by adding [`NonSendTrackingPlugin`].
+///
+/// This is meant for debugging and tooling, such as listing which non-[`Send`] resources exist, where they were inserted, and removing or re-initializing them while the app is running. With the `brp` feature, it can also be accessed remotely through `NonSendBrpPlugin`.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{CommandsExt, NonSendTracker, NonSendTrackingPlugin};
+/// #
+/// struct MyNonSend(*const u8);
+///
+/// fn create_my_non_send(mut commands: Commands) {
+///     commands.insert_non_send_resource(|| {
+///         MyNonSend(std::ptr::null())
+///     });
+/// }
+///
+/// fn list_non_send(tracker: Res<NonSendTracker>) {
+///     for tracked in tracker.iter() {
+///         println!("{} was inserted at {}", tracked.type_name(), tracked.location());
+///     }
+/// }
+/// #
+/// # App::new()
+/// #     .add_plugins(NonSendTrackingPlugin)
+/// #     .add_systems(Startup, (create_my_non_send, list_non_send, check).chain())
+/// #     .run();
+/// #
+/// # fn check(tracker: Res<NonSendTracker>) {
+/// #     assert_eq!(tracker.iter().count(), 1);
+/// # }
+/// ```
+#[derive(Resource, Default)]
+pub struct NonSendTracker {
+    entries: HashMap<TypeId, TrackedNonSend>,
+}
+
+impl NonSendTracker {
+    /// Returns the tracking information for the non-[`Send`] resource with the given [`TypeId`], if it is tracked.
+    pub fn get(&self, type_id: TypeId) -> Option<&TrackedNonSend> {
+        self.entries.get(&type_id)
+    }
+
+    /// Returns the tracking information for the non-[`Send`] resource with the given type name, if it is tracked.
+    pub fn get_by_name(&self, type_name: &str) -> Option<&TrackedNonSend> {
+        self.entries
+            .values()
+            .find(|tracked| tracked.type_name == type_name)
+    }
+
+    /// Iterates over all tracked non-[`Send`] resources.
+    pub fn iter(&self) -> impl Iterator<Item = &TrackedNonSend> {
+        self.entries.values()
+    }
+}
+
+/// Information about a non-[`Send`] resource inserted through this crate's commands.
+///
+/// See [`NonSendTracker`].
+#[derive(Clone, Debug)]
+pub struct TrackedNonSend {
+    type_id: TypeId,
+    type_name: &'static str,
+    location: &'static Location<'static>,
+    inserted_at: Instant,
+    inserted_tick: Tick,
+    remove: fn(&mut World),
+    reinit: Option<ReinitFn>,
+}
+
+impl TrackedNonSend {
+    /// Returns the [`TypeId`] of the resource.
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Returns the type name of the resource.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the location of the code that queued the command which inserted the resource.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// Returns when the resource was inserted.
+    pub fn inserted_at(&self) -> Instant {
+        self.inserted_at
+    }
+
+    /// Returns the change tick of the [`World`] when the resource was inserted.
+    pub fn inserted_tick(&self) -> Tick {
+        self.inserted_tick
+    }
+
+    /// Returns how long ago the resource was inserted.
+    pub fn age(&self) -> Duration {
+        self.inserted_at.elapsed()
+    }
+
+    /// Returns true if the resource can be re-initialized with [`reinit_tracked_non_send`].
+    ///
+    /// This is only the case for resources that were inserted with [`init_non_send_resource`](crate::init_non_send_resource).
+    pub fn can_reinit(&self) -> bool {
+        self.reinit.is_some()
+    }
+}
+
+/// Removes the tracked non-[`Send`] resource with the given [`TypeId`] from the [`World`].
+///
+/// Returns false if the resource is not tracked.
+pub fn remove_tracked_non_send(world: &mut World, type_id: TypeId) -> bool {
+    let Some(remove) = world
+        .get_resource::<NonSendTracker>()
+        .and_then(|tracker| tracker.get(type_id))
+        .map(|tracked| tracked.remove)
+    else {
+        return false;
+    };
+
+    remove(world);
+
+    true
+}
+
+/// Removes and re-initializes the tracked non-[`Send`] resource with the given [`TypeId`] using [`FromWorld`].
+///
+/// Returns false if the resource is not tracked or was not inserted with [`init_non_send_resource`](crate::init_non_send_resource).
+pub fn reinit_tracked_non_send(world: &mut World, type_id: TypeId) -> bool {
+    let Some((remove, Some(reinit), location)) = world
+        .get_resource::<NonSendTracker>()
+        .and_then(|tracker| tracker.get(type_id))
+        .map(|tracked| (tracked.remove, tracked.reinit, tracked.location))
+    else {
+        return false;
+    };
+
+    remove(world);
+    reinit(world, location);
+
+    true
+}
+
+/// A [`Plugin`] that enables tracking non-[`Send`] resources inserted through this crate's commands.
+///
+/// See [`NonSendTracker`].
+pub struct NonSendTrackingPlugin;
+
+impl Plugin for NonSendTrackingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NonSendTracker>();
+    }
+}
+
+/// Records that `R` was inserted, if tracking is enabled.
+pub(crate) fn track<R: 'static>(
+    world: &mut World,
+    location: &'static Location<'static>,
+    reinit: Option<ReinitFn>,
+) {
+    // The removal options of a replaced resource do not carry over to the new one.
+    crate::builder::clear_removal_options::<R>(world, None);
+    track_moved::<R>(world, location, reinit);
+}
+
+/// Records that `R` was moved back into the [`World`], keeping the removal options registered for it.
+pub(crate) fn track_moved<R: 'static>(
+    world: &mut World,
+    location: &'static Location<'static>,
+    reinit: Option<ReinitFn>,
+) {
+    let inserted_tick = world.read_change_tick();
+
+    if let Some(mut tracker) = world.get_resource_mut::<NonSendTracker>() {
+        tracker.entries.insert(
+            TypeId::of::<R>(),
+            TrackedNonSend {
+                type_id: TypeId::of::<R>(),
+                type_name: std::any::type_name::<R>(),
+                location,
+                inserted_at: Instant::now(),
+                inserted_tick,
+                remove: remove_and_untrack::<R>,
+                reinit,
+            },
+        );
+    }
+}
+
+/// A non-[`Send`] resource that was moved out of the [`World`] with [`take`], along with everything this crate records about it.
+pub(crate) struct MovedNonSend<R> {
+    resource: R,
+    tracked: Option<TrackedNonSend>,
+    removal_options: crate::builder::TakenRemovalOptions,
+    projections: Vec<crate::mirror::Projection>,
+}
+
+/// Moves `R` out of the [`World`] without dropping it or running its removal hooks, so it can be moved back with [`restore`].
+///
+/// While `R` is moved out, a replacement can be inserted and removed with [`track`] and [`remove`] without affecting it.
+pub(crate) fn take<R: 'static>(world: &mut World) -> Option<MovedNonSend<R>> {
+    let resource = world.remove_non_send_resource::<R>()?;
+
+    let tracked = world
+        .get_resource_mut::<NonSendTracker>()
+        .and_then(|mut tracker| tracker.entries.remove(&TypeId::of::<R>()));
+
+    Some(MovedNonSend {
+        resource,
+        tracked,
+        removal_options: crate::builder::take_removal_options::<R>(world, None),
+        projections: crate::mirror::take_projections::<R>(world),
+    })
+}
+
+/// Moves a resource taken with [`take`] back into the [`World`], replacing any value of `R`.
+pub(crate) fn restore<R: 'static>(world: &mut World, moved: MovedNonSend<R>) {
+    world.insert_non_send_resource(moved.resource);
+
+    if let (Some(tracked), Some(mut tracker)) =
+        (moved.tracked, world.get_resource_mut::<NonSendTracker>())
+    {
+        tracker.entries.insert(TypeId::of::<R>(), tracked);
+    }
+
+    crate::builder::restore_removal_options::<R>(world, None, moved.removal_options);
+    crate::mirror::restore_projections::<R>(world, moved.projections);
+}
+
+/// Records that `R` was removed, if tracking is enabled.
+pub(crate) fn untrack<R: 'static>(world: &mut World) {
+    if let Some(mut tracker) = world.get_resource_mut::<NonSendTracker>() {
+        tracker.entries.remove(&TypeId::of::<R>());
+    }
+}
+
+/// Removes `R` and records its removal, dropping it and running its removal hooks if it existed.
+///
+/// Copies of `R` mirrored into sub-apps are removed as well.
+///
+/// Returns false if `R` did not exist.
+pub(crate) fn remove<R: 'static>(world: &mut World) -> bool {
+    let removed = world.remove_non_send_resource::<R>();
+    untrack::<R>(world);
+    crate::mirror::removed::<R>(world);
+
+    let Some(resource) = removed else {
+        return false;
+    };
+
+    crate::builder::removed(world, None, resource);
+    true
+}
+
+/// Initializes `R` if it does not exist yet, tracking it if tracking is enabled.
+///
+/// The factory registered in `NonSendFactories` is used if there is one, otherwise `R` is created with [`FromWorld`].
+pub(crate) fn init<R: FromWorld + 'static>(
+    world: &mut World,
+    location: &'static Location<'static>,
+) {
+    if !world.contains_non_send::<R>() {
+        if !crate::factory::create::<R>(world) {
+            world.init_non_send_resource::<R>();
+        }
+
+        track::<R>(world, location, Some(init::<R>));
+    }
+}
+
+fn remove_and_untrack<R: 'static>(world: &mut World) {
+    remove::<R>(world);
+}
@@ -0,0 +1,103 @@
+use std::panic::Location;
+
+use bevy_app::{App, Last, Plugin};
+use bevy_ecs::{
+    system::Resource,
+    world::{Mut, World},
+};
+use bevy_utils::tracing::warn;
+
+/// A debugging [`Plugin`] that warns about non-[`Send`] resources that were expected, but never inserted.
+///
+/// Expectations are declared with [`AppExt::expect_non_send`](crate::AppExt::expect_non_send), which also adds this plugin. At the end of every frame, any expected resource that still does not exist after its deadline is reported once, naming the type and where the expectation was declared.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_command_non_send::{AppExt, CommandsExt};
+/// #
+/// struct AudioDevice(*const u8);
+///
+/// fn create_audio_device(mut commands: Commands) {
+///     commands.insert_non_send_resource(|| AudioDevice(std::ptr::null()));
+/// }
+///
+/// App::new()
+///     // Warns if `AudioDevice` does not exist by the end of the second frame.
+///     .expect_non_send::<AudioDevice>(2)
+///     .add_systems(Startup, create_audio_device)
+///     .run();
+/// ```
+pub struct NonSendWatchdogPlugin;
+
+impl Plugin for NonSendWatchdogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NonSendWatchdog>()
+            .add_systems(Last, check_expectations);
+    }
+}
+
+/// A [`Resource`] holding the expected non-[`Send`] resources that have not been inserted yet.
+///
+/// See [`NonSendWatchdogPlugin`].
+#[derive(Resource, Default)]
+pub struct NonSendWatchdog {
+    expectations: Vec<Expectation>,
+    frame: u32,
+}
+
+struct Expectation {
+    type_name: &'static str,
+    location: &'static Location<'static>,
+    within_frames: u32,
+    exists: fn(&World) -> bool,
+}
+
+impl NonSendWatchdog {
+    /// Expects `R` to be inserted within the given number of frames.
+    #[track_caller]
+    pub fn expect<R: 'static>(&mut self, within_frames: u32) {
+        self.expectations.push(Expectation {
+            type_name: std::any::type_name::<R>(),
+            location: Location::caller(),
+            within_frames,
+            exists: World::contains_non_send::<R>,
+        });
+    }
+
+    /// Returns the type names of the expected resources that have not been inserted or reported yet.
+    pub fn pending(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.expectations
+            .iter()
+            .map(|expectation| expectation.type_name)
+    }
+
+    /// Returns the number of frames that have finished since the watchdog was added.
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+}
+
+fn check_expectations(world: &mut World) {
+    world.resource_scope(|world, mut watchdog: Mut<NonSendWatchdog>| {
+        watchdog.frame += 1;
+
+        let frame = watchdog.frame;
+
+        watchdog.expectations.retain(|expectation| {
+            if (expectation.exists)(world) {
+                return false;
+            }
+
+            if frame < expectation.within_frames {
+                return true;
+            }
+
+            warn!(
+                "Non-send resource `{}` was expected within {} frame(s) at {}, but has not been inserted after {frame} frame(s).",
+                expectation.type_name, expectation.within_frames, expectation.location
+            );
+
+            false
+        });
+    });
+}
@@ -0,0 +1,107 @@
+use std::panic::Location;
+
+use bevy_ecs::{entity::Entity, query::With, system::Command, world::World};
+use bevy_window::{PrimaryWindow, RawHandleWrapper};
+use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
+
+use crate::{tracking, NonSendCommand, NonSendCommandKind};
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource created from the raw handles of a window.
+///
+/// `func` is executed on the main thread and receives the [`RawWindowHandle`] and [`RawDisplayHandle`] of `window`. This is meant for creating GL or Vulkan surfaces and native overlays.
+///
+/// # Panics
+///
+/// Panics if the window does not have a [`RawHandleWrapper`], such as when it has not been created by the windowing backend yet.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::window::{PrimaryWindow, RawHandleWrapper};
+/// # use bevy_command_non_send::insert_non_send_with_window_handle;
+/// # use raw_window_handle::{RawDisplayHandle, RawWindowHandle, WebDisplayHandle, WebWindowHandle};
+/// #
+/// struct Surface(*const u8, RawWindowHandle);
+///
+/// fn create_surface(mut commands: Commands, window: Query<Entity, With<PrimaryWindow>>) {
+///     commands.add(
+///         insert_non_send_with_window_handle(window.single(), |window, _display| {
+///             Surface(std::ptr::null(), window)
+///         })
+///     );
+/// }
+/// #
+/// # let mut app = App::new();
+/// #
+/// # app.world.spawn((
+/// #     PrimaryWindow,
+/// #     RawHandleWrapper {
+/// #         window_handle: RawWindowHandle::Web(WebWindowHandle::new(1)),
+/// #         display_handle: RawDisplayHandle::Web(WebDisplayHandle::new()),
+/// #     },
+/// # ));
+/// #
+/// # app.add_systems(Startup, (create_surface, check).chain()).run();
+/// #
+/// # fn check(surface: NonSend<Surface>) {
+/// #     assert_eq!(surface.1, RawWindowHandle::Web(WebWindowHandle::new(1)));
+/// # }
+/// ```
+#[track_caller]
+pub fn insert_non_send_with_window_handle<F, R>(window: Entity, func: F) -> impl Command
+where
+    F: FnOnce(RawWindowHandle, RawDisplayHandle) -> R + Send + 'static,
+    R: 'static,
+{
+    let location = Location::caller();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| {
+            let handles = world
+                .get::<RawHandleWrapper>(window)
+                .unwrap_or_else(|| panic!("Window {window:?} does not have a `RawHandleWrapper`."));
+
+            let resource = (func)(handles.window_handle, handles.display_handle);
+
+            world.insert_non_send_resource(resource);
+            tracking::track::<R>(world, location, None);
+        },
+    )
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource created from the raw handles of the [`PrimaryWindow`].
+///
+/// See [`insert_non_send_with_window_handle`].
+///
+/// # Panics
+///
+/// Panics if there is not exactly one primary window, or if it does not have a [`RawHandleWrapper`].
+#[track_caller]
+pub fn insert_non_send_with_primary_window_handle<F, R>(func: F) -> impl Command
+where
+    F: FnOnce(RawWindowHandle, RawDisplayHandle) -> R + Send + 'static,
+    R: 'static,
+{
+    let location = Location::caller();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| {
+            let window = world
+                .query_filtered::<Entity, With<PrimaryWindow>>()
+                .get_single(world)
+                .expect("There must be exactly one `PrimaryWindow`.");
+
+            let handles = world
+                .get::<RawHandleWrapper>(window)
+                .unwrap_or_else(|| panic!("Window {window:?} does not have a `RawHandleWrapper`."));
+
+            let resource = (func)(handles.window_handle, handles.display_handle);
+
+            world.insert_non_send_resource(resource);
+            tracking::track::<R>(world, location, None);
+        },
+    )
+}
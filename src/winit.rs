@@ -0,0 +1,113 @@
+use std::panic::Location;
+
+use bevy_ecs::{entity::Entity, system::Command, world::World};
+use bevy_winit::WinitWindows;
+use winit::window::Window;
+
+use crate::{tracking, NonSendCommand, NonSendCommandKind};
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource in the [`World`] using a closure with access to [`WinitWindows`].
+///
+/// This is useful for creating window-bound objects, such as IME handlers or native menus. Bevy does not expose the `winit` event loop outside of its runner, so [`WinitWindows`] is the only `winit` state available to commands.
+///
+/// # Panics
+///
+/// Panics if [`WinitWindows`] does not exist, which usually means `WinitPlugin` was not added.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy::winit::WinitWindows;
+/// # use bevy_command_non_send::insert_non_send_with_winit_windows;
+/// #
+/// struct NativeMenus(Vec<*const u8>);
+///
+/// fn create_menus(mut commands: Commands) {
+///     commands.add(
+///         insert_non_send_with_winit_windows(|windows: &WinitWindows| {
+///             NativeMenus(windows.windows.keys().map(|_| std::ptr::null()).collect())
+///         })
+///     );
+/// }
+/// #
+/// # App::new()
+/// #     .add_plugins(DefaultPlugins)
+/// #     .add_systems(Startup, create_menus)
+/// #     .run();
+/// ```
+#[track_caller]
+pub fn insert_non_send_with_winit_windows<F, R>(func: F) -> impl Command
+where
+    F: FnOnce(&WinitWindows) -> R + Send + 'static,
+    R: 'static,
+{
+    let location = Location::caller();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| {
+            let windows = world
+                .get_non_send_resource::<WinitWindows>()
+                .expect("`WinitWindows` must exist. Did you add `WinitPlugin`?");
+
+            let resource = (func)(windows);
+
+            world.insert_non_send_resource(resource);
+            tracking::track::<R>(world, location, None);
+        },
+    )
+}
+
+/// Creates a [`Command`] for inserting a non-[`Send`] resource in the [`World`] using a closure with access to the `winit` [`Window`] of `window`.
+///
+/// # Panics
+///
+/// Panics if [`WinitWindows`] does not exist, or if `window` does not have a `winit` window yet. Windows are created in `Update`, so they may not exist during `Startup`.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy::window::PrimaryWindow;
+/// # use bevy_command_non_send::insert_non_send_with_window;
+/// #
+/// struct ImeHandler(*const u8);
+///
+/// fn create_ime_handler(mut commands: Commands, window: Query<Entity, Added<PrimaryWindow>>) {
+///     if let Ok(window) = window.get_single() {
+///         commands.add(
+///             insert_non_send_with_window(window, |_window| {
+///                 ImeHandler(std::ptr::null())
+///             })
+///         );
+///     }
+/// }
+/// #
+/// # App::new()
+/// #     .add_plugins(DefaultPlugins)
+/// #     .add_systems(PostUpdate, create_ime_handler)
+/// #     .run();
+/// ```
+#[track_caller]
+pub fn insert_non_send_with_window<F, R>(window: Entity, func: F) -> impl Command
+where
+    F: FnOnce(&Window) -> R + Send + 'static,
+    R: 'static,
+{
+    let location = Location::caller();
+
+    NonSendCommand::new::<R>(
+        NonSendCommandKind::Insert,
+        location,
+        move |world: &mut World| {
+            let winit_window = world
+                .get_non_send_resource::<WinitWindows>()
+                .expect("`WinitWindows` must exist. Did you add `WinitPlugin`?")
+                .get_window(window)
+                .unwrap_or_else(|| panic!("Window {window:?} does not have a `winit` window."));
+
+            let resource = (func)(winit_window);
+
+            world.insert_non_send_resource(resource);
+            tracking::track::<R>(world, location, None);
+        },
+    )
+}